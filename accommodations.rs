@@ -0,0 +1,108 @@
+//! Accommodated students, guaranteed a seat above the capacity of the courses
+
+use ink_lang::codegen::{EmitEvent, StaticEnv};
+
+use crate::course_reg::{AccommodatedRegistration, CourseReg};
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn set_accommodation_inner(&mut self, student: AccountId, accommodated: bool) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_ACCOMMODATIONS)?;
+        self.require_role(Role::Owner)?;
+        if !self.is_school_member_inner(student) {
+            return Err(Error::NotSchoolMember);
+        }
+        if accommodated {
+            self.accommodations.insert(student, &());
+        } else {
+            self.accommodations.remove(student);
+        }
+        Ok(())
+    }
+
+    /// registers the accommodated caller to the course, past its capacity
+    /// by up to the school's accommodation buffer
+    pub(crate) fn register_with_accommodation_inner(&mut self, course_id: CourseId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_ACCOMMODATIONS)?;
+        let caller = Self::env().caller();
+        if !self.accommodations.contains(caller) {
+            return Err(Error::NoAccommodation);
+        }
+        if self.supervision_topics.contains(course_id) {
+            return Err(Error::ApplicationRequired);
+        }
+        if self.feature_enabled(FEATURE_REGISTRATION_QUESTIONS) && self.registration_questions.contains(course_id) {
+            return Err(Error::AnswerRequired);
+        }
+        self.mark_registration_phase(course_id);
+        let course = self.courses.get(course_id).ok_or(Error::NonexistentCourse)?;
+        let capacity = course.capacity.saturating_add(self.school_config.get_or_default().accommodation_buffer);
+        if course.enrolled_count >= capacity {
+            return Err(Error::CourseCapacityFull { capacity });
+        }
+        self.check_seat_taker(caller, &course, &[])?;
+        self.ensure_corequisites(caller, course_id, &[course_id])?;
+        self.move_registration(course_id, None, Some(caller))?;
+        self.record_activity(caller, course_id, ActivityKind::Added);
+        Self::env().emit_event(AccommodatedRegistration {
+            student: caller,
+            course_id,
+            over_capacity: course.enrolled_count >= course.capacity,
+        });
+        Ok(())
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Accommodated registration past the capacity test
+    #[ink::test]
+    fn accommodated_registration() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student1 = AccountId::from([0x1; 32]);
+        let student2 = AccountId::from([0x2; 32]);
+        let student3 = AccountId::from([0x3; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        let config = SchoolConfig { accommodation_buffer: MAX_ACCOMMODATION_BUFFER + 1, ..SchoolConfig::default() };
+        assert_eq!(course_reg.set_school_config(config, None), Err(Error::InvalidAccommodationBuffer));
+        let config = SchoolConfig { accommodation_buffer: 1, ..SchoolConfig::default() };
+        assert_eq!(course_reg.set_school_config(config, None), Ok(()));
+        for student in [student1, student2, student3] {
+            assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        }
+        assert_eq!(course_reg.create_course(course_id, 1, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.set_accommodation([0x9; 32].into(), true, None), Err(Error::NotSchoolMember));
+        assert_eq!(course_reg.set_accommodation(student2, true, None), Ok(()));
+        assert_eq!(course_reg.set_accommodation(student3, true, None), Ok(()));
+        assert!(course_reg.has_accommodation(student2));
+
+        set_next_caller(student1);
+        assert_eq!(course_reg.register_with_accommodation(course_id, None), Err(Error::NoAccommodation));
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        set_next_caller(student2);
+        assert_eq!(course_reg.register_to_course(course_id, None), Err(Error::CourseCapacityFull { capacity: 1 }));
+        let events = ink_env::test::recorded_events().count();
+        assert_eq!(course_reg.register_with_accommodation(course_id, None), Ok(()));
+        assert_eq!(ink_env::test::recorded_events().count(), events + 2);
+        assert_eq!(course_reg.get_course_info(course_id).unwrap().enrolled_count, 2);
+
+        // the buffer is used up
+        set_next_caller(student3);
+        assert_eq!(course_reg.register_with_accommodation(course_id, None), Err(Error::CourseCapacityFull { capacity: 2 }));
+    }
+}
@@ -0,0 +1,69 @@
+//! Per-term counters of the students' registration activity, for reporting
+
+use crate::course_reg::CourseReg;
+use crate::types::*;
+
+impl CourseReg {
+    /// counts the student's registration change in the course's term,
+    /// courses without a term aren't counted
+    pub(crate) fn record_activity(&mut self, student: AccountId, course_id: CourseId, kind: ActivityKind) {
+        let term_id = match self.course_terms.get(course_id) {
+            Some(term_id) => term_id,
+            None => return,
+        };
+        let mut activity = self.term_activity.get((student, term_id)).unwrap_or_default();
+        let counter = match kind {
+            ActivityKind::Added => &mut activity.added,
+            ActivityKind::Dropped => &mut activity.dropped,
+            ActivityKind::Swapped => &mut activity.swapped,
+        };
+        *counter = counter.saturating_add(1);
+        self.term_activity.insert((student, term_id), &activity);
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::course_reg::CourseReg;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Term activity counters test
+    #[ink::test]
+    fn term_activity() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student1 = AccountId::from([0x1; 32]);
+        let student2 = AccountId::from([0x2; 32]);
+        let course_id1 = CourseId(hash_keccak_256("test_course1".as_bytes()));
+        let course_id2 = CourseId(hash_keccak_256("test_course2".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_student(student1, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student2, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id1, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id2, 10, start_time, term_id, None, None), Ok(()));
+
+        set_next_caller(student1);
+        assert_eq!(course_reg.register_to_course(course_id1, None).map(|_| ()), Ok(()));
+        let proposal_id = course_reg.propose_swap(course_id1, None).unwrap();
+        set_next_caller(student2);
+        assert_eq!(course_reg.register_to_course(course_id2, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id2, None), Ok(()));
+        set_next_caller(student1);
+        assert_eq!(course_reg.accept_counter_offer(proposal_id, course_id2, student2, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.offer_seat_release(course_id2, None), Ok(()));
+        set_next_caller(student2);
+        assert_eq!(course_reg.claim_released_seat(course_id2, student1, None), Ok(()));
+
+        assert_eq!(course_reg.get_term_activity(student1, term_id),
+                   TermActivity { added: 1, dropped: 1, swapped: 1 });
+        assert_eq!(course_reg.get_term_activity(student2, term_id),
+                   TermActivity { added: 2, dropped: 0, swapped: 1 });
+        assert_eq!(course_reg.get_term_activity(student2, term_id + 1), TermActivity::default());
+    }
+}
@@ -0,0 +1,104 @@
+//! Course assignments and the anchored hashes of the students' submissions
+
+use ink_lang::codegen::StaticEnv;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn create_assignment_inner(&mut self,
+                                          course_id: CourseId,
+                                          deadline: Timestamp) -> Result<AssignmentId, Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_ASSIGNMENTS)?;
+        self.require_course_teacher(course_id, Self::env().caller())?;
+        if self.course_ends.contains(course_id) {
+            return Err(Error::CourseAlreadyEnded);
+        }
+        Self::validate_future_timestamp(deadline)?;
+        let assignment_id = self.assignment_counts.get(course_id).unwrap_or(0);
+        if assignment_id >= MAX_ASSIGNMENTS_PER_COURSE {
+            return Err(Error::TooManyAssignments);
+        }
+        let assignment_count = assignment_id.checked_add(1).ok_or(Error::ArithmeticOverflow)?;
+        self.assignments.insert((course_id, assignment_id), &Assignment { deadline });
+        self.assignment_counts.insert(course_id, &assignment_count);
+        Ok(assignment_id)
+    }
+
+    pub(crate) fn submit_assignment_inner(&mut self,
+                                          course_id: CourseId,
+                                          assignment_id: AssignmentId,
+                                          content_hash: [u8; 32]) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_ASSIGNMENTS)?;
+        let caller = Self::env().caller();
+        self.ensure_membership_active(caller)?;
+        let assignment = self.assignments.get((course_id, assignment_id))
+            .ok_or(Error::AssignmentNotFound)?;
+        if !self.enrollments.contains((course_id, caller)) {
+            return Err(Error::NotRegistered);
+        }
+        // the first submission is the evidence, it can't be replaced
+        if self.submissions.contains((course_id, assignment_id, caller)) {
+            return Err(Error::AlreadySubmitted);
+        }
+        let submitted_at = Self::now();
+        let submission = Submission {
+            content_hash,
+            submitted_at,
+            late: submitted_at > assignment.deadline,
+        };
+        self.submissions.insert((course_id, assignment_id, caller), &submission);
+        Ok(())
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Assignment submission test
+    #[ink::test]
+    fn assignment_submission() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student1 = AccountId::from([0x1; 32]);
+        let student2 = AccountId::from([0x2; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        let deadline = start_time + MILLIS_PER_DAY;
+        assert_eq!(course_reg.admit_as_student(student1, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student2, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_assignment(course_id, deadline, None), Ok(0));
+        set_next_caller(student1);
+        assert_eq!(course_reg.create_assignment(course_id, deadline, None), Err(Error::InsufficientPermissions));
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        set_next_caller(student2);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+
+        set_next_caller(student1);
+        assert_eq!(course_reg.submit_assignment(course_id, 1, [0x1; 32], None), Err(Error::AssignmentNotFound));
+        assert_eq!(course_reg.submit_assignment(course_id, 0, [0x1; 32], None), Ok(()));
+        assert_eq!(course_reg.submit_assignment(course_id, 0, [0x2; 32], None), Err(Error::AlreadySubmitted));
+
+        // late submissions are accepted and flagged
+        TestClock::set(deadline + 1);
+        set_next_caller(student2);
+        assert_eq!(course_reg.submit_assignment(course_id, 0, [0x3; 32], None), Ok(()));
+        assert_eq!(course_reg.get_submission(course_id, 0, student1).map(|submission| submission.late), Some(false));
+        assert_eq!(course_reg.get_submission(course_id, 0, student2),
+                   Some(Submission { content_hash: [0x3; 32], submitted_at: deadline + 1, late: true }));
+    }
+}
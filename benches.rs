@@ -0,0 +1,129 @@
+//! Worst-case storage benchmarks, run with `cargo test --features benches`
+
+use crate::clock::TestClock;
+use crate::course_reg::CourseReg;
+use crate::types::*;
+
+use ink_lang as ink;
+use scale::Encode;
+
+type Env = ink_env::DefaultEnvironment;
+
+/// The max size of a single storage value accepted by the contracts pallet
+const MAX_VALUE_SIZE: usize = 16 * 1024;
+
+fn set_next_caller(caller: AccountId) {
+    ink_env::test::set_caller::<Env>(caller);
+}
+
+fn account(i: u32) -> AccountId {
+    let mut bytes = [0xff; 32];
+    bytes[..4].copy_from_slice(&i.to_le_bytes());
+    AccountId::from(bytes)
+}
+
+fn course(i: u32) -> CourseId {
+    let mut bytes = [0xcc; 32];
+    bytes[..4].copy_from_slice(&i.to_le_bytes());
+    CourseId(bytes)
+}
+
+/// returns the storage reads and writes done by `f`
+fn measure_rw(f: impl FnOnce()) -> (usize, usize) {
+    let contract = ink_env::test::callee::<Env>();
+    let (reads, writes) = ink_env::test::get_contract_storage_rw::<Env>(&contract);
+    f();
+    let (reads_after, writes_after) = ink_env::test::get_contract_storage_rw::<Env>(&contract);
+    (reads_after - reads, writes_after - writes)
+}
+
+/// creates a school whose teacher, `account(1)`, has created
+/// `courses` courses of max capacity
+fn school(courses: u32) -> CourseReg {
+    let owner = account(0);
+    let teacher = account(1);
+    set_next_caller(owner);
+    TestClock::set(MIN_TIMESTAMP);
+    let mut course_reg = CourseReg::new(owner);
+    let term = Term {
+        start: MIN_TIMESTAMP,
+        end: MIN_TIMESTAMP + 2,
+        add_deadline: MIN_TIMESTAMP + 1,
+        drop_deadline: MIN_TIMESTAMP + 1,
+        swap_deadline: MIN_TIMESTAMP + 1,
+    };
+    let term_id = course_reg.create_term(term, None).unwrap();
+    course_reg.admit_as_teacher(teacher, None).unwrap();
+    course_reg.set_course_quota_override(teacher, Some(MAX_COURSES_PER_TEACHER), None).unwrap();
+    set_next_caller(teacher);
+    for i in 0..courses {
+        course_reg.create_course(course(i), MAX_COURSE_CAPACITY, MIN_TIMESTAMP + 1, term_id, None, None).unwrap();
+    }
+    course_reg
+}
+
+fn admit_student(course_reg: &mut CourseReg, student: AccountId) {
+    set_next_caller(account(0));
+    course_reg.admit_as_student(student, None).unwrap();
+    set_next_caller(student);
+}
+
+/// A course filled up to the max capacity
+#[ink::test]
+fn full_course() {
+    let mut course_reg = school(1);
+    let mut first = (0, 0);
+    let mut last = (0, 0);
+    for i in 0..MAX_COURSE_CAPACITY {
+        let student = account(i + 2);
+        admit_student(&mut course_reg, student);
+        let rw = measure_rw(|| { course_reg.register_to_course(course(0), None).unwrap(); });
+        // the first registration also marks the opening of the registration period
+        if i == 1 {
+            first = rw;
+        }
+        last = rw;
+    }
+    let roster_size = course_reg.rosters.get(course(0)).unwrap().encoded_size();
+    ink_env::debug_println!("register rw first: {:?} last: {:?}, roster size: {}", first, last, roster_size);
+    assert_eq!(first, last);
+    assert!(roster_size <= MAX_VALUE_SIZE);
+}
+
+/// An account holding the max number of registration tokens
+#[ink::test]
+fn max_registrations_per_account() {
+    let mut course_reg = school(MAX_REGISTRATIONS_PER_ACCOUNT);
+    let student = account(2);
+    admit_student(&mut course_reg, student);
+    for i in 0..MAX_REGISTRATIONS_PER_ACCOUNT {
+        course_reg.register_to_course(course(i), None).unwrap();
+    }
+    let index_size = course_reg.registration_index.get(student).unwrap().encoded_size();
+    let view_rw = measure_rw(|| { course_reg.get_own_registrations().unwrap(); });
+    ink_env::debug_println!("own registrations rw: {:?}, index size: {}", view_rw, index_size);
+    assert!(index_size <= MAX_VALUE_SIZE);
+}
+
+/// A swap proposal collecting the max number of counter offers
+#[ink::test]
+fn max_counter_offers() {
+    let mut course_reg = school(MAX_COUNTER_OFFERS + 1);
+    let proposer = account(2);
+    admit_student(&mut course_reg, proposer);
+    course_reg.register_to_course(course(0), None).unwrap();
+    let proposal_id = course_reg.propose_swap(course(0), None).unwrap();
+    for i in 1..=MAX_COUNTER_OFFERS {
+        let student = account(i + 2);
+        admit_student(&mut course_reg, student);
+        course_reg.register_to_course(course(i), None).unwrap();
+        course_reg.counter_swap_proposal(proposal_id, course(i), None).unwrap();
+    }
+    let proposal_size = course_reg.proposals.get(proposal_id).unwrap().encoded_size();
+    set_next_caller(proposer);
+    let accept_rw = measure_rw(|| {
+        course_reg.accept_counter_offer(proposal_id, course(MAX_COUNTER_OFFERS), account(MAX_COUNTER_OFFERS + 2), None).unwrap();
+    });
+    ink_env::debug_println!("accept rw: {:?}, proposal size: {}", accept_rw, proposal_size);
+    assert!(proposal_size <= MAX_VALUE_SIZE);
+}
@@ -0,0 +1,169 @@
+//! The one-time import of the records of a legacy student information system
+//!
+//! the imports are open from deployment until the owner finishes the
+//! bootstrap, they skip the time checks, the imported records are history
+
+use ink_prelude::vec::Vec;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    fn ensure_bootstrap(&self) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.require_role(Role::Owner)?;
+        if self.bootstrap_finished.get_or_default() {
+            return Err(Error::BootstrapFinished);
+        }
+        Ok(())
+    }
+
+    /// seals the imports for good
+    pub(crate) fn finish_bootstrap_inner(&mut self) -> Result<(), Error> {
+        self.ensure_bootstrap()?;
+        self.bootstrap_finished.set(&true);
+        Ok(())
+    }
+
+    /// admits the members of the batch, returns the result of each
+    pub(crate) fn import_members_inner(&mut self, batch: Vec<MemberImport>) -> Result<Vec<Result<(), Error>>, Error> {
+        self.ensure_bootstrap()?;
+        if batch.len() > MAX_BATCH_SIZE as usize {
+            return Err(Error::BatchTooLarge);
+        }
+        Ok(batch.into_iter()
+           .map(|member| if member.as_teacher {
+               self.admit_as_teacher_inner(member.account)
+           } else {
+               self.admit_as_student_inner(member.account)
+           })
+           .collect())
+    }
+
+    /// creates the courses of the batch, returns the result of each
+    pub(crate) fn import_courses_inner(&mut self, batch: Vec<CourseImport>) -> Result<Vec<Result<(), Error>>, Error> {
+        self.ensure_bootstrap()?;
+        if batch.len() > MAX_BATCH_SIZE as usize {
+            return Err(Error::BatchTooLarge);
+        }
+        Ok(batch.into_iter().map(|course| self.import_course(course)).collect())
+    }
+
+    /// creates the course of the teacher, its start may have passed
+    fn import_course(&mut self, import: CourseImport) -> Result<(), Error> {
+        if !self.is_teacher_inner(import.teacher) {
+            return Err(Error::NotSchoolMember);
+        }
+        let spec = CourseSpec {
+            course_id: import.course_id,
+            capacity: import.capacity,
+            start_date: import.start_date,
+            term_id: import.term_id,
+            room: None,
+        };
+        if let Some(violation) = self.course_spec_violations(&spec, true).into_iter().next() {
+            return Err(violation);
+        }
+        let active_count = self.active_course_counts.get(import.teacher).unwrap_or(0)
+            .checked_add(1).ok_or(Error::ArithmeticOverflow)?;
+        let course = Course {
+            teacher: import.teacher,
+            capacity: import.capacity,
+            course_id: import.course_id,
+            start_date: import.start_date,
+            enrolled_count: 0,
+        };
+        self.courses.insert(import.course_id, &course);
+        self.course_terms.insert(import.course_id, &import.term_id);
+        self.active_course_counts.insert(import.teacher, &active_count);
+        if import.start_date > self.latest_course_start.get_or_default() {
+            self.latest_course_start.set(&import.start_date);
+        }
+        Ok(())
+    }
+
+    /// registers the students of the batch, returns the result of each
+    pub(crate) fn import_registrations_inner(&mut self,
+                                             batch: Vec<RegistrationImport>) -> Result<Vec<Result<(), Error>>, Error> {
+        self.ensure_bootstrap()?;
+        if batch.len() > MAX_BATCH_SIZE as usize {
+            return Err(Error::BatchTooLarge);
+        }
+        Ok(batch.into_iter().map(|registration| self.import_registration(registration)).collect())
+    }
+
+    /// registers the student to the course, its registration period may have passed
+    fn import_registration(&mut self, import: RegistrationImport) -> Result<(), Error> {
+        let course = self.courses.get(import.course_id).ok_or(Error::NonexistentCourse)?;
+        if !self.is_school_member_inner(import.student) {
+            return Err(Error::NotSchoolMember);
+        }
+        if self.enrollments.contains((import.course_id, import.student)) {
+            return Err(Error::AlreadyRegistered);
+        }
+        if course.enrolled_count >= course.capacity {
+            return Err(Error::CourseCapacityFull { capacity: course.capacity });
+        }
+        let held = self.registration_index.get(import.student).unwrap_or_default().len();
+        if held >= MAX_REGISTRATIONS_PER_ACCOUNT as usize {
+            return Err(Error::TooManyRegistrations);
+        }
+        self.move_registration(import.course_id, None, Some(import.student))
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+    use ink_prelude::vec;
+
+    /// Legacy import and sealing test
+    #[ink::test]
+    fn bootstrap_import() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let teacher = AccountId::from([0x1; 32]);
+        let student = AccountId::from([0x2; 32]);
+        let course_id = CourseId(hash_keccak_256("legacy_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert!(course_reg.is_bootstrap_mode());
+        assert_eq!(course_reg.import_members(vec![
+            MemberImport { account: teacher, as_teacher: true },
+            MemberImport { account: student, as_teacher: false },
+        ], None), Ok(vec![Ok(()), Ok(())]));
+        assert!(course_reg.is_teacher(teacher));
+
+        // the course started already, it's imported as history
+        TestClock::set(start_time + MILLIS_PER_DAY);
+        let course = CourseImport { course_id, teacher, capacity: 1, start_date: start_time, term_id };
+        assert_eq!(course_reg.import_courses(vec![
+            course,
+            course,
+            CourseImport { teacher: student, ..course },
+        ], None), Ok(vec![Ok(()), Err(Error::CourseAlreadyExists), Err(Error::NotSchoolMember)]));
+        assert_eq!(course_reg.get_course_info(course_id).unwrap().teacher, teacher);
+        assert_eq!(course_reg.import_registrations(vec![
+            RegistrationImport { course_id, student },
+            RegistrationImport { course_id, student: teacher },
+        ], None), Ok(vec![Ok(()), Err(Error::CourseCapacityFull { capacity: 1 })]));
+        assert!(course_reg.is_registered(course_id, student));
+
+        set_next_caller(teacher);
+        assert_eq!(course_reg.finish_bootstrap(None), Err(Error::InsufficientPermissions));
+        set_next_caller(owner);
+        assert_eq!(course_reg.finish_bootstrap(None), Ok(()));
+        assert!(!course_reg.is_bootstrap_mode());
+        assert_eq!(course_reg.import_members(vec![], None), Err(Error::BootstrapFinished));
+        assert_eq!(course_reg.finish_bootstrap(None), Err(Error::BootstrapFinished));
+    }
+}
@@ -0,0 +1,72 @@
+//! The time source of the contract and timestamp validation
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+/// A source of the current time
+///
+/// all timestamps of the contract are milliseconds since the unix
+/// epoch, the unit of the block timestamp
+pub trait Clock {
+    /// returns the current time in milliseconds
+    fn now() -> Timestamp;
+}
+
+/// The clock of the chain, the timestamp of the current block
+pub struct BlockClock;
+
+impl Clock for BlockClock {
+    fn now() -> Timestamp {
+        ink_env::block_timestamp::<ink_env::DefaultEnvironment>()
+    }
+}
+
+#[cfg(any(test, feature = "fixtures"))]
+std::thread_local! {
+    static TEST_CLOCK_NOW: core::cell::Cell<Option<Timestamp>> = const { core::cell::Cell::new(None) };
+}
+
+/// A clock the off-chain tests can set, falls back to the block clock
+#[cfg(any(test, feature = "fixtures"))]
+pub struct TestClock;
+
+#[cfg(any(test, feature = "fixtures"))]
+impl TestClock {
+    /// sets the time returned by the clock of the current test thread
+    pub fn set(now: Timestamp) {
+        TEST_CLOCK_NOW.with(|clock| clock.set(Some(now)));
+    }
+}
+
+#[cfg(any(test, feature = "fixtures"))]
+impl Clock for TestClock {
+    fn now() -> Timestamp {
+        TEST_CLOCK_NOW.with(|clock| clock.get()).unwrap_or_else(BlockClock::now)
+    }
+}
+
+/// The clock the contract reads the time from
+#[cfg(not(any(test, feature = "fixtures")))]
+pub(crate) type ContractClock = BlockClock;
+#[cfg(any(test, feature = "fixtures"))]
+pub(crate) type ContractClock = TestClock;
+
+impl CourseReg {
+    /// returns the current time in milliseconds
+    pub(crate) fn now() -> Timestamp {
+        ContractClock::now()
+    }
+
+    /// checks that a timestamp given to a message is in milliseconds,
+    /// lies in the future and isn't unreasonably far ahead
+    pub(crate) fn validate_future_timestamp(timestamp: Timestamp) -> Result<(), Error> {
+        let now = Self::now();
+        if timestamp < MIN_TIMESTAMP
+            || timestamp <= now
+            || timestamp - now > MAX_SCHEDULE_AHEAD {
+            return Err(Error::InvalidTimestamp);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+//! Named cohorts of school members, the groups other modules target
+
+use ink_prelude::vec::Vec;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn create_cohort_inner(&mut self, name: Vec<u8>) -> Result<CohortId, Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_COHORTS)?;
+        self.require_role(Role::Owner)?;
+        if name.is_empty() || name.len() > MAX_COHORT_NAME_LENGTH as usize {
+            return Err(Error::InvalidCohortName);
+        }
+        let cohort_id = self.next_cohort_id.get_or_default();
+        self.next_cohort_id.set(&cohort_id.checked_add(1).ok_or(Error::ArithmeticOverflow)?);
+        self.cohorts.insert(cohort_id, &Cohort { name, member_count: 0 });
+        Ok(cohort_id)
+    }
+
+    pub(crate) fn add_to_cohort_inner(&mut self, cohort_id: CohortId, account: AccountId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_COHORTS)?;
+        self.require_role(Role::Owner)?;
+        let mut cohort = self.cohorts.get(cohort_id).ok_or(Error::NonexistentCohort)?;
+        if !self.is_school_member_inner(account) {
+            return Err(Error::NotSchoolMember);
+        }
+        if self.cohort_members.contains((cohort_id, account)) {
+            return Err(Error::AlreadyInCohort);
+        }
+        cohort.member_count = cohort.member_count.checked_add(1).ok_or(Error::ArithmeticOverflow)?;
+        self.cohort_members.insert((cohort_id, account), &());
+        self.cohorts.insert(cohort_id, &cohort);
+        Ok(())
+    }
+
+    pub(crate) fn remove_from_cohort_inner(&mut self, cohort_id: CohortId, account: AccountId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_COHORTS)?;
+        self.require_role(Role::Owner)?;
+        let mut cohort = self.cohorts.get(cohort_id).ok_or(Error::NonexistentCohort)?;
+        if !self.cohort_members.contains((cohort_id, account)) {
+            return Err(Error::NotInCohort);
+        }
+        cohort.member_count = cohort.member_count.checked_sub(1).ok_or(Error::ArithmeticOverflow)?;
+        self.cohort_members.remove((cohort_id, account));
+        self.cohorts.insert(cohort_id, &cohort);
+        Ok(())
+    }
+
+    /// returns true if the account is a member of the cohort
+    pub(crate) fn in_cohort(&self, cohort_id: CohortId, account: AccountId) -> bool {
+        self.cohort_members.contains((cohort_id, account))
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Cohort membership test
+    #[ink::test]
+    fn cohort_membership() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        assert_eq!(course_reg.create_cohort(ink_prelude::vec![], None), Err(Error::InvalidCohortName));
+        let cohort_id = course_reg.create_cohort("class of 2027".as_bytes().to_vec(), None).unwrap();
+        assert_eq!(course_reg.add_to_cohort(cohort_id, student, None), Err(Error::NotSchoolMember));
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert_eq!(course_reg.add_to_cohort(cohort_id + 1, student, None), Err(Error::NonexistentCohort));
+        assert_eq!(course_reg.add_to_cohort(cohort_id, student, None), Ok(()));
+        assert_eq!(course_reg.add_to_cohort(cohort_id, student, None), Err(Error::AlreadyInCohort));
+        assert!(course_reg.is_in_cohort(cohort_id, student));
+        assert_eq!(course_reg.get_cohort(cohort_id).unwrap().member_count, 1);
+
+        set_next_caller(student);
+        assert_eq!(course_reg.remove_from_cohort(cohort_id, student, None), Err(Error::InsufficientPermissions));
+        set_next_caller(owner);
+        assert_eq!(course_reg.remove_from_cohort(cohort_id, student, None), Ok(()));
+        assert_eq!(course_reg.remove_from_cohort(cohort_id, student, None), Err(Error::NotInCohort));
+        assert!(!course_reg.is_in_cohort(cohort_id, student));
+    }
+}
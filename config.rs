@@ -0,0 +1,73 @@
+//! The school-wide settings the owner can tune
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn set_school_config_inner(&mut self, config: SchoolConfig) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.require_role(Role::Owner)?;
+        if config.grade_points.is_empty()
+            || config.grade_points.len() > usize::from(Grade::MAX) + 1
+            || usize::from(config.passing_grade) >= config.grade_points.len() {
+            return Err(Error::InvalidGradeScale);
+        }
+        if !config.grade_labels.is_empty()
+            && (config.grade_labels.len() != config.grade_points.len()
+                || config.grade_labels.iter().any(|label| label.is_empty() || label.len() > MAX_GRADE_LABEL_LENGTH as usize)) {
+            return Err(Error::InvalidGradeScale);
+        }
+        if config.blackouts.len() > MAX_BLACKOUTS as usize
+            || config.blackouts.iter().any(|blackout| blackout.start >= blackout.end) {
+            return Err(Error::InvalidBlackout);
+        }
+        if config.course_quota == 0 || config.course_quota > MAX_COURSES_PER_TEACHER {
+            return Err(Error::InvalidCourseQuota);
+        }
+        if config.accommodation_buffer > MAX_ACCOMMODATION_BUFFER {
+            return Err(Error::InvalidAccommodationBuffer);
+        }
+        self.school_config.set(&config);
+        Ok(())
+    }
+
+    pub(crate) fn set_feature_flags_inner(&mut self, flags: FeatureFlags) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.require_role(Role::Owner)?;
+        self.feature_flags.set(&flags);
+        Ok(())
+    }
+
+    /// returns true unless the owner disabled the feature
+    pub(crate) fn feature_enabled(&self, feature: u64) -> bool {
+        self.feature_flags.get_or_default().disabled & feature == 0
+    }
+
+    /// fails if the owner disabled the feature
+    pub(crate) fn ensure_feature(&self, feature: u64) -> Result<(), Error> {
+        if !self.feature_enabled(feature) {
+            return Err(Error::FeatureDisabled { feature });
+        }
+        Ok(())
+    }
+
+    /// fails during the blackout windows, when swaps and seat transfers are frozen
+    pub(crate) fn ensure_no_blackout(&self) -> Result<(), Error> {
+        let now = Self::now();
+        match self.school_config.get_or_default().blackouts.iter().find(|blackout| blackout.start <= now && now < blackout.end) {
+            Some(blackout) => Err(Error::BlackoutActive { ends_at: blackout.end }),
+            None => Ok(()),
+        }
+    }
+
+    /// returns the grade points of a grade on the school's scale,
+    /// grades above the scale are worth the points of its top grade
+    pub(crate) fn grade_points(&self, grade: Grade) -> u32 {
+        let scale = self.school_config.get_or_default().grade_points;
+        scale.get(usize::from(grade))
+            .or_else(|| scale.last())
+            .copied()
+            .unwrap_or(0)
+    }
+}
@@ -0,0 +1,113 @@
+//! Corequisites, courses that have to be taken together
+
+use ink_lang::codegen::{EmitEvent, StaticEnv};
+use ink_prelude::vec::Vec;
+
+use crate::course_reg::{CorequisiteDropped, CourseReg};
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn set_corequisites_inner(&mut self,
+                                         course_id: CourseId,
+                                         corequisites: Vec<CourseId>) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_COREQUISITES)?;
+        self.require_course_teacher(course_id, Self::env().caller())?;
+        if self.course_ends.contains(course_id) {
+            return Err(Error::CourseAlreadyEnded);
+        }
+        if corequisites.len() > MAX_COREQUISITES as usize
+            || corequisites.contains(&course_id)
+            || corequisites.iter().any(|corequisite| !self.courses.contains(corequisite)) {
+            return Err(Error::InvalidCorequisites);
+        }
+        self.corequisites.insert(course_id, &corequisites);
+        Ok(())
+    }
+
+    /// fails if the student is neither registered to a corequisite
+    /// of the course nor registering to it in the `batch`
+    pub(crate) fn ensure_corequisites(&self,
+                                      student: AccountId,
+                                      course_id: CourseId,
+                                      batch: &[CourseId]) -> Result<(), Error> {
+        if !self.feature_enabled(FEATURE_COREQUISITES) {
+            return Ok(());
+        }
+        for corequisite in self.corequisites.get(course_id).unwrap_or_default() {
+            if !batch.contains(&corequisite) && !self.enrollments.contains((corequisite, student)) {
+                return Err(Error::MissingCorequisite { corequisite });
+            }
+        }
+        Ok(())
+    }
+
+    /// emits a warning for each of the student's courses that
+    /// has the course the student just left as corequisite
+    pub(crate) fn warn_dropped_corequisite(&self, student: AccountId, course_id: CourseId) {
+        for required_by in self.registration_index.get(student).unwrap_or_default() {
+            if self.corequisites.get(required_by).unwrap_or_default().contains(&course_id) {
+                Self::env().emit_event(CorequisiteDropped {
+                    student,
+                    course_id,
+                    required_by,
+                });
+            }
+        }
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+    use ink_prelude::vec;
+
+    /// Corequisite registration test
+    #[ink::test]
+    fn corequisite_registration() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let other = AccountId::from([0x2; 32]);
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        let lecture = CourseId(hash_keccak_256("lecture".as_bytes()));
+        let lab = CourseId(hash_keccak_256("lab".as_bytes()));
+        let seminar = CourseId(hash_keccak_256("seminar".as_bytes()));
+        for course_id in [lecture, lab, seminar] {
+            assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        }
+        assert_eq!(course_reg.set_corequisites(lab, vec![lab], None), Err(Error::InvalidCorequisites));
+        assert_eq!(course_reg.set_corequisites(lab, vec![lecture], None), Ok(()));
+        assert_eq!(course_reg.get_corequisites(lab), vec![lecture]);
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(other, None), Ok(()));
+
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(lab, None),
+                   Err(Error::MissingCorequisite { corequisite: lecture }));
+        assert_eq!(course_reg.register_to_courses(vec![lab, lecture, lab], None), Err(Error::AlreadyRegistered));
+        assert!(!course_reg.is_registered(lecture, student));
+        assert_eq!(course_reg.register_to_courses(vec![lab, lecture], None).map(|_| ()), Ok(()));
+        assert!(course_reg.is_registered(lab, student));
+
+        // swapping the lecture away warns about the lab
+        let proposal_id = course_reg.propose_swap(lecture, None).unwrap();
+        set_next_caller(other);
+        assert_eq!(course_reg.register_to_course(seminar, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, seminar, None), Ok(()));
+        set_next_caller(student);
+        let emitted = ink_env::test::recorded_events().count();
+        assert_eq!(course_reg.accept_counter_offer(proposal_id, seminar, other, None).map(|_| ()), Ok(()));
+        // the warning and the settlement
+        assert_eq!(ink_env::test::recorded_events().count(), emitted + 2);
+    }
+}
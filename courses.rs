@@ -0,0 +1,473 @@
+//! Creation and lookup of the university courses
+
+use ink_env::hash;
+use ink_lang::codegen::{EmitEvent, StaticEnv};
+use ink_prelude::vec::Vec;
+
+use crate::course_reg::{CourseReg, RegistrationOpening};
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn create_course_inner(&mut self,
+                                      course_id: CourseId,
+                                      course_cap: u32,
+                                      course_start:Timestamp,
+                                      term_id: TermId,
+                                      room: Option<RoomId>) -> Result<(),Error> {
+        self.ensure_version()?;
+        let caller = self.require_role(Role::Teacher)?.caller;
+        let spec = CourseSpec { course_id, capacity: course_cap, start_date: course_start, term_id, room };
+        if let Some(violation) = self.course_spec_violations(&spec, false).into_iter().next() {
+            return Err(violation);
+        }
+        let term = self.terms.get(term_id).ok_or(Error::NonexistentTerm)?;
+        let active_count = self.active_course_counts.get(caller).unwrap_or(0);
+        if active_count >= self.course_quota(caller) {
+            return Err(Error::CourseQuotaExceeded);
+        }
+        let active_count = active_count.checked_add(1).ok_or(Error::ArithmeticOverflow)?;
+        let course = Course {
+            teacher: caller,
+            capacity: course_cap,
+            course_id,
+            start_date: course_start,
+            enrolled_count: 0,
+        };
+        self.courses.insert(course_id, &course);
+        self.course_terms.insert(course_id, &term_id);
+        if let Some(room_id) = room {
+            self.place_in_room(course_id, room_id);
+        }
+        Self::env().emit_event(RegistrationOpening {
+            course_id,
+            opens_at: term.start,
+            closes_at: term.add_deadline,
+        });
+        self.active_course_counts.insert(caller, &active_count);
+        if course_start > self.latest_course_start.get_or_default() {
+            self.latest_course_start.set(&course_start);
+        }
+        Ok(())
+    }
+
+    /// returns every rule the course spec breaks, in the order they're checked,
+    /// an `imported` course may have started already
+    pub(crate) fn course_spec_violations(&self, spec: &CourseSpec, imported: bool) -> Vec<Error> {
+        let mut violations = Vec::new();
+        if self.courses.contains(spec.course_id) {
+            violations.push(Error::CourseAlreadyExists);
+        }
+        if !imported {
+            violations.extend(Self::validate_future_timestamp(spec.start_date).err());
+        }
+        match self.terms.get(spec.term_id) {
+            None => violations.push(Error::NonexistentTerm),
+            Some(term) if spec.start_date < term.start || spec.start_date >= term.end => {
+                violations.push(Error::CourseOutsideTerm)
+            }
+            Some(_) => (),
+        }
+        if spec.capacity == 0 {
+            violations.push(Error::InvalidCapacity);
+        }
+        if spec.capacity > MAX_COURSE_CAPACITY {
+            violations.push(Error::CourseCapacityTooLarge);
+        }
+        if let Some(room_id) = spec.room {
+            violations.extend(self.ensure_room_fits(room_id, spec.capacity).err());
+            violations.extend(self.ensure_room_has_space(room_id).err());
+        }
+        violations
+    }
+
+    /// creates every valid course of the batch
+    ///
+    /// returns the result of each spec, in the order of the batch
+    pub(crate) fn create_courses_inner(&mut self, batch: Vec<CourseSpec>) -> Result<Vec<Result<(), Error>>, Error> {
+        self.ensure_version()?;
+        self.require_role(Role::Teacher)?;
+        if batch.len() > MAX_BATCH_SIZE as usize {
+            return Err(Error::BatchTooLarge);
+        }
+        Ok(batch.into_iter()
+           .map(|spec| {
+               self.create_course_inner(spec.course_id, spec.capacity, spec.start_date, spec.term_id, spec.room)
+           })
+           .collect())
+    }
+
+    pub(crate) fn end_course_inner(&mut self, course_id: CourseId) -> Result<(), Error> {
+        self.end_course_by(course_id, Self::env().caller())
+    }
+
+    /// ends the course on behalf of `actor`, its teacher
+    pub(crate) fn end_course_by(&mut self, course_id: CourseId, actor: AccountId) -> Result<(), Error> {
+        self.ensure_version()?;
+        let course = self.require_course_teacher(course_id, actor)?;
+        if self.course_ends.contains(course_id) {
+            return Err(Error::CourseAlreadyEnded);
+        }
+        if course.start_date > Self::now() {
+            return Err(Error::CourseNotStarted);
+        }
+        self.record_course_end(&course);
+        Ok(())
+    }
+
+    /// ends the started course now and frees its place in the teacher's quota
+    pub(crate) fn record_course_end(&mut self, course: &Course) {
+        let active_count = self.active_course_counts.get(course.teacher).unwrap_or(0);
+        self.course_ends.insert(course.course_id, &Self::now());
+        self.active_course_counts.insert(course.teacher, &active_count.saturating_sub(1));
+    }
+
+    /// sets or, with None, removes the teacher's exception to the school's course quota
+    pub(crate) fn set_course_quota_override_inner(&mut self, teacher: AccountId, quota: Option<u32>) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.require_role(Role::Owner)?;
+        match quota {
+            Some(quota) if quota > MAX_COURSES_PER_TEACHER => return Err(Error::InvalidCourseQuota),
+            Some(quota) => self.course_quota_overrides.insert(teacher, &quota),
+            None => self.course_quota_overrides.remove(teacher),
+        }
+        Ok(())
+    }
+
+    /// returns the number of active courses the teacher can have
+    pub(crate) fn course_quota(&self, teacher: AccountId) -> u32 {
+        self.course_quota_overrides.get(teacher).unwrap_or(self.school_config.get_or_default().course_quota)
+    }
+
+    pub(crate) fn set_course_credits_inner(&mut self, course_id: CourseId, credits: u32) -> Result<(), Error> {
+        self.set_course_credits_by(course_id, credits, Self::env().caller())
+    }
+
+    /// sets the credits of the course on behalf of `actor`, its teacher
+    pub(crate) fn set_course_credits_by(&mut self,
+                                        course_id: CourseId,
+                                        credits: u32,
+                                        actor: AccountId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.require_course_teacher(course_id, actor)?;
+        if self.course_ends.contains(course_id) {
+            return Err(Error::CourseAlreadyEnded);
+        }
+        if credits > MAX_COURSE_CREDITS {
+            return Err(Error::InvalidCredits);
+        }
+        self.course_credits.insert(course_id, &credits);
+        Ok(())
+    }
+
+    pub(crate) fn set_course_uri_inner(&mut self,
+                                       course_id: CourseId,
+                                       uri: Vec<u8>,
+                                       content_hash: [u8; 32]) -> Result<(), Error> {
+        self.set_course_uri_by(course_id, uri, content_hash, Self::env().caller())
+    }
+
+    /// publishes the metadata of the course on behalf of `actor`, its teacher
+    pub(crate) fn set_course_uri_by(&mut self,
+                                    course_id: CourseId,
+                                    uri: Vec<u8>,
+                                    content_hash: [u8; 32],
+                                    actor: AccountId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_COURSE_METADATA)?;
+        self.require_course_teacher(course_id, actor)?;
+        if uri.is_empty() || uri.len() > MAX_URI_LENGTH as usize {
+            return Err(Error::InvalidUri);
+        }
+        self.course_metadata.insert(course_id, &CourseMetadata { uri, content_hash });
+        Ok(())
+    }
+
+    pub(crate) fn get_course_info_inner(&self, course_id: CourseId) -> Result<CourseView,Error> {
+        self.courses.get(course_id).map(|course| self.course_view(course)).ok_or(Error::NonexistentCourse)
+    }
+
+    /// returns teh Keccak256 hash of the input bytes
+    pub fn hash_keccak_256(input: &[u8]) -> [u8; 32] {
+        let mut output = <hash::Keccak256 as hash::HashOutput>::Type::default();
+        ink_env::hash_bytes::<hash::Keccak256>(input, &mut output);
+        output
+    }
+
+    /// returns the id of the named course in the term, the Keccak256 hash of
+    /// `COURSE_ID_DOMAIN`, the term and the name, so ids never collide with
+    /// other hashes of the contract
+    pub(crate) fn derive_course_id_inner(name: &[u8], term: [u8; 32]) -> CourseId {
+        let mut input = Vec::with_capacity(COURSE_ID_DOMAIN.len() + term.len() + name.len());
+        input.extend_from_slice(COURSE_ID_DOMAIN);
+        input.extend_from_slice(&term);
+        input.extend_from_slice(name);
+        CourseId(Self::hash_keccak_256(&input))
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Course creation test
+    #[ink::test]
+    fn course_creation() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let teacher = AccountId::from([0x1; 32]);
+        let course_name = "test_course".as_bytes();
+        let course_id = CourseId(hash_keccak_256(course_name));
+        let course_cap:u32 = 10;
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+
+        assert_eq!(course_reg.admit_as_teacher(teacher, None), Ok(()));
+        assert!(course_reg.is_school_member(teacher));
+        assert!(course_reg.is_teacher(teacher));
+        set_next_caller(teacher);
+        assert_eq!(course_reg.create_course(course_id, course_cap, start_time, term_id, None, None), Ok(()));
+
+        assert_ne!(course_reg.get_course_info(course_id), Err(Error::NonexistentCourse));
+    }
+
+    /// Collection bounds test
+    #[ink::test]
+    fn collection_bounds() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let teacher = AccountId::from([0x1; 32]);
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_teacher(teacher, None), Ok(()));
+        assert_eq!(course_reg.set_course_quota_override(teacher, Some(MAX_COURSES_PER_TEACHER), None), Ok(()));
+        set_next_caller(teacher);
+        assert_eq!(course_reg.create_course(CourseId([0x0; 32]), MAX_COURSE_CAPACITY + 1, start_time, term_id, None, None),
+                   Err(Error::CourseCapacityTooLarge));
+        for i in 0..MAX_COURSES_PER_TEACHER {
+            let course_id = CourseId(hash_keccak_256(&i.to_le_bytes()));
+            assert_eq!(course_reg.create_course(course_id, 1, start_time, term_id, None, None), Ok(()));
+        }
+        assert_eq!(course_reg.create_course(CourseId(hash_keccak_256(&0u32.to_le_bytes())), 1, start_time, term_id, None, None),
+                   Err(Error::CourseAlreadyExists));
+        assert_eq!(course_reg.create_course(CourseId([0x0; 32]), 1, start_time, term_id, None, None),
+                   Err(Error::CourseQuotaExceeded));
+
+        // counter offers on a single proposal are bounded
+        let course_id = CourseId(hash_keccak_256(&0u32.to_le_bytes()));
+        set_next_caller(owner);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        let proposal_id = course_reg.propose_swap(course_id, None).unwrap();
+        for i in 0..MAX_COUNTER_OFFERS {
+            let student = AccountId::from([i as u8 + 0x10; 32]);
+            let counter_id = CourseId(hash_keccak_256(&(i + 1).to_le_bytes()));
+            set_next_caller(owner);
+            assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+            set_next_caller(student);
+            assert_eq!(course_reg.register_to_course(counter_id, None).map(|_| ()), Ok(()));
+            assert_eq!(course_reg.counter_swap_proposal(proposal_id, counter_id, None), Ok(()));
+        }
+        let student = AccountId::from([0xff; 32]);
+        let counter_id = CourseId(hash_keccak_256(&(MAX_COUNTER_OFFERS + 1).to_le_bytes()));
+        set_next_caller(owner);
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(counter_id, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, counter_id, None), Err(Error::TooManyCounterOffers));
+    }
+
+    /// Invalid capacity and counter overflow test
+    #[ink::test]
+    fn checked_arithmetic() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.create_course(course_id, 0, start_time, term_id, None, None), Err(Error::InvalidCapacity));
+        assert_eq!(course_reg.create_course(course_id, 1, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+
+        // the token stays with the owner when the proposal id would overflow
+        course_reg.next_proposal_id.set(&ProposalId(u64::MAX));
+        assert_eq!(course_reg.propose_swap(course_id, None), Err(Error::ArithmeticOverflow));
+        assert_eq!(course_reg.get_own_registrations().unwrap().len(), 1);
+    }
+
+    /// Timestamp validation test
+    #[ink::test]
+    fn timestamp_validation() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        let now = start_time - 7 * MILLIS_PER_DAY;
+        // seconds instead of milliseconds
+        assert_eq!(course_reg.create_course(course_id, 1, start_time / 1000, term_id, None, None), Err(Error::InvalidTimestamp));
+        assert_eq!(course_reg.create_course(course_id, 1, now, term_id, None, None), Err(Error::InvalidTimestamp));
+        assert_eq!(course_reg.create_course(course_id, 1, now + MAX_SCHEDULE_AHEAD + 1, term_id, None, None), Err(Error::InvalidTimestamp));
+        assert_eq!(course_reg.create_course(course_id, 1, start_time, term_id, None, None), Ok(()));
+
+        TestClock::set(start_time);
+        assert_eq!(course_reg.register_to_course(course_id, None), Err(Error::RegistrationClosed { closed_at: start_time }));
+    }
+
+    /// Batch course creation test
+    #[ink::test]
+    fn batch_course_creation() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        let spec = CourseSpec {
+            course_id: CourseId(hash_keccak_256("test_course".as_bytes())),
+            capacity: 10,
+            start_date: start_time,
+            term_id,
+            room: None,
+        };
+        let other_id = CourseId(hash_keccak_256("other_course".as_bytes()));
+        let batch = ink_prelude::vec![
+            spec,
+            spec,
+            CourseSpec { course_id: other_id, capacity: 0, ..spec },
+            CourseSpec { course_id: other_id, start_date: 0, ..spec },
+            CourseSpec { course_id: other_id, ..spec },
+        ];
+        assert_eq!(course_reg.create_courses(batch.clone(), None), Ok(ink_prelude::vec![
+            Ok(()),
+            Err(Error::CourseAlreadyExists),
+            Err(Error::InvalidCapacity),
+            Err(Error::InvalidTimestamp),
+            Ok(()),
+        ]));
+        assert!(course_reg.get_course_info(other_id).is_ok());
+
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        set_next_caller(student);
+        assert_eq!(course_reg.create_courses(batch, None), Err(Error::InsufficientPermissions));
+    }
+
+    /// Course id derivation test
+    #[ink::test]
+    fn course_id_derivation() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let course_reg = CourseReg::new(owner);
+        let name = "test_course".as_bytes().to_vec();
+        let course_id = course_reg.derive_course_id(name.clone(), [0x1; 32]);
+        let mut input = COURSE_ID_DOMAIN.to_vec();
+        input.extend_from_slice(&[0x1; 32]);
+        input.extend_from_slice(&name);
+        assert_eq!(course_id, CourseId(hash_keccak_256(&input)));
+        assert_ne!(course_id, course_reg.derive_course_id(name.clone(), [0x2; 32]));
+        assert_ne!(course_id, CourseId(hash_keccak_256(&name)));
+    }
+
+    /// Course metadata URI test
+    #[ink::test]
+    fn course_metadata() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        let uri = "ipfs://syllabus".as_bytes().to_vec();
+        let content_hash = hash_keccak_256("syllabus".as_bytes());
+        let too_long = ink_prelude::vec![b'a'; MAX_URI_LENGTH as usize + 1];
+        assert_eq!(course_reg.set_course_uri(course_id, too_long, content_hash, None), Err(Error::InvalidUri));
+        assert_eq!(course_reg.set_course_uri(course_id, uri.clone(), content_hash, None), Ok(()));
+        assert_eq!(course_reg.get_course_metadata(course_id), Some(CourseMetadata { uri: uri.clone(), content_hash }));
+
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        set_next_caller(student);
+        assert_eq!(course_reg.set_course_uri(course_id, uri, content_hash, None), Err(Error::InsufficientPermissions));
+    }
+
+    /// Per-teacher active course quota test
+    #[ink::test]
+    fn course_quota() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let teacher = AccountId::from([0x1; 32]);
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_teacher(teacher, None), Ok(()));
+        let config = SchoolConfig { course_quota: 0, ..SchoolConfig::default() };
+        assert_eq!(course_reg.set_school_config(config, None), Err(Error::InvalidCourseQuota));
+        let config = SchoolConfig { course_quota: 2, ..SchoolConfig::default() };
+        assert_eq!(course_reg.set_school_config(config, None), Ok(()));
+        assert_eq!(course_reg.get_course_quota(teacher), 2);
+
+        set_next_caller(teacher);
+        assert_eq!(course_reg.create_course(CourseId([0x1; 32]), 1, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_course(CourseId([0x2; 32]), 1, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_course(CourseId([0x3; 32]), 1, start_time, term_id, None, None), Err(Error::CourseQuotaExceeded));
+        assert_eq!(course_reg.set_course_quota_override(teacher, Some(3), None), Err(Error::InsufficientPermissions));
+
+        // the owner grants an exception
+        set_next_caller(owner);
+        assert_eq!(course_reg.set_course_quota_override(teacher, Some(MAX_COURSES_PER_TEACHER + 1), None),
+                   Err(Error::InvalidCourseQuota));
+        assert_eq!(course_reg.set_course_quota_override(teacher, Some(3), None), Ok(()));
+        set_next_caller(teacher);
+        assert_eq!(course_reg.create_course(CourseId([0x3; 32]), 1, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.get_active_course_count(teacher), 3);
+        set_next_caller(owner);
+        assert_eq!(course_reg.set_course_quota_override(teacher, None, None), Ok(()));
+        assert_eq!(course_reg.get_course_quota(teacher), 2);
+
+        // ending a course frees its place
+        TestClock::set(start_time);
+        set_next_caller(teacher);
+        assert_eq!(course_reg.end_course(CourseId([0x1; 32]), None), Ok(()));
+        assert_eq!(course_reg.end_course(CourseId([0x2; 32]), None), Ok(()));
+        assert_eq!(course_reg.get_active_course_count(teacher), 1);
+        assert_eq!(course_reg.create_course(CourseId([0x4; 32]), 1, start_time + 1, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_course(CourseId([0x5; 32]), 1, start_time + 1, term_id, None, None), Err(Error::CourseQuotaExceeded));
+    }
+
+    /// Course creation after the earlier courses ended test
+    #[ink::test]
+    fn course_creation_after_ended_courses() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let teacher = AccountId::from([0x1; 32]);
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_teacher(teacher, None), Ok(()));
+        assert_eq!(course_reg.set_course_quota_override(teacher, Some(MAX_COURSES_PER_TEACHER), None), Ok(()));
+        set_next_caller(teacher);
+        for i in 0..MAX_COURSES_PER_TEACHER {
+            let course_id = CourseId(hash_keccak_256(&i.to_le_bytes()));
+            assert_eq!(course_reg.create_course(course_id, 1, start_time, term_id, None, None), Ok(()));
+        }
+
+        // only the active courses count, however many the teacher created before
+        TestClock::set(start_time);
+        for i in 0..MAX_COURSES_PER_TEACHER {
+            assert_eq!(course_reg.end_course(CourseId(hash_keccak_256(&i.to_le_bytes())), None), Ok(()));
+        }
+        assert_eq!(course_reg.create_course(CourseId([0x0; 32]), 1, start_time + 1, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.get_active_course_count(teacher), 1);
+    }
+}
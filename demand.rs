@@ -0,0 +1,137 @@
+//! The interest in the courses the seats couldn't meet, for planning more sections
+//!
+//! a message that fails is reverted with everything it wrote, so a
+//! registration rejected for capacity can't count itself, the student
+//! records the rejection with a call of its own instead, counted only
+//! while registering would fail for the capacity alone
+
+use ink_lang::codegen::{EmitEvent, StaticEnv};
+
+use crate::course_reg::{CourseReg, HighDemand};
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    /// counts the caller as turned away by the full course, once per student
+    ///
+    /// fails unless registering the caller right now would fail for the
+    /// capacity alone, with the error it would fail with otherwise
+    pub(crate) fn record_capacity_rejection_inner(&mut self, course_id: CourseId) -> Result<(), Error> {
+        self.ensure_version()?;
+        let caller = self.require_role(Role::Member)?.caller;
+        let course = self.courses.get(course_id).ok_or(Error::NonexistentCourse)?;
+        if course.enrolled_count < course.capacity {
+            return Err(Error::CourseNotFull);
+        }
+        self.check_seat_taker(caller, &course, &[])?;
+        if self.capacity_rejections.contains((course_id, caller)) {
+            return Err(Error::AlreadyRecorded);
+        }
+        self.capacity_rejections.insert((course_id, caller), &());
+        let mut metrics = self.demand_metrics.get(course_id).unwrap_or_default();
+        metrics.capacity_rejections = metrics.capacity_rejections.saturating_add(1);
+        self.demand_metrics.insert(course_id, &metrics);
+        if metrics.capacity_rejections == HIGH_DEMAND_THRESHOLD {
+            Self::env().emit_event(HighDemand {
+                course_id,
+                capacity: course.capacity,
+                capacity_rejections: metrics.capacity_rejections,
+            });
+        }
+        Ok(())
+    }
+
+    /// counts the course being added to a student's plan
+    pub(crate) fn record_plan_addition(&mut self, course_id: CourseId) {
+        let mut metrics = self.demand_metrics.get(course_id).unwrap_or_default();
+        metrics.plan_additions = metrics.plan_additions.saturating_add(1);
+        self.demand_metrics.insert(course_id, &metrics);
+    }
+
+    pub(crate) fn get_demand_metrics_inner(&self, course_id: CourseId) -> Result<DemandMetrics, Error> {
+        if !self.courses.contains(course_id) {
+            return Err(Error::NonexistentCourse);
+        }
+        Ok(self.demand_metrics.get(course_id).unwrap_or_default())
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Demand metrics of a full course test
+    #[ink::test]
+    fn demand_metrics() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.create_course(course_id, 1, start_time, term_id, None, None), Ok(()));
+        let students: ink_prelude::vec::Vec<AccountId> =
+            (1..=HIGH_DEMAND_THRESHOLD as u8 + 1).map(|i| AccountId::from([i; 32])).collect();
+        for student in &students {
+            assert_eq!(course_reg.admit_as_student(*student, None), Ok(()));
+        }
+
+        set_next_caller(students[0]);
+        assert_eq!(course_reg.add_to_plan(course_id, None), Ok(()));
+        assert_eq!(course_reg.record_capacity_rejection(course_id, None), Err(Error::CourseNotFull));
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.record_capacity_rejection(course_id, None), Err(Error::AlreadyRegistered));
+
+        let events = ink_env::test::recorded_events().count();
+        for student in &students[1..] {
+            set_next_caller(*student);
+            assert_eq!(course_reg.register_to_course(course_id, None), Err(Error::CourseCapacityFull { capacity: 1 }));
+            assert_eq!(course_reg.record_capacity_rejection(course_id, None), Ok(()));
+        }
+        // the threshold is crossed once
+        assert_eq!(ink_env::test::recorded_events().count(), events + 1);
+        assert_eq!(course_reg.record_capacity_rejection(course_id, None), Err(Error::AlreadyRecorded));
+        assert_eq!(course_reg.get_demand_metrics(course_id), Ok(DemandMetrics {
+            capacity_rejections: HIGH_DEMAND_THRESHOLD,
+            plan_additions: 1,
+        }));
+        assert_eq!(course_reg.get_demand_metrics(CourseId([0x1; 32])), Err(Error::NonexistentCourse));
+    }
+
+    /// Capacity rejections only counted while registering could succeed test
+    #[ink::test]
+    fn capacity_rejection_checks() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let late = AccountId::from([0x2; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.create_course(course_id, 1, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        for account in [student, late] {
+            assert_eq!(course_reg.admit_as_student(account, None), Ok(()));
+        }
+
+        // an outsider or a report after registration closed isn't counted
+        set_next_caller(AccountId::from([0x3; 32]));
+        assert_eq!(course_reg.record_capacity_rejection(course_id, None), Err(Error::InsufficientPermissions));
+        set_next_caller(student);
+        assert_eq!(course_reg.record_capacity_rejection(course_id, None), Ok(()));
+        assert_eq!(course_reg.record_capacity_rejection(course_id, None), Err(Error::AlreadyRecorded));
+        TestClock::set(start_time);
+        set_next_caller(late);
+        assert_eq!(course_reg.record_capacity_rejection(course_id, None),
+                   Err(Error::RegistrationClosed { closed_at: start_time }));
+        assert_eq!(course_reg.get_demand_metrics(course_id).unwrap().capacity_rejections, 1);
+    }
+}
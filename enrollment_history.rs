@@ -0,0 +1,112 @@
+//! The periods the students held their seats, kept for checks of past enrollments
+
+use ink_prelude::vec::Vec;
+
+use crate::course_reg::CourseReg;
+use crate::types::*;
+
+impl CourseReg {
+    /// opens a period of the student's seat in the course
+    pub(crate) fn record_enrollment_start(&mut self, course_id: CourseId, student: AccountId) {
+        let mut periods = self.enrollment_periods.get((course_id, student)).unwrap_or_default();
+        if periods.len() >= MAX_ENROLLMENT_PERIODS as usize {
+            periods.remove(0);
+        }
+        periods.push(EnrollmentPeriod { from: Self::now(), until: None });
+        self.enrollment_periods.insert((course_id, student), &periods);
+    }
+
+    /// closes the open period of the student's seat in the course
+    pub(crate) fn record_enrollment_end(&mut self, course_id: CourseId, student: AccountId) {
+        let mut periods: Vec<EnrollmentPeriod> = self.enrollment_periods.get((course_id, student)).unwrap_or_default();
+        if let Some(period) = periods.last_mut().filter(|period| period.until.is_none()) {
+            period.until = Some(Self::now());
+            self.enrollment_periods.insert((course_id, student), &periods);
+        }
+    }
+
+    /// returns true if the student held a seat in the course at `as_of`,
+    /// the seats of an ended course are held until it ended
+    ///
+    /// seats taken before the periods were recorded have none, they
+    /// count from the start of the course
+    pub(crate) fn verify_enrollment_inner(&self, student: AccountId, course_id: CourseId, as_of: Timestamp) -> bool {
+        if self.course_ends.get(course_id).is_some_and(|ended_at| ended_at <= as_of) {
+            return false;
+        }
+        let periods = self.enrollment_periods.get((course_id, student)).unwrap_or_default();
+        if periods.is_empty() {
+            return self.enrollments.contains((course_id, student))
+                && self.courses.get(course_id).is_some_and(|course| course.start_date <= as_of);
+        }
+        periods.iter().any(|period| period.from <= as_of && period.until.is_none_or(|until| as_of < until))
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::reader::CourseRegistryReader;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Past enrollment verification test
+    #[ink::test]
+    fn enrollment_history() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let other = AccountId::from([0x2; 32]);
+        let course_id1 = CourseId(hash_keccak_256("test_course1".as_bytes()));
+        let course_id2 = CourseId(hash_keccak_256("test_course2".as_bytes()));
+        let start_time = get_start_time();
+        let now = start_time - 7 * MILLIS_PER_DAY;
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.create_course(course_id1, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id2, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(other, None), Ok(()));
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(course_id1, None).map(|_| ()), Ok(()));
+        set_next_caller(other);
+        TestClock::set(now + MILLIS_PER_DAY);
+        assert_eq!(course_reg.register_to_course(course_id2, None).map(|_| ()), Ok(()));
+        assert!(!course_reg.verify_enrollment(student, course_id1, now - 1));
+        assert!(course_reg.verify_enrollment(student, course_id1, now));
+
+        // the swap ends the student's first period and starts the second
+        let swapped_at = now + 2 * MILLIS_PER_DAY;
+        TestClock::set(swapped_at);
+        set_next_caller(student);
+        let proposal_id = course_reg.propose_swap(course_id1, None).unwrap();
+        set_next_caller(other);
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id2, None), Ok(()));
+        set_next_caller(student);
+        assert_eq!(course_reg.accept_counter_offer(proposal_id, course_id2, other, None).map(|_| ()), Ok(()));
+        assert!(course_reg.verify_enrollment(student, course_id1, swapped_at - 1));
+        assert!(!course_reg.verify_enrollment(student, course_id1, swapped_at));
+        assert!(!course_reg.verify_enrollment(student, course_id2, swapped_at - 1));
+        assert!(course_reg.verify_enrollment(student, course_id2, swapped_at));
+        assert!(course_reg.verify_enrollment(other, course_id2, now + MILLIS_PER_DAY));
+        assert!(course_reg.verify_enrollment(other, course_id1, swapped_at));
+
+        // a seat from before the periods were recorded counts from the course start
+        course_reg.enrollment_periods.remove((course_id1, other));
+        assert!(!course_reg.verify_enrollment(other, course_id1, start_time - 1));
+        assert!(course_reg.verify_enrollment(other, course_id1, start_time));
+
+        // no seat is held once the course ended
+        let ended_at = start_time + MILLIS_PER_DAY;
+        TestClock::set(ended_at);
+        set_next_caller(owner);
+        assert_eq!(course_reg.end_course(course_id2, None), Ok(()));
+        assert!(course_reg.verify_enrollment(student, course_id2, ended_at - 1));
+        assert!(!course_reg.verify_enrollment(student, course_id2, ended_at));
+        assert!(course_reg.verify_enrollment(other, course_id2, now + MILLIS_PER_DAY));
+    }
+}
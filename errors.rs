@@ -0,0 +1,187 @@
+//! The errors returned by the contract messages
+
+use crate::types::*;
+
+/// The errors of the contract messages
+///
+/// every failure has its own variant, and variants carry the
+/// values a dApp needs to explain the failure to the user
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum Error {
+   InsufficientPermissions,
+   NonexistentCourse,
+   /// the course already has `capacity` registered students
+   CourseCapacityFull { capacity: u32 },
+   AlreadyRegistered,
+   NoRegistrations,
+   /// registration to the course closed when it started at `closed_at`
+   RegistrationClosed { closed_at: Timestamp },
+   NoSwappableRegistrations,
+   NoProposedSwap,
+   SwapProposalNotFound,
+   CounterOfferNotFound,
+   NotProposalOwner,
+   RegistrationNotOwned,
+   StorageVersionMismatch,
+   CodeUpgradeFailed,
+   NoMigrationPending,
+   CoursesStillScheduled,
+   CourseAlreadyExists,
+   CourseCapacityTooLarge,
+   TooManyRegistrations,
+   TooManyProposals,
+   TooManyCounterOffers,
+   ArithmeticOverflow,
+   InvalidCapacity,
+   InvalidTimestamp,
+   /// the guard's nonce isn't the `expected` next nonce of the caller
+   NonceMismatch { expected: u64 },
+   /// the state changed since the call was built, it is now at `current`
+   StaleState { current: u64 },
+   CourseNotStarted,
+   CourseNotEnded,
+   CourseAlreadyEnded,
+   NotRegistered,
+   GradeAlreadyRecorded,
+   NoGradeRecorded,
+   GradeAlreadyRevealed,
+   CommitmentMismatch,
+   TooManyGrades,
+   InvalidGradeScale,
+   InvalidCredits,
+   InvalidSessionWindow,
+   TooManySessions,
+   SessionNotFound,
+   SessionNotOpen,
+   AlreadyCheckedIn,
+   InvalidCheckInCode,
+   TooManyAssignments,
+   AssignmentNotFound,
+   AlreadySubmitted,
+   EvaluationClosed,
+   EvaluationAlreadySubmitted,
+   NoEvaluationSubmitted,
+   RevealNotOpen,
+   EvaluationAlreadyRevealed,
+   InvalidScore,
+   InvalidTerm,
+   NonexistentTerm,
+   CourseOutsideTerm,
+   /// swapping registrations of the course closed at `closed_at`
+   SwapsClosed { closed_at: Timestamp },
+   /// dropping the course closed at `closed_at`
+   DropsClosed { closed_at: Timestamp },
+   InvalidProgram,
+   NonexistentProgram,
+   NoProgramAssigned,
+   NotSchoolMember,
+   StudentSuspended,
+   /// the registration would take the credits in progress over the `cap` of the standing
+   CreditCapExceeded { cap: u32 },
+   InvalidCorequisites,
+   /// the course can only be taken together with the `corequisite`
+   MissingCorequisite { corequisite: CourseId },
+   NotSupervisionSlot,
+   ApplicationRequired,
+   AlreadyApplied,
+   ApplicationNotFound,
+   TooManyApplications,
+   InvalidExam,
+   TooManyExams,
+   ExamNotFound,
+   ExamSignupClosed,
+   ExamFull,
+   AlreadySignedUpForPeriod,
+   ResitNotAllowed,
+   NotSignedUp,
+   ExamNotHeld,
+   ExamResultAlreadyRecorded,
+   InvalidCohortName,
+   NonexistentCohort,
+   AlreadyInCohort,
+   NotInCohort,
+   BatchTooLarge,
+   /// registration to the course opens with its term at `opens_at`
+   RegistrationNotOpen { opens_at: Timestamp },
+   SeatReleaseAlreadyOffered,
+   SeatReleaseNotFound,
+   InvalidBlackout,
+   /// swaps and seat transfers are frozen until `ends_at`
+   BlackoutActive { ends_at: Timestamp },
+   InvalidUri,
+   /// the owner disabled the `feature` on this deployment
+   FeatureDisabled { feature: u64 },
+   InvariantChecksDisabled,
+   TooManySnapshots,
+   SnapshotNotFound,
+   CourseQuotaExceeded,
+   InvalidCourseQuota,
+   /// the membership of the account activates at `active_from`
+   MembershipNotActive { active_from: Timestamp },
+   TooManyPendingActivations,
+   /// the membership of the account lapsed at `expired_at`, it needs renewal
+   MembershipExpired { expired_at: Timestamp },
+   InvalidRoleTransition,
+   AlumniReadOnly,
+   NoNotificationKey,
+   InvalidNotice,
+   AlreadyPlanned,
+   NotPlanned,
+   TooManyPlannedCourses,
+   NoPlannedCourses,
+   NotOpenToPublic,
+   InvalidPublicEnrollment,
+   /// the public registration has to transfer exactly the `fee`
+   IncorrectFee { fee: Balance },
+   /// all `capacity` public seats of the course are taken
+   PublicCapacityFull { capacity: u32 },
+   /// the account can take its next public seat at `retry_at`
+   RateLimited { retry_at: Timestamp },
+   AnswerRequired,
+   NoRegistrationQuestion,
+   InvalidAccommodationBuffer,
+   NoAccommodation,
+   /// the `policy` of one of the courses forbids the swap
+   SwapPolicyViolation { policy: SwapPolicy },
+   BootstrapFinished,
+   RejectedByHook,
+   HookCallFailed,
+   AccountInUse,
+   RegistrationFrozen,
+   RegistrationNotFrozen,
+   /// the two accounts already settled `cap` swaps with each other this term
+   SwapPairLimitExceeded { cap: u32 },
+   CourseNotFull,
+   AlreadyRecorded,
+   StandingCounterNotFound,
+   TooManyStandingCounters,
+   InvalidOfficeHourWindow,
+   TooManyOfficeHours,
+   OfficeHourNotFound,
+   OfficeHourStarted,
+   OfficeHourBooked,
+   AlreadyBooked,
+   BookingNotFound,
+   NonexistentRoom,
+   /// the room seats only `capacity` students
+   RoomTooSmall { capacity: u32 },
+   TooManyCoursesInRoom,
+   GradeOutsideScale,
+   /// the course is in `state`, the transition starts from another one
+   InvalidCourseTransition { state: CourseState },
+   /// the term is over at `ends_at`
+   TermNotOver { ends_at: Timestamp },
+   AlreadyMember,
+   TeacherHasActiveCourses,
+   /// the registrations are collected as seat requests until `settles_at`
+   RegistrationCollecting { settles_at: Timestamp },
+   NotCollecting,
+   AlreadyRequested,
+   TooManySeatRequests,
+   MigrationNotPlanned,
+   EmptyMigrationPlan,
+   /// `remaining` of the planned entries of the step aren't converted yet
+   MigrationIncomplete { remaining: u32 },
+   MemberHoldsRegistrations,
+}
@@ -0,0 +1,161 @@
+//! Commit-reveal course evaluations by the registered students
+
+use ink_env::hash;
+use ink_lang::codegen::StaticEnv;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn submit_evaluation_inner(&mut self,
+                                          course_id: CourseId,
+                                          commitment: [u8; 32]) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_EVALUATIONS)?;
+        let caller = Self::env().caller();
+        let ended = self.course_ends.get(course_id).ok_or(Error::CourseNotEnded)?;
+        let reveal_opens = ended.checked_add(EVALUATION_COMMIT_PERIOD).ok_or(Error::ArithmeticOverflow)?;
+        if Self::now() >= reveal_opens {
+            return Err(Error::EvaluationClosed);
+        }
+        // only the students holding a registration evaluate
+        if !self.enrollments.contains((course_id, caller)) {
+            return Err(Error::NotRegistered);
+        }
+        if self.evaluations.contains((course_id, caller)) {
+            return Err(Error::EvaluationAlreadySubmitted);
+        }
+        self.evaluations.insert((course_id, caller), &Evaluation { commitment, revealed: false });
+        Ok(())
+    }
+
+    pub(crate) fn reveal_evaluation_inner(&mut self,
+                                          course_id: CourseId,
+                                          score: u8,
+                                          comment_hash: [u8; 32],
+                                          salt: [u8; 32]) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_EVALUATIONS)?;
+        let caller = Self::env().caller();
+        let ended = self.course_ends.get(course_id).ok_or(Error::CourseNotEnded)?;
+        let reveal_opens = ended.checked_add(EVALUATION_COMMIT_PERIOD).ok_or(Error::ArithmeticOverflow)?;
+        let reveal_closes = reveal_opens.checked_add(EVALUATION_REVEAL_PERIOD).ok_or(Error::ArithmeticOverflow)?;
+        let now = Self::now();
+        if now < reveal_opens || now >= reveal_closes {
+            return Err(Error::RevealNotOpen);
+        }
+        let mut evaluation = self.evaluations.get((course_id, caller)).ok_or(Error::NoEvaluationSubmitted)?;
+        if evaluation.revealed {
+            return Err(Error::EvaluationAlreadyRevealed);
+        }
+        if !(MIN_EVALUATION_SCORE..=MAX_EVALUATION_SCORE).contains(&score) {
+            return Err(Error::InvalidScore);
+        }
+        if Self::evaluation_commitment(score, comment_hash, salt) != evaluation.commitment {
+            return Err(Error::CommitmentMismatch);
+        }
+        let course = self.courses.get(course_id).ok_or(Error::NonexistentCourse)?;
+        let course_rating = self.course_ratings.get(course_id).unwrap_or_default().add(score)?;
+        let teacher_rating = self.teacher_ratings.get(course.teacher).unwrap_or_default().add(score)?;
+
+        evaluation.revealed = true;
+        self.evaluations.insert((course_id, caller), &evaluation);
+        self.course_ratings.insert(course_id, &course_rating);
+        self.teacher_ratings.insert(course.teacher, &teacher_rating);
+        Ok(())
+    }
+
+    /// returns the commitment to an evaluation, the Keccak256 hash
+    /// of the score followed by the comment hash and the salt
+    pub fn evaluation_commitment(score: u8, comment_hash: [u8; 32], salt: [u8; 32]) -> [u8; 32] {
+        let mut input = [0u8; 65];
+        input[0] = score;
+        input[1..33].copy_from_slice(&comment_hash);
+        input[33..].copy_from_slice(&salt);
+        let mut output = <hash::Keccak256 as hash::HashOutput>::Type::default();
+        ink_env::hash_bytes::<hash::Keccak256>(&input, &mut output);
+        output
+    }
+}
+
+impl RatingTotals {
+    /// counts one more revealed score
+    fn add(self, score: u8) -> Result<Self, Error> {
+        Ok(RatingTotals {
+            count: self.count.checked_add(1).ok_or(Error::ArithmeticOverflow)?,
+            score_total: self.score_total.checked_add(u32::from(score)).ok_or(Error::ArithmeticOverflow)?,
+        })
+    }
+
+    /// returns the count and the average score in hundredths
+    pub(crate) fn rating(&self) -> Rating {
+        let average = u64::from(self.score_total).saturating_mul(100)
+            .checked_div(u64::from(self.count))
+            .unwrap_or(0);
+        Rating {
+            count: self.count,
+            average: u32::try_from(average).unwrap_or(u32::MAX),
+        }
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Evaluation commit and reveal test
+    #[ink::test]
+    fn course_evaluation() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student1 = AccountId::from([0x1; 32]);
+        let student2 = AccountId::from([0x2; 32]);
+        let outsider = AccountId::from([0x3; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        let salt = [0x7; 32];
+        let scores = [(student1, 5), (student2, 2)];
+        for student in [student1, student2, outsider] {
+            assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        }
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        for (student, _) in scores {
+            set_next_caller(student);
+            assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+            assert_eq!(course_reg.submit_evaluation(course_id, [0x0; 32], None), Err(Error::CourseNotEnded));
+        }
+        TestClock::set(start_time);
+        set_next_caller(owner);
+        assert_eq!(course_reg.end_course(course_id, None), Ok(()));
+
+        for (student, score) in scores {
+            set_next_caller(student);
+            let commitment = CourseReg::evaluation_commitment(score, [0x1; 32], salt);
+            assert_eq!(course_reg.submit_evaluation(course_id, commitment, None), Ok(()));
+            assert_eq!(course_reg.reveal_evaluation(course_id, score, [0x1; 32], salt, None), Err(Error::RevealNotOpen));
+        }
+        set_next_caller(outsider);
+        assert_eq!(course_reg.submit_evaluation(course_id, [0x0; 32], None), Err(Error::NotRegistered));
+
+        TestClock::set(start_time + EVALUATION_COMMIT_PERIOD);
+        for (student, score) in scores {
+            set_next_caller(student);
+            assert_eq!(course_reg.reveal_evaluation(course_id, score, [0x2; 32], salt, None), Err(Error::CommitmentMismatch));
+            assert_eq!(course_reg.reveal_evaluation(course_id, score, [0x1; 32], salt, None), Ok(()));
+            assert_eq!(course_reg.reveal_evaluation(course_id, score, [0x1; 32], salt, None),
+                       Err(Error::EvaluationAlreadyRevealed));
+        }
+        assert_eq!(course_reg.get_course_rating(course_id), Rating { count: 2, average: 350 });
+        assert_eq!(course_reg.get_teacher_rating(owner), Rating { count: 2, average: 350 });
+    }
+}
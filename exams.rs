@@ -0,0 +1,155 @@
+//! Exam sittings of the courses and the resits of failed attempts
+
+use ink_lang::codegen::StaticEnv;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn create_exam_inner(&mut self, course_id: CourseId, exam: Exam) -> Result<ExamId, Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_EXAMS)?;
+        self.require_course_teacher(course_id, Self::env().caller())?;
+        if self.course_ends.contains(course_id) {
+            return Err(Error::CourseAlreadyEnded);
+        }
+        Self::validate_future_timestamp(exam.date)?;
+        if exam.capacity == 0 || exam.capacity > MAX_COURSE_CAPACITY
+            || exam.signup_opens >= exam.signup_closes
+            || exam.signup_closes > exam.date {
+            return Err(Error::InvalidExam);
+        }
+        let exam_id = self.exam_counts.get(course_id).unwrap_or(0);
+        if exam_id >= MAX_EXAMS_PER_COURSE {
+            return Err(Error::TooManyExams);
+        }
+        let exam_count = exam_id.checked_add(1).ok_or(Error::ArithmeticOverflow)?;
+        self.exams.insert((course_id, exam_id), &exam);
+        self.exam_counts.insert(course_id, &exam_count);
+        Ok(exam_id)
+    }
+
+    pub(crate) fn sign_up_for_exam_inner(&mut self, course_id: CourseId, exam_id: ExamId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_EXAMS)?;
+        let caller = Self::env().caller();
+        self.ensure_membership_active(caller)?;
+        let exam = self.exams.get((course_id, exam_id)).ok_or(Error::ExamNotFound)?;
+        if !self.enrollments.contains((course_id, caller)) {
+            return Err(Error::NotRegistered);
+        }
+        let now = Self::now();
+        if now < exam.signup_opens || now >= exam.signup_closes {
+            return Err(Error::ExamSignupClosed);
+        }
+        if self.exam_sittings.contains((course_id, exam.period, caller)) {
+            return Err(Error::AlreadySignedUpForPeriod);
+        }
+        if exam.resit && !self.failed_exams.contains((course_id, caller)) {
+            return Err(Error::ResitNotAllowed);
+        }
+        let seated = self.exam_seats.get((course_id, exam_id)).unwrap_or(0);
+        if seated >= exam.capacity {
+            return Err(Error::ExamFull);
+        }
+        let seated = seated.checked_add(1).ok_or(Error::ArithmeticOverflow)?;
+        self.exam_seats.insert((course_id, exam_id), &seated);
+        self.exam_sittings.insert((course_id, exam.period, caller), &exam_id);
+        Ok(())
+    }
+
+    /// marks the student's attempt, a failed first attempt opens the resits
+    pub(crate) fn record_exam_result_inner(&mut self,
+                                           course_id: CourseId,
+                                           exam_id: ExamId,
+                                           student: AccountId,
+                                           passed: bool) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_EXAMS)?;
+        self.require_course_teacher(course_id, Self::env().caller())?;
+        let exam = self.exams.get((course_id, exam_id)).ok_or(Error::ExamNotFound)?;
+        if self.exam_sittings.get((course_id, exam.period, student)) != Some(exam_id) {
+            return Err(Error::NotSignedUp);
+        }
+        if exam.date > Self::now() {
+            return Err(Error::ExamNotHeld);
+        }
+        if self.exam_results.contains((course_id, exam_id, student)) {
+            return Err(Error::ExamResultAlreadyRecorded);
+        }
+        self.exam_results.insert((course_id, exam_id, student), &passed);
+        if !passed && !exam.resit {
+            self.failed_exams.insert((course_id, student), &());
+        }
+        Ok(())
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Exam sitting and resit test
+    #[ink::test]
+    fn exam_sittings() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let other = AccountId::from([0x2; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(other, None), Ok(()));
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+
+        let day = MILLIS_PER_DAY;
+        let exam = Exam {
+            date: start_time + 30 * day,
+            capacity: 1,
+            location_hash: [0x1; 32],
+            signup_opens: start_time,
+            signup_closes: start_time + 20 * day,
+            period: 0,
+            resit: false,
+        };
+        let resit = Exam { date: start_time + 50 * day, signup_closes: start_time + 40 * day, resit: true, ..exam };
+        set_next_caller(owner);
+        assert_eq!(course_reg.create_exam(course_id, Exam { signup_closes: exam.date + 1, ..exam }, None),
+                   Err(Error::InvalidExam));
+        assert_eq!(course_reg.create_exam(course_id, exam, None), Ok(0));
+        assert_eq!(course_reg.create_exam(course_id, Exam { period: 1, ..exam }, None), Ok(1));
+        assert_eq!(course_reg.create_exam(course_id, Exam { period: 2, ..resit }, None), Ok(2));
+
+        set_next_caller(student);
+        assert_eq!(course_reg.sign_up_for_exam(course_id, 0, None), Err(Error::ExamSignupClosed));
+        TestClock::set(start_time);
+        assert_eq!(course_reg.sign_up_for_exam(course_id, 2, None), Err(Error::ResitNotAllowed));
+        assert_eq!(course_reg.sign_up_for_exam(course_id, 0, None), Ok(()));
+        assert_eq!(course_reg.sign_up_for_exam(course_id, 0, None), Err(Error::AlreadySignedUpForPeriod));
+        set_next_caller(other);
+        assert_eq!(course_reg.sign_up_for_exam(course_id, 0, None), Err(Error::NotRegistered));
+
+        // failing the first attempt opens the resit
+        set_next_caller(owner);
+        assert_eq!(course_reg.record_exam_result(course_id, 0, student, false, None), Err(Error::ExamNotHeld));
+        TestClock::set(exam.date);
+        assert_eq!(course_reg.record_exam_result(course_id, 0, student, false, None), Ok(()));
+        assert_eq!(course_reg.record_exam_result(course_id, 0, student, true, None),
+                   Err(Error::ExamResultAlreadyRecorded));
+        set_next_caller(student);
+        assert_eq!(course_reg.sign_up_for_exam(course_id, 2, None), Ok(()));
+        assert_eq!(course_reg.get_exam_sitting(course_id, 2, student), Some(2));
+    }
+}
@@ -0,0 +1,103 @@
+//! Chunked SCALE exports of the storage, for verifiable off-chain backups
+//!
+//! only the sections keyed by sequential ids can be walked, the courses,
+//! memberships and registrations live in mappings keyed by hashes and
+//! accounts, a backup reads them by the keys it learns from the events
+
+use ink_env::hash;
+use ink_prelude::vec::Vec;
+use scale::Encode;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    /// returns the entries of the section from the id `cursor` on, at most
+    /// `EXPORT_CHUNK_SIZE` of them, encoded as a list of `(id, entry)` pairs
+    pub(crate) fn export_state_inner(&self, section: StateSection, cursor: u64) -> Result<StateChunk, Error> {
+        self.require_role(Role::Owner)?;
+        let (data, next_cursor) = match section {
+            StateSection::Config => {
+                let config = (self.owner, self.storage_version(), self.state_version.get_or_default(), self.school_config.get_or_default(), self.feature_flags.get_or_default());
+                (config.encode(), None)
+            }
+            StateSection::Terms => Self::export_entries(cursor, u64::from(self.next_term_id.get_or_default()), |id| {
+                self.terms.get(TermId::try_from(id).ok()?)
+            }),
+            StateSection::Programs => Self::export_entries(cursor, u64::from(self.next_program_id.get_or_default()), |id| {
+                self.programs.get(ProgramId::try_from(id).ok()?)
+            }),
+            StateSection::Cohorts => Self::export_entries(cursor, u64::from(self.next_cohort_id.get_or_default()), |id| {
+                self.cohorts.get(CohortId::try_from(id).ok()?)
+            }),
+            StateSection::Proposals => Self::export_entries(cursor, self.next_proposal_id.get_or_default().0, |id| {
+                self.proposals.get(ProposalId(id))
+            }),
+        };
+        Ok(StateChunk { section, data, next_cursor, manifest_hash: self.manifest_hash() })
+    }
+
+    /// encodes the existing entries of the ids from `cursor` up to `end`
+    fn export_entries<T: Encode>(cursor: u64, end: u64, entry: impl Fn(u64) -> Option<T>) -> (Vec<u8>, Option<u64>) {
+        let chunk_end = cursor.saturating_add(u64::from(EXPORT_CHUNK_SIZE)).min(end);
+        let entries: Vec<(u64, T)> = (cursor..chunk_end)
+            .filter_map(|id| entry(id).map(|entry| (id, entry)))
+            .collect();
+        let next_cursor = if chunk_end < end { Some(chunk_end) } else { None };
+        (entries.encode(), next_cursor)
+    }
+
+    /// returns the Keccak256 hash of the state version and the id counters,
+    /// every chunk of a consistent backup carries the same manifest hash
+    fn manifest_hash(&self) -> [u8; 32] {
+        let manifest = (self.state_version.get_or_default(),
+                        self.next_term_id.get_or_default(),
+                        self.next_program_id.get_or_default(),
+                        self.next_cohort_id.get_or_default(),
+                        self.next_proposal_id.get_or_default());
+        let mut output = <hash::Keccak256 as hash::HashOutput>::Type::default();
+        ink_env::hash_bytes::<hash::Keccak256>(&manifest.encode(), &mut output);
+        output
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+    use ink_prelude::vec::Vec;
+    use scale::Decode;
+
+    /// Chunked state export test
+    #[ink::test]
+    fn state_export() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let start_time = get_start_time();
+        for offset in 0..(EXPORT_CHUNK_SIZE + 1) {
+            create_term(&mut course_reg, start_time + u64::from(offset));
+        }
+        let first = course_reg.export_state(StateSection::Terms, 0).unwrap();
+        let terms = <Vec<(u64, Term)>>::decode(&mut &first.data[..]).unwrap();
+        assert_eq!(terms.len(), EXPORT_CHUNK_SIZE as usize);
+        assert_eq!(course_reg.get_term(0), Some(terms[0].1));
+        assert_eq!(first.next_cursor, Some(u64::from(EXPORT_CHUNK_SIZE)));
+        let last = course_reg.export_state(StateSection::Terms, first.next_cursor.unwrap()).unwrap();
+        assert_eq!(<Vec<(u64, Term)>>::decode(&mut &last.data[..]).unwrap().len(), 1);
+        assert_eq!(last.next_cursor, None);
+        assert_eq!(first.manifest_hash, last.manifest_hash);
+
+        // a change between chunks shows in the manifest
+        create_term(&mut course_reg, start_time);
+        assert_ne!(course_reg.export_state(StateSection::Proposals, 0).unwrap().manifest_hash, first.manifest_hash);
+        set_next_caller(AccountId::from([0x1; 32]));
+        assert_eq!(course_reg.export_state(StateSection::Config, 0), Err(Error::InsufficientPermissions));
+    }
+}
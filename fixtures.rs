@@ -0,0 +1,172 @@
+//! Deterministic scenarios for the off-chain tests of the contract and of its integrators
+//!
+//! compiled for the unit tests and with the `fixtures` feature, the
+//! builders run in the off-chain test environment, inside `#[ink::test]`
+//! or `ink_env::test::run_test`, and panic if a scenario breaks a limit
+
+use ink_prelude::vec::Vec;
+
+use crate::clock::TestClock;
+use crate::course_reg::CourseReg;
+use crate::types::*;
+
+/// The time the scenarios are built at, the clock is set to it
+pub const FIXTURE_NOW: Timestamp = MIN_TIMESTAMP;
+
+/// Returns the `i`th account of the scenarios, the owner is account 0
+pub fn account(i: u32) -> AccountId {
+    let mut bytes = [0xfa; 32];
+    bytes[..4].copy_from_slice(&i.to_le_bytes());
+    AccountId::from(bytes)
+}
+
+/// Returns the id of the `i`th course of the scenarios
+pub fn course_id(i: u32) -> CourseId {
+    let mut bytes = [0xfc; 32];
+    bytes[..4].copy_from_slice(&i.to_le_bytes());
+    CourseId(bytes)
+}
+
+/// A school built by a `SchoolBuilder`
+pub struct School {
+    /// the contract, the caller is left set to the owner
+    pub course_reg: CourseReg,
+    /// the owner of the contract
+    pub owner: AccountId,
+    /// the admitted teachers
+    pub teachers: Vec<AccountId>,
+    /// the admitted students
+    pub students: Vec<AccountId>,
+    /// the created courses, taught by the teachers in turn
+    pub courses: Vec<CourseId>,
+    /// the term of the courses
+    pub term_id: TermId,
+    /// the start of the courses, registration and swaps are open until then
+    pub course_start: Timestamp,
+}
+
+/// Builds a school with teachers, courses and students, the first
+/// `registered` students are registered to every course
+#[derive(Clone, Copy, Debug)]
+pub struct SchoolBuilder {
+    teachers: u32,
+    courses: u32,
+    students: u32,
+    registered: u32,
+    capacity: u32,
+}
+
+impl Default for SchoolBuilder {
+    /// a school of a single teacher, without courses and students
+    fn default() -> Self {
+        SchoolBuilder { teachers: 1, courses: 0, students: 0, registered: 0, capacity: 10 }
+    }
+}
+
+impl SchoolBuilder {
+    /// a school of a single teacher, without courses and students
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// sets the number of teachers, the owner teaches if it's 0
+    pub fn teachers(mut self, teachers: u32) -> Self {
+        self.teachers = teachers;
+        self
+    }
+
+    /// sets the number of courses
+    pub fn courses(mut self, courses: u32) -> Self {
+        self.courses = courses;
+        self
+    }
+
+    /// sets the number of students
+    pub fn students(mut self, students: u32) -> Self {
+        self.students = students;
+        self
+    }
+
+    /// sets the number of students registered to every course
+    pub fn registered(mut self, registered: u32) -> Self {
+        self.registered = registered;
+        self
+    }
+
+    /// sets the capacity of the courses
+    pub fn capacity(mut self, capacity: u32) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// builds the school in the current test environment
+    pub fn build(self) -> School {
+        assert!(self.registered <= self.students, "more registered students than students");
+        TestClock::set(FIXTURE_NOW);
+        let course_start = FIXTURE_NOW + 7 * MILLIS_PER_DAY;
+        let owner = account(0);
+        ink_env::test::set_caller::<ink_env::DefaultEnvironment>(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let term = Term {
+            start: FIXTURE_NOW + 1,
+            end: course_start + 100 * MILLIS_PER_DAY,
+            add_deadline: course_start,
+            drop_deadline: course_start,
+            swap_deadline: course_start,
+        };
+        let term_id = course_reg.create_term(term, None).expect("the fixture term is valid");
+        TestClock::set(term.start);
+
+        let teachers: Vec<AccountId> = (1..=self.teachers).map(account).collect();
+        let students: Vec<AccountId> = (1..=self.students).map(|i| account(self.teachers + i)).collect();
+        for teacher in teachers.iter().copied().chain(core::iter::once(owner)) {
+            if teacher != owner {
+                course_reg.admit_as_teacher(teacher, None).expect("the owner admits");
+            }
+            course_reg.set_course_quota_override(teacher, Some(MAX_COURSES_PER_TEACHER), None)
+                .expect("the quota is valid");
+        }
+        for student in &students {
+            course_reg.admit_as_student(*student, None).expect("the owner admits");
+        }
+        let courses: Vec<CourseId> = (0..self.courses).map(course_id).collect();
+        for (i, course) in courses.iter().enumerate() {
+            let teacher = teachers.get(i % teachers.len().max(1)).copied().unwrap_or(owner);
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(teacher);
+            course_reg.create_course(*course, self.capacity, course_start, term_id, None, None)
+                .expect("the fixture course is within the limits");
+        }
+        for student in &students[..self.registered as usize] {
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(*student);
+            for course in &courses {
+                course_reg.register_to_course(*course, None).expect("the fixture registration is within the limits");
+            }
+        }
+        ink_env::test::set_caller::<ink_env::DefaultEnvironment>(owner);
+        School { course_reg, owner, teachers, students, courses, term_id, course_start }
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+
+    use ink_lang as ink;
+
+    /// Fixture school scenario test
+    #[ink::test]
+    fn school_fixture() {
+        let school = SchoolBuilder::new().teachers(2).courses(3).students(4).registered(3).capacity(5).build();
+        assert_eq!(school.teachers.len(), 2);
+        assert!(school.teachers.iter().all(|teacher| school.course_reg.is_teacher(*teacher)));
+        assert_eq!(school.course_reg.get_course_info(school.courses[1]).unwrap().teacher, school.teachers[1]);
+        assert_eq!(school.course_reg.get_course_info(school.courses[2]).unwrap().teacher, school.teachers[0]);
+        for course in &school.courses {
+            assert_eq!(school.course_reg.get_course_info(*course).unwrap().enrolled_count, 3);
+            assert!(!school.course_reg.is_registered(*course, school.students[3]));
+        }
+        assert_eq!(school.course_reg.check_invariants(school.courses.clone(), school.students.clone()),
+                   Ok(Vec::new()));
+    }
+}
@@ -0,0 +1,90 @@
+//! Registrations frozen by the registrar while an academic-integrity case is open
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn freeze_registration_inner(&mut self, student: AccountId, course_id: CourseId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.require_role(Role::Owner)?;
+        if !self.enrollments.contains((course_id, student)) {
+            return Err(Error::NotRegistered);
+        }
+        self.frozen_registrations.insert((course_id, student), &());
+        Ok(())
+    }
+
+    pub(crate) fn unfreeze_registration_inner(&mut self, student: AccountId, course_id: CourseId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.require_role(Role::Owner)?;
+        if !self.frozen_registrations.contains((course_id, student)) {
+            return Err(Error::RegistrationNotFrozen);
+        }
+        self.frozen_registrations.remove((course_id, student));
+        Ok(())
+    }
+
+    /// fails if the student's seat in the course can't leave the student
+    pub(crate) fn ensure_not_frozen(&self, student: AccountId, course_id: CourseId) -> Result<(), Error> {
+        if self.frozen_registrations.contains((course_id, student)) {
+            return Err(Error::RegistrationFrozen);
+        }
+        Ok(())
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+    use ink_prelude::vec;
+
+    /// Frozen registration test
+    #[ink::test]
+    fn frozen_registration() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student1 = AccountId::from([0x1; 32]);
+        let student2 = AccountId::from([0x2; 32]);
+        let course_id1 = CourseId(hash_keccak_256("test_course1".as_bytes()));
+        let course_id2 = CourseId(hash_keccak_256("test_course2".as_bytes()));
+        let course_id3 = CourseId(hash_keccak_256("test_course3".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_student(student1, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student2, None), Ok(()));
+        for course_id in [course_id1, course_id2, course_id3] {
+            assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        }
+        set_next_caller(student1);
+        assert_eq!(course_reg.register_to_courses(vec![course_id1, course_id3], None).map(|_| ()), Ok(()));
+        let proposal_id = course_reg.propose_swap(course_id1, None).unwrap();
+        set_next_caller(student2);
+        assert_eq!(course_reg.register_to_course(course_id2, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id2, None), Ok(()));
+
+        set_next_caller(owner);
+        assert_eq!(course_reg.freeze_registration(student2, course_id1, None), Err(Error::NotRegistered));
+        assert_eq!(course_reg.freeze_registration(student1, course_id1, None), Ok(()));
+        assert!(course_reg.is_registration_frozen(student1, course_id1));
+        set_next_caller(student1);
+        assert_eq!(course_reg.accept_counter_offer(proposal_id, course_id2, student2, None),
+                   Err(Error::RegistrationFrozen));
+        assert_eq!(course_reg.offer_seat_release(course_id1, None), Err(Error::RegistrationFrozen));
+
+        // the other courses of the student aren't touched
+        assert_eq!(course_reg.propose_swap(course_id3, None).map(|_| ()), Ok(()));
+        set_next_caller(owner);
+        assert_eq!(course_reg.unfreeze_registration(student1, course_id1, None), Ok(()));
+        assert_eq!(course_reg.unfreeze_registration(student1, course_id1, None), Err(Error::RegistrationNotFrozen));
+        set_next_caller(student1);
+        assert_eq!(course_reg.accept_counter_offer(proposal_id, course_id2, student2, None).map(|_| ()), Ok(()));
+    }
+}
@@ -0,0 +1,268 @@
+//! Grade commitments recorded by the teachers and revealed by the students
+
+use ink_env::hash;
+use ink_lang::codegen::StaticEnv;
+use ink_prelude::vec::Vec;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn record_grade_inner(&mut self,
+                                     course_id: CourseId,
+                                     student: AccountId,
+                                     grade_commitment: [u8; 32]) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_GRADES)?;
+        let caller = Self::env().caller();
+        self.require_course_teacher(course_id, caller)?;
+        if !self.course_ends.contains(course_id) {
+            return Err(Error::CourseNotEnded);
+        }
+        if !self.enrollments.contains((course_id, student)) {
+            return Err(Error::NotRegistered);
+        }
+        if self.grades.contains((course_id, student)) {
+            return Err(Error::GradeAlreadyRecorded);
+        }
+        let mut transcript = self.transcripts.get(student).unwrap_or_default();
+        if transcript.len() >= MAX_GRADES_PER_STUDENT as usize {
+            return Err(Error::TooManyGrades);
+        }
+        transcript.push(course_id);
+        let record = GradeRecord {
+            course_id,
+            commitment: grade_commitment,
+            revealed_grade: None,
+        };
+        self.grades.insert((course_id, student), &record);
+        self.transcripts.insert(student, &transcript);
+        Ok(())
+    }
+
+    pub(crate) fn reveal_grade_inner(&mut self,
+                                     course_id: CourseId,
+                                     salt: [u8; 32],
+                                     grade: Grade) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_GRADES)?;
+        let caller = Self::env().caller();
+        let mut record = self.grades.get((course_id, caller)).ok_or(Error::NoGradeRecorded)?;
+        if record.revealed_grade.is_some() {
+            return Err(Error::GradeAlreadyRevealed);
+        }
+        if Self::grade_commitment(grade, salt) != record.commitment {
+            return Err(Error::CommitmentMismatch);
+        }
+        if usize::from(grade) >= self.school_config.get_or_default().grade_points.len() {
+            return Err(Error::GradeOutsideScale);
+        }
+        record.revealed_grade = Some(grade);
+        self.grades.insert((course_id, caller), &record);
+        Ok(())
+    }
+
+    pub(crate) fn get_transcript_inner(&self, student: AccountId) -> Vec<GradeRecord> {
+        self.transcripts.get(student)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|course_id| self.grades.get((course_id, student)))
+            .collect()
+    }
+
+    pub(crate) fn get_academic_summary_inner(&self, student: AccountId) -> Result<AcademicSummary, Error> {
+        let mut credits_earned: u32 = 0;
+        let mut graded_credits: u64 = 0;
+        let mut weighted_points: u64 = 0;
+        let passing_grade = self.school_config.get_or_default().passing_grade;
+        for record in self.get_transcript_inner(student) {
+            let grade = match record.revealed_grade {
+                Some(grade) => grade,
+                None => continue,
+            };
+            let credits = self.course_credits.get(record.course_id).unwrap_or(0);
+            if grade >= passing_grade {
+                credits_earned = credits_earned.checked_add(credits).ok_or(Error::ArithmeticOverflow)?;
+            }
+            graded_credits = graded_credits.checked_add(u64::from(credits)).ok_or(Error::ArithmeticOverflow)?;
+            let points = u64::from(self.grade_points(grade)).checked_mul(u64::from(credits))
+                .ok_or(Error::ArithmeticOverflow)?;
+            weighted_points = weighted_points.checked_add(points).ok_or(Error::ArithmeticOverflow)?;
+        }
+
+        let credits_in_progress = self.credits_in_progress(student)?;
+        let gpa = weighted_points.checked_div(graded_credits).unwrap_or(0);
+        Ok(AcademicSummary {
+            credits_earned,
+            credits_in_progress,
+            gpa: u32::try_from(gpa).map_err(|_| Error::ArithmeticOverflow)?,
+        })
+    }
+
+    /// returns the credits of the student's registered courses that haven't ended
+    pub(crate) fn credits_in_progress(&self, student: AccountId) -> Result<u32, Error> {
+        let mut credits_in_progress: u32 = 0;
+        for course_id in self.registration_index.get(student).unwrap_or_default() {
+            if self.course_ends.contains(course_id) {
+                continue;
+            }
+            let credits = self.course_credits.get(course_id).unwrap_or(0);
+            credits_in_progress = credits_in_progress.checked_add(credits).ok_or(Error::ArithmeticOverflow)?;
+        }
+        Ok(credits_in_progress)
+    }
+
+    /// returns true if the student's passing grade in the course is revealed
+    pub(crate) fn has_completed(&self, student: AccountId, course_id: CourseId) -> bool {
+        self.grades.get((course_id, student))
+            .and_then(|record| record.revealed_grade)
+            .map(|grade| grade >= self.school_config.get_or_default().passing_grade)
+            .unwrap_or(false)
+    }
+
+    /// returns the name of the grade on the school's scale, `None` on a numeric scale
+    pub(crate) fn get_grade_label_inner(&self, grade: Grade) -> Option<Vec<u8>> {
+        self.school_config.get_or_default().grade_labels.get(usize::from(grade)).cloned()
+    }
+
+    /// returns the commitment to a grade, the Keccak256 hash
+    /// of the grade followed by the salt
+    pub fn grade_commitment(grade: Grade, salt: [u8; 32]) -> [u8; 32] {
+        let mut input = [0u8; 33];
+        input[0] = grade;
+        input[1..].copy_from_slice(&salt);
+        let mut output = <hash::Keccak256 as hash::HashOutput>::Type::default();
+        ink_env::hash_bytes::<hash::Keccak256>(&input, &mut output);
+        output
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Grade commitment and reveal test
+    #[ink::test]
+    fn grade_commitments() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let teacher = AccountId::from([0x1; 32]);
+        let student = AccountId::from([0x2; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        let salt = [0x7; 32];
+        let commitment = CourseReg::grade_commitment(4, salt);
+        assert_eq!(course_reg.admit_as_teacher(teacher, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        set_next_caller(teacher);
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+
+        set_next_caller(teacher);
+        assert_eq!(course_reg.record_grade(course_id, student, commitment, None), Err(Error::CourseNotEnded));
+        assert_eq!(course_reg.end_course(course_id, None), Err(Error::CourseNotStarted));
+        TestClock::set(start_time);
+        assert_eq!(course_reg.end_course(course_id, None), Ok(()));
+        assert_eq!(course_reg.record_grade(course_id, owner, commitment, None), Err(Error::NotRegistered));
+        assert_eq!(course_reg.record_grade(course_id, student, commitment, None), Ok(()));
+        assert_eq!(course_reg.record_grade(course_id, student, commitment, None), Err(Error::GradeAlreadyRecorded));
+
+        set_next_caller(student);
+        assert_eq!(course_reg.get_transcript(student)[0].revealed_grade, None);
+        assert_eq!(course_reg.reveal_grade(course_id, salt, 5, None), Err(Error::CommitmentMismatch));
+        assert_eq!(course_reg.reveal_grade(course_id, salt, 4, None), Ok(()));
+        assert_eq!(course_reg.get_transcript(student),
+                   ink_prelude::vec![GradeRecord { course_id, commitment, revealed_grade: Some(4) }]);
+    }
+
+    /// Credits and grade point average test
+    #[ink::test]
+    fn academic_summary() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        let salt = [0x7; 32];
+        let courses = [(CourseId(hash_keccak_256("c1".as_bytes())), 6, 5),
+                       (CourseId(hash_keccak_256("c2".as_bytes())), 4, 1),
+                       (CourseId(hash_keccak_256("c3".as_bytes())), 2, 0)];
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        for (course_id, credits, _) in courses {
+            assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+            assert_eq!(course_reg.set_course_credits(course_id, credits, None), Ok(()));
+        }
+        assert_eq!(course_reg.set_course_credits(courses[0].0, MAX_COURSE_CREDITS + 1, None), Err(Error::InvalidCredits));
+        set_next_caller(student);
+        for (course_id, _, _) in courses {
+            assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        }
+
+        // the first two courses end and their grades are revealed
+        TestClock::set(start_time);
+        for (course_id, _, grade) in &courses[..2] {
+            set_next_caller(owner);
+            assert_eq!(course_reg.end_course(*course_id, None), Ok(()));
+            let commitment = CourseReg::grade_commitment(*grade, salt);
+            assert_eq!(course_reg.record_grade(*course_id, student, commitment, None), Ok(()));
+            set_next_caller(student);
+            assert_eq!(course_reg.reveal_grade(*course_id, salt, *grade, None), Ok(()));
+        }
+        assert_eq!(course_reg.get_academic_summary(student),
+                   Ok(AcademicSummary { credits_earned: 6, credits_in_progress: 2, gpa: 340 }));
+
+        set_next_caller(owner);
+        let scale = SchoolConfig { grade_points: ink_prelude::vec![0, 0, 100], passing_grade: 3, ..SchoolConfig::default() };
+        assert_eq!(course_reg.set_school_config(scale, None), Err(Error::InvalidGradeScale));
+    }
+
+    /// Named grade scale test
+    #[ink::test]
+    fn grade_labels() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        let salt = [0x7; 32];
+        let labels: ink_prelude::vec::Vec<ink_prelude::vec::Vec<u8>> =
+            [b"F", b"D", b"C", b"B", b"A"].iter().map(|label| label.to_vec()).collect();
+        let scale = SchoolConfig {
+            grade_points: ink_prelude::vec![0, 100, 200, 300, 400],
+            passing_grade: 1,
+            grade_labels: labels[..4].to_vec(),
+            ..SchoolConfig::default()
+        };
+        assert_eq!(course_reg.set_school_config(scale.clone(), None), Err(Error::InvalidGradeScale));
+        assert_eq!(course_reg.set_school_config(SchoolConfig { grade_labels: labels, ..scale }, None), Ok(()));
+        assert_eq!(course_reg.get_grade_label(4), Some(b"A".to_vec()));
+        assert_eq!(course_reg.get_grade_label(5), None);
+
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        TestClock::set(start_time);
+        set_next_caller(owner);
+        assert_eq!(course_reg.end_course(course_id, None), Ok(()));
+        assert_eq!(course_reg.record_grade(course_id, student, CourseReg::grade_commitment(5, salt), None), Ok(()));
+
+        // the grade committed to isn't on the scale
+        set_next_caller(student);
+        assert_eq!(course_reg.reveal_grade(course_id, salt, 5, None), Err(Error::GradeOutsideScale));
+    }
+}
@@ -0,0 +1,135 @@
+//! Replay protection and stale state detection of the mutating messages
+
+use ink_lang::codegen::StaticEnv;
+use ink_prelude::vec::Vec;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    /// runs a mutating message behind the caller's guard
+    ///
+    /// the guard is checked before the message touches storage, and only
+    /// a successful message bumps the caller's nonce and the state version,
+    /// so a failed call can be resubmitted with the same guard
+    pub(crate) fn guarded<T>(&mut self,
+                             guard: Option<CallGuard>,
+                             message: impl FnOnce(&mut Self) -> Result<T, Error>) -> Result<T, Error> {
+        let caller = Self::env().caller();
+        let nonce = self.nonces.get(caller).unwrap_or(0);
+        let state_version = self.state_version.get_or_default();
+        if let Some(guard) = guard {
+            if guard.nonce != nonce {
+                return Err(Error::NonceMismatch { expected: nonce });
+            }
+            if let Some(expected) = guard.expected_state_version {
+                if expected != state_version {
+                    return Err(Error::StaleState { current: state_version });
+                }
+            }
+        }
+        let next_nonce = nonce.checked_add(1).ok_or(Error::ArithmeticOverflow)?;
+        let next_state_version = state_version.checked_add(1).ok_or(Error::ArithmeticOverflow)?;
+
+        let result = message(self)?;
+        self.nonces.insert(caller, &next_nonce);
+        self.state_version.set(&next_state_version);
+        Ok(result)
+    }
+
+    /// runs a mutating message behind the caller's guard and returns the
+    /// receipt of the courses it changed the seats of
+    pub(crate) fn receipted(&mut self,
+                            guard: Option<CallGuard>,
+                            message: impl FnOnce(&mut Self) -> Result<Vec<CourseId>, Error>) -> Result<Receipt, Error> {
+        let course_ids = self.guarded(guard, message)?;
+        let remaining_seats = course_ids.iter()
+            .map(|course_id| self.remaining_capacity_inner(*course_id).unwrap_or(0))
+            .collect();
+        Ok(Receipt { state_version: self.state_version.get_or_default(), course_ids, remaining_seats })
+    }
+
+    pub(crate) fn get_nonce_inner(&self, account: AccountId) -> u64 {
+        self.nonces.get(account).unwrap_or(0)
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Replayed and stale call rejection test
+    #[ink::test]
+    fn call_guards() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student1 = AccountId::from([0x1; 32]);
+        let student2 = AccountId::from([0x2; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_student(student1, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student2, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id, 1, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.get_nonce(owner), 4);
+        let state_version = course_reg.get_state_version();
+
+        // a replayed call carries a used nonce
+        let guard = CallGuard { nonce: 0, expected_state_version: Some(state_version) };
+        set_next_caller(student1);
+        assert_eq!(course_reg.register_to_course(course_id, Some(guard)).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.register_to_course(course_id, Some(guard)), Err(Error::NonceMismatch { expected: 1 }));
+
+        // the seat was taken since the call was built
+        set_next_caller(student2);
+        assert_eq!(course_reg.register_to_course(course_id, Some(guard)),
+                   Err(Error::StaleState { current: state_version + 1 }));
+        assert_eq!(course_reg.get_nonce(student2), 0);
+    }
+
+    /// Receipts of the seat changes test
+    #[ink::test]
+    fn receipts() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student1 = AccountId::from([0x1; 32]);
+        let student2 = AccountId::from([0x2; 32]);
+        let course_id1 = CourseId(hash_keccak_256("test_course1".as_bytes()));
+        let course_id2 = CourseId(hash_keccak_256("test_course2".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_student(student1, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student2, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id1, 5, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id2, 3, start_time, term_id, None, None), Ok(()));
+
+        set_next_caller(student1);
+        let receipt = course_reg.register_to_course(course_id1, None).unwrap();
+        assert_eq!(receipt, Receipt {
+            state_version: course_reg.get_state_version(),
+            course_ids: ink_prelude::vec![course_id1],
+            remaining_seats: ink_prelude::vec![4],
+        });
+        let proposal_id = course_reg.propose_swap(course_id1, None).unwrap();
+        set_next_caller(student2);
+        let receipt = course_reg.register_to_courses(ink_prelude::vec![course_id2], None).unwrap();
+        assert_eq!(receipt.remaining_seats, ink_prelude::vec![2]);
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id2, None), Ok(()));
+        set_next_caller(student1);
+        let receipt = course_reg.accept_counter_offer(proposal_id, course_id2, student2, None).unwrap();
+        assert_eq!(receipt, Receipt {
+            state_version: course_reg.get_state_version(),
+            course_ids: ink_prelude::vec![course_id1, course_id2],
+            remaining_seats: ink_prelude::vec![4, 2],
+        });
+    }
+}
@@ -0,0 +1,125 @@
+//! Registration hooks, external contracts plugged into the registration of a course
+//!
+//! a hook is asked before every registration to the course and told after
+//! every seat filled in it, so a department can add its own eligibility
+//! rules, like exam scores kept by another contract, without a fork
+
+use ink_env::call::{build_call, Call, ExecutionInput, Selector};
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+/// The interface a registration hook contract implements
+#[ink_lang::trait_definition]
+pub trait RegistrationHook {
+    /// Returns true if the student may register to the course
+    #[ink(message)]
+    fn before_register(&self, course_id: CourseId, student: AccountId) -> bool;
+
+    /// Takes note of the student taking a seat in the course
+    #[ink(message)]
+    fn after_register(&mut self, course_id: CourseId, student: AccountId);
+}
+
+/// The selector of `RegistrationHook::before_register`
+const BEFORE_REGISTER: [u8; 4] = ink_lang::selector_bytes!("RegistrationHook::before_register");
+/// The selector of `RegistrationHook::after_register`
+const AFTER_REGISTER: [u8; 4] = ink_lang::selector_bytes!("RegistrationHook::after_register");
+
+impl CourseReg {
+    pub(crate) fn set_registration_hook_inner(&mut self,
+                                              course_id: CourseId,
+                                              hook: Option<AccountId>) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_REGISTRATION_HOOKS)?;
+        self.require_role(Role::Owner)?;
+        if !self.courses.contains(course_id) {
+            return Err(Error::NonexistentCourse);
+        }
+        match hook {
+            Some(hook) => self.registration_hooks.insert(course_id, &hook),
+            None => self.registration_hooks.remove(course_id),
+        }
+        Ok(())
+    }
+
+    /// asks the hook of the course, if any, whether the student may register
+    pub(crate) fn ensure_hook_allows(&self, course_id: CourseId, student: AccountId) -> Result<(), Error> {
+        let hook = match self.registration_hook(course_id) {
+            Some(hook) => hook,
+            None => return Ok(()),
+        };
+        let allowed = build_call::<ink_env::DefaultEnvironment>()
+            .call_type(Call::new().callee(hook))
+            .exec_input(ExecutionInput::new(Selector::new(BEFORE_REGISTER)).push_arg(course_id).push_arg(student))
+            .returns::<bool>()
+            .fire()
+            .map_err(|_| Error::HookCallFailed)?;
+        if !allowed {
+            return Err(Error::RejectedByHook);
+        }
+        Ok(())
+    }
+
+    /// tells the hook of the course, if any, that the student took a seat
+    ///
+    /// the seat is taken already, a failing hook doesn't undo it
+    pub(crate) fn notify_hook(&self, course_id: CourseId, student: AccountId) {
+        if let Some(hook) = self.registration_hook(course_id) {
+            let _ = build_call::<ink_env::DefaultEnvironment>()
+                .call_type(Call::new().callee(hook))
+                .exec_input(ExecutionInput::new(Selector::new(AFTER_REGISTER)).push_arg(course_id).push_arg(student))
+                .returns::<()>()
+                .fire();
+        }
+    }
+
+    fn registration_hook(&self, course_id: CourseId) -> Option<AccountId> {
+        if !self.feature_enabled(FEATURE_REGISTRATION_HOOKS) {
+            return None;
+        }
+        self.registration_hooks.get(course_id)
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Registration hook configuration test
+    ///
+    /// the off-chain environment can't call other contracts, the calls
+    /// to the hook are left to the end-to-end tests
+    #[ink::test]
+    fn registration_hook_config() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let hook = AccountId::from([0x9; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.set_registration_hook(CourseId([0x1; 32]), Some(hook), None), Err(Error::NonexistentCourse));
+        assert_eq!(course_reg.set_registration_hook(course_id, Some(hook), None), Ok(()));
+        assert_eq!(course_reg.get_registration_hook(course_id), Some(hook));
+        set_next_caller(student);
+        assert_eq!(course_reg.set_registration_hook(course_id, None, None), Err(Error::InsufficientPermissions));
+
+        // without a hook the registration doesn't call out
+        set_next_caller(owner);
+        assert_eq!(course_reg.set_registration_hook(course_id, None, None), Ok(()));
+        assert_eq!(course_reg.get_registration_hook(course_id), None);
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+    }
+}
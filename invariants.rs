@@ -0,0 +1,129 @@
+//! Cross-structure consistency checks for tests and audit builds
+//!
+//! the walk is only compiled with the `audit` feature, production
+//! builds answer `check_invariants` with `InvariantChecksDisabled`
+
+#[cfg(any(test, feature = "audit"))]
+use ink_prelude::vec::Vec;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    /// checks the listed courses and accounts, the proposals of the listed
+    /// courses make up the escrow, so every course with proposals is listed
+    #[cfg(any(test, feature = "audit"))]
+    pub(crate) fn check_invariants_inner(&self,
+                                         course_ids: &[CourseId],
+                                         accounts: &[AccountId]) -> Result<Vec<InvariantViolation>, Error> {
+        if course_ids.len() > MAX_BATCH_SIZE as usize || accounts.len() > MAX_BATCH_SIZE as usize {
+            return Err(Error::BatchTooLarge);
+        }
+        let mut escrowed = Vec::new();
+        for course_id in course_ids {
+            for proposal_id in self.course_proposals.get(course_id).unwrap_or_default() {
+                if let Some(proposal) = self.proposals.get(proposal_id) {
+                    escrowed.push(proposal.offer);
+                    escrowed.extend(proposal.counter_offers);
+                }
+            }
+        }
+
+        let mut violations = Vec::new();
+        for course_id in course_ids {
+            let course = match self.courses.get(course_id) {
+                Some(course) => course,
+                None => continue,
+            };
+            let roster = self.rosters.get(course_id).unwrap_or_default();
+            if usize::try_from(course.enrolled_count).ok() != Some(roster.len()) {
+                violations.push(InvariantViolation::EnrolledCountMismatch { course_id: *course_id });
+            }
+            for student in roster {
+                let course_id = *course_id;
+                if !self.enrollments.contains((course_id, student)) {
+                    violations.push(InvariantViolation::NotEnrolled { course_id, student });
+                }
+                let held = self.registrations.contains(TokenId { owner: student, course_id });
+                let in_escrow = escrowed.contains(&CourseRegistration { owner: student, course_id });
+                if !held && !in_escrow {
+                    violations.push(InvariantViolation::MissingToken { course_id, student });
+                }
+                if held && in_escrow {
+                    violations.push(InvariantViolation::TokenHeldAndEscrowed { course_id, student });
+                }
+            }
+        }
+
+        for student in accounts {
+            let student = *student;
+            let index = self.registration_index.get(student).unwrap_or_default();
+            for (position, course_id) in index.iter().enumerate() {
+                let course_id = *course_id;
+                if index[..position].contains(&course_id) {
+                    violations.push(InvariantViolation::DuplicateIndexEntry { course_id, student });
+                }
+                if !self.registrations.contains(TokenId { owner: student, course_id }) {
+                    violations.push(InvariantViolation::MissingToken { course_id, student });
+                }
+                if !self.enrollments.contains((course_id, student)) {
+                    violations.push(InvariantViolation::NotEnrolled { course_id, student });
+                }
+            }
+        }
+        Ok(violations)
+    }
+
+    #[cfg(not(any(test, feature = "audit")))]
+    pub(crate) fn check_invariants_inner(&self,
+                                         _course_ids: &[CourseId],
+                                         _accounts: &[AccountId]) -> Result<ink_prelude::vec::Vec<InvariantViolation>, Error> {
+        Err(Error::InvariantChecksDisabled)
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::course_reg::CourseReg;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+    use ink_prelude::vec;
+
+    /// Invariant violation report test
+    #[ink::test]
+    fn invariant_report() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student1 = AccountId::from([0x1; 32]);
+        let student2 = AccountId::from([0x2; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student1, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student2, None), Ok(()));
+        set_next_caller(student1);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        assert!(course_reg.propose_swap(course_id, None).is_ok());
+        set_next_caller(student2);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        let courses = vec![course_id];
+        let accounts = vec![student1, student2];
+        assert_eq!(course_reg.check_invariants(courses.clone(), accounts.clone()), Ok(vec![]));
+
+        // a token both held and escrowed, and a count that drifted from the roster
+        course_reg.mint(course_id, student1);
+        let mut course = course_reg.courses.get(course_id).unwrap();
+        course.enrolled_count = 3;
+        course_reg.courses.insert(course_id, &course);
+        assert_eq!(course_reg.check_invariants(courses, accounts), Ok(vec![
+            InvariantViolation::EnrolledCountMismatch { course_id },
+            InvariantViolation::TokenHeldAndEscrowed { course_id, student: student1 },
+        ]));
+    }
+}
@@ -0,0 +1,81 @@
+//! A root storage value read on access instead of when the contract is loaded
+
+use core::marker::PhantomData;
+
+use ink_primitives::Key;
+use ink_storage::traits::{push_packed_root, ExtKeyPtr, KeyPtr, PackedLayout, SpreadAllocate, SpreadLayout};
+
+/// A value in its own storage cell, read and written on access
+///
+/// the contract loads its root struct before every message and a plain
+/// field traps if its cell is empty, as it is after an upgrade that added
+/// the field; a `Lazy` field takes the cell of the plain field it replaces
+/// and reads an empty cell as `None`
+pub struct Lazy<T> {
+    key: Key,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T: PackedLayout> Lazy<T> {
+    fn new(key: Key) -> Self {
+        Self { key, marker: PhantomData }
+    }
+
+    /// returns the stored value, `None` if the cell is empty
+    pub fn get(&self) -> Option<T> {
+        let mut value = ink_env::get_contract_storage::<T>(&self.key)
+            .expect("the cell holds a value of its type")?;
+        value.pull_packed(&self.key);
+        Some(value)
+    }
+
+    /// writes the value to the cell
+    pub fn set(&mut self, value: &T) {
+        push_packed_root(value, &self.key);
+    }
+}
+
+impl<T: PackedLayout + Default> Lazy<T> {
+    /// returns the stored value, the default if the cell is empty
+    pub fn get_or_default(&self) -> T {
+        self.get().unwrap_or_default()
+    }
+}
+
+impl<T> core::fmt::Debug for Lazy<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Lazy").field("key", &self.key).finish()
+    }
+}
+
+impl<T: PackedLayout> SpreadLayout for Lazy<T> {
+    const FOOTPRINT: u64 = 1;
+    const REQUIRES_DEEP_CLEAN_UP: bool = false;
+
+    fn pull_spread(ptr: &mut KeyPtr) -> Self {
+        Self::new(*ExtKeyPtr::next_for::<Self>(ptr))
+    }
+
+    fn push_spread(&self, ptr: &mut KeyPtr) {
+        // the value is written by `set`, only the cell is skipped
+        ptr.advance_by(Self::FOOTPRINT);
+    }
+
+    fn clear_spread(&self, ptr: &mut KeyPtr) {
+        ptr.advance_by(Self::FOOTPRINT);
+    }
+}
+
+impl<T: PackedLayout> SpreadAllocate for Lazy<T> {
+    fn allocate_spread(ptr: &mut KeyPtr) -> Self {
+        Self::new(*ExtKeyPtr::next_for::<Self>(ptr))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: PackedLayout + scale_info::TypeInfo + 'static> ink_storage::traits::StorageLayout for Lazy<T> {
+    fn layout(key_ptr: &mut KeyPtr) -> ink_metadata::layout::Layout {
+        use ink_metadata::layout::{CellLayout, Layout, LayoutKey};
+        Layout::Cell(CellLayout::new::<T>(LayoutKey::from(key_ptr.advance_by(1))))
+    }
+}
@@ -2,77 +2,575 @@
 
 use ink_lang as ink;
 
+mod accommodations;
+mod activity;
+mod assignments;
+mod bootstrap;
+mod clock;
+mod cohorts;
+mod config;
+mod corequisites;
+mod courses;
+mod demand;
+mod enrollment_history;
+mod errors;
+mod evaluations;
+mod exams;
+mod export;
+mod freezes;
+mod grades;
+mod guards;
+mod hooks;
+mod invariants;
+mod lazy;
+mod lifecycle;
+mod maintenance;
+mod membership;
+mod notifications;
+mod office_hours;
+mod overrides;
+mod permissions;
+mod plans;
+mod profiles;
+mod programs;
+mod public;
+mod questions;
+mod reader;
+mod registrations;
+mod releases;
+mod rooms;
+mod rotation;
+mod schedules;
+mod sessions;
+mod snapshots;
+mod standing;
+mod standing_counters;
+mod supervision;
+mod swap_pairs;
+mod swap_policy;
+mod swap_reputation;
+mod swap_search;
+mod swaps;
+mod terms;
+mod tie_break;
+mod tokens;
+mod transitions;
+mod types;
+mod validation;
+mod views;
+
+#[cfg(any(test, feature = "fixtures"))]
+pub mod fixtures;
+#[cfg(test)]
+mod swap_model;
+#[cfg(test)]
+mod test_utils;
+#[cfg(all(test, feature = "benches"))]
+mod benches;
+
+pub use crate::clock::{BlockClock, Clock};
+#[cfg(any(test, feature = "fixtures"))]
+pub use crate::clock::TestClock;
+pub use crate::course_reg::{CourseReg, CourseRegRef};
+pub use crate::errors::Error;
+pub use crate::hooks::RegistrationHook;
+pub use crate::reader::CourseRegistryReader;
+pub use crate::types::*;
+
+/// The contract storage, events and messages
+///
+/// the messages carry the docs and delegate to the
+/// `..._inner` functions of the modules, which hold the logic
 #[ink::contract]
 mod course_reg {
-    //use ink_env::debug_println;
-    use ink_env::hash;
     use ink_storage::Mapping;
     use ink_prelude::vec::Vec;
-    use ink_storage::traits::{SpreadAllocate, PackedLayout, SpreadLayout};
-
-    /// A university course created by a teacher
-    #[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, PartialEq, Debug)]
-    #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
-    pub struct Course {
-        /// the teacher who created the course
-        teacher: AccountId,
-        /// the id of the course
-        course_id: [u8; 32],
-        /// the max number of students who can register
-        capacity: u32,
-        /// the registered students
-        registrations: Vec<AccountId>,
-        /// the starting time of the course
-        start_date: Timestamp,
-    }
-
-    /// A course registration token
-    #[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, PartialEq, Debug)]
-    #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
-    pub struct CourseRegistration {
-        /// the owner of the token
-        owner: AccountId,
-        /// the id of the course
-        course_id: [u8; 32],
-    }
-
-    /// A course registration token swap proposal 
-    #[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode)]
-    #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
-    pub struct CourseRegistrationSwapProposal {
-        /// the offered token
-        offer: CourseRegistration,
-        /// the tokens offered in exchange
-        counter_offers: Vec<CourseRegistration>
-    }
+    use ink_storage::traits::SpreadAllocate;
+
+    use crate::errors::Error;
+    use crate::lazy::Lazy;
+    use crate::reader::CourseRegistryReader;
+    use crate::types::{
+        AcademicStanding, AcademicSummary, Assignment, AssignmentId, Attendance, CallGuard, Cohort,
+        CohortId, CounterOfferRef, Course, CourseId, CourseImport, CourseMetadata,
+        CourseRegistration, CourseRegistrationSwapProposal, CourseSpec, CourseState, CourseV2,
+        CourseV3, CourseView, Cursor, DemandMetrics, DepartmentId, EnrollmentPeriod, EscrowedToken,
+        Evaluation, Exam, ExamId, FeatureFlags, Grade, GradeRecord, InvariantViolation,
+        MemberImport, MigrationProgress, OfficeHourSlot, OfficeHourWindow, Page, PendingActivation,
+        PlanIssue, Profile, Program, ProgramId, ProgramProgress, ProposalId, PublicEnrollment,
+        Rating, RatingTotals, Receipt, RegistrationAnswer, RegistrationImport, RegistrationPhase,
+        RegistrationTransitions, RegistrationView, RoomId, RosterSnapshot, SchoolConfig, Session,
+        SessionId, SlotId, SnapshotId, StateChunk, StateSection, Submission, SupervisionApplication,
+        SwapPolicy, SwapPreview, SwapProposalV4, SwapReputation, SwapView, TeacherAction, Term,
+        TermActivity, TermId, TieBreakPolicy, TokenId, STORAGE_VERSION, SUPPORTED_FEATURES,
+    };
 
     /// Contract storage
     #[ink(storage)]
     #[derive(SpreadAllocate)]
     pub struct CourseReg {
         /// the owner of the contract, the school leader
-        owner: AccountId,
+        pub(crate) owner: AccountId,
         /// the members of the school, <id, isTeacher>
-        school_members: Mapping<AccountId, bool>,
+        pub(crate) school_members: Mapping<AccountId, bool>,
+        /// the V2 layout of the courses <CourseId, Course>, only read by the migration
+        pub(crate) courses_v2: Mapping<CourseId, CourseV2>,
+        /// the V4 layout of the proposed swaps <CourseId, swapProposals>, only read by the migration
+        pub(crate) swaps_v4: Mapping<CourseId, Vec<SwapProposalV4>>,
+        /// the V1 layout of the registration tokens <owner, tokens>, only read by the migration
+        pub(crate) registrations_v1: Mapping<AccountId, Vec<CourseRegistration>>,
+        /// the storage layout version the data was written with, empty in the first layout
+        pub(crate) version: Lazy<u32>,
+        /// the latest start date of all created courses
+        pub(crate) latest_course_start: Lazy<Timestamp>,
+        /// the owned registration tokens <(owner, courseId), token>
+        pub(crate) registrations: Mapping<TokenId, CourseRegistration>,
+        /// the course ids of the tokens owned by an account <owner, courseIds>
+        pub(crate) registration_index: Mapping<AccountId, Vec<CourseId>>,
+        /// the V3 layout of the courses <CourseId, Course>, only read by the migration
+        pub(crate) courses_v3: Mapping<CourseId, CourseV3>,
+        /// the registered students of the courses <(courseId, student)>
+        pub(crate) enrollments: Mapping<(CourseId, AccountId), ()>,
         /// the courses created by the teachers <CourseId, Course>
-        courses: Mapping<[u8; 32], Course>,
-        /// the proposed swaps <swapId, swapProposal>
-        swaps: Mapping<[u8; 32], Vec<CourseRegistrationSwapProposal>>,
-        /// the owned registration tokens <owner, tokens>
-        registrations: Mapping<AccountId, Vec<CourseRegistration>>,
+        pub(crate) courses: Mapping<CourseId, Course>,
+        /// the registered students of the courses in registration order <CourseId, students>
+        pub(crate) rosters: Mapping<CourseId, Vec<AccountId>>,
+        /// the proposed swaps <ProposalId, swapProposal>
+        pub(crate) proposals: Mapping<ProposalId, CourseRegistrationSwapProposal>,
+        /// the ids of the proposals offering a course <CourseId, ProposalIds>
+        pub(crate) course_proposals: Mapping<CourseId, Vec<ProposalId>>,
+        /// the id the next swap proposal gets
+        pub(crate) next_proposal_id: Lazy<ProposalId>,
+        /// no longer written, kept so the cells after it keep their keys
+        pub(crate) teacher_course_counts: Mapping<AccountId, u32>,
+        /// the next nonce of the accounts' guarded calls <account, nonce>
+        pub(crate) nonces: Mapping<AccountId, u64>,
+        /// bumped by every successful guarded call
+        pub(crate) state_version: Lazy<u64>,
+        /// the end times of the ended courses <CourseId, endTime>
+        pub(crate) course_ends: Mapping<CourseId, Timestamp>,
+        /// the grades recorded for the students <(courseId, student), grade>
+        pub(crate) grades: Mapping<(CourseId, AccountId), GradeRecord>,
+        /// the ids of the courses a student was graded in <student, courseIds>
+        pub(crate) transcripts: Mapping<AccountId, Vec<CourseId>>,
+        /// the credits the courses are worth <CourseId, credits>
+        pub(crate) course_credits: Mapping<CourseId, u32>,
+        /// the attendance sessions of the courses <(courseId, sessionId), session>
+        pub(crate) sessions: Mapping<(CourseId, SessionId), Session>,
+        /// the number of sessions opened for the courses <CourseId, count>
+        pub(crate) session_counts: Mapping<CourseId, u32>,
+        /// the check-ins to the sessions <(courseId, sessionId, student)>
+        pub(crate) check_ins: Mapping<(CourseId, SessionId, AccountId), ()>,
+        /// the number of sessions the students attended <(courseId, student), count>
+        pub(crate) attendance: Mapping<(CourseId, AccountId), u32>,
+        /// the assignments of the courses <(courseId, assignmentId), assignment>
+        pub(crate) assignments: Mapping<(CourseId, AssignmentId), Assignment>,
+        /// the number of assignments created for the courses <CourseId, count>
+        pub(crate) assignment_counts: Mapping<CourseId, u32>,
+        /// the submissions of the assignments <(courseId, assignmentId, student), submission>
+        pub(crate) submissions: Mapping<(CourseId, AssignmentId, AccountId), Submission>,
+        /// the evaluations of the courses <(courseId, student), evaluation>
+        pub(crate) evaluations: Mapping<(CourseId, AccountId), Evaluation>,
+        /// the revealed evaluation scores of the courses <CourseId, totals>
+        pub(crate) course_ratings: Mapping<CourseId, RatingTotals>,
+        /// the revealed evaluation scores of the teachers' courses <teacher, totals>
+        pub(crate) teacher_ratings: Mapping<AccountId, RatingTotals>,
+        /// the terms of the school <TermId, term>
+        pub(crate) terms: Mapping<TermId, Term>,
+        /// the id the next term gets
+        pub(crate) next_term_id: Lazy<TermId>,
+        /// the terms of the courses <CourseId, TermId>
+        pub(crate) course_terms: Mapping<CourseId, TermId>,
+        /// the degree programs <ProgramId, program>
+        pub(crate) programs: Mapping<ProgramId, Program>,
+        /// the id the next program gets
+        pub(crate) next_program_id: Lazy<ProgramId>,
+        /// the programs of the students <student, ProgramId>
+        pub(crate) student_programs: Mapping<AccountId, ProgramId>,
+        /// the academic standings of the students, `Good` when missing
+        pub(crate) standings: Mapping<AccountId, AcademicStanding>,
+        /// the courses that have to be taken together with a course
+        pub(crate) corequisites: Mapping<CourseId, Vec<CourseId>>,
+        /// the topic hashes of the supervision slots
+        pub(crate) supervision_topics: Mapping<CourseId, [u8; 32]>,
+        /// the applications to the supervision slots
+        pub(crate) supervision_applications: Mapping<CourseId, Vec<SupervisionApplication>>,
+        /// the exams of the courses <(courseId, examId), exam>
+        pub(crate) exams: Mapping<(CourseId, ExamId), Exam>,
+        /// the number of exams created for the courses <CourseId, count>
+        pub(crate) exam_counts: Mapping<CourseId, u32>,
+        /// the signed up students of the exams <(courseId, examId), count>
+        pub(crate) exam_seats: Mapping<(CourseId, ExamId), u32>,
+        /// the exam the students sit in a period <(courseId, period, student), examId>
+        pub(crate) exam_sittings: Mapping<(CourseId, u32, AccountId), ExamId>,
+        /// the marked attempts <(courseId, examId, student), passed>
+        pub(crate) exam_results: Mapping<(CourseId, ExamId, AccountId), bool>,
+        /// the students who failed a first attempt <(courseId, student), ()>
+        pub(crate) failed_exams: Mapping<(CourseId, AccountId), ()>,
+        /// the cohorts <CohortId, cohort>
+        pub(crate) cohorts: Mapping<CohortId, Cohort>,
+        /// the id the next cohort gets
+        pub(crate) next_cohort_id: Lazy<CohortId>,
+        /// the members of the cohorts <(cohortId, account), ()>
+        pub(crate) cohort_members: Mapping<(CohortId, AccountId), ()>,
+        /// the registration boundaries of the courses marked with an event
+        pub(crate) registration_phases: Mapping<CourseId, RegistrationPhase>,
+        /// the seats their students offer to release <(courseId, releaser), ()>
+        pub(crate) seat_releases: Mapping<(CourseId, AccountId), ()>,
+        /// the hashes of the members' off-chain profiles
+        pub(crate) profiles: Mapping<AccountId, Profile>,
+        /// the off-chain metadata of the courses
+        pub(crate) course_metadata: Mapping<CourseId, CourseMetadata>,
+        /// the features the owner disabled
+        pub(crate) feature_flags: Lazy<FeatureFlags>,
+        /// the registration changes of the students per term
+        pub(crate) term_activity: Mapping<(AccountId, TermId), TermActivity>,
+        /// the roster snapshots of the courses <(courseId, snapshotId), snapshot>
+        pub(crate) roster_snapshots: Mapping<(CourseId, SnapshotId), RosterSnapshot>,
+        /// the number of snapshots taken of the courses <CourseId, count>
+        pub(crate) snapshot_counts: Mapping<CourseId, u32>,
+        /// the number of courses of the teachers that haven't ended yet
+        pub(crate) active_course_counts: Mapping<AccountId, u32>,
+        /// the owner's exceptions to the school's course quota <teacher, quota>
+        pub(crate) course_quota_overrides: Mapping<AccountId, u32>,
+        /// the activation dates of the students admitted ahead of time
+        pub(crate) activation_dates: Mapping<AccountId, Timestamp>,
+        /// the students admitted ahead of time, pruned by the next deferred admission
+        pub(crate) pending_activations: Lazy<Vec<AccountId>>,
+        /// the end of the validity of the students' membership <account, validUntil>
+        pub(crate) membership_expiries: Mapping<AccountId, Timestamp>,
+        /// the graduated students and their graduation date <account, graduatedAt>
+        pub(crate) alumni: Mapping<AccountId, Timestamp>,
+        /// the public keys the members receive sealed notices with
+        pub(crate) notification_keys: Mapping<AccountId, [u8; 32]>,
+        /// the draft schedules of the students <student, courseIds>
+        pub(crate) plans: Mapping<AccountId, Vec<CourseId>>,
+        /// the public pools of the courses open to non-members
+        pub(crate) public_enrollments: Mapping<CourseId, PublicEnrollment>,
+        /// the public registrants of the courses <(courseId, account), ()>
+        pub(crate) public_registrations: Mapping<(CourseId, AccountId), ()>,
+        /// the number of public registrants of the courses
+        pub(crate) public_counts: Mapping<CourseId, u32>,
+        /// the time of the accounts' last public registration
+        pub(crate) last_public_registrations: Mapping<AccountId, Timestamp>,
+        /// the hashes of the registration questions of the courses
+        pub(crate) registration_questions: Mapping<CourseId, [u8; 32]>,
+        /// the hashes of the students' answers <(courseId, student), answerHash>
+        pub(crate) registration_answers: Mapping<(CourseId, AccountId), [u8; 32]>,
+        /// the students guaranteed a seat past the capacity of the courses
+        pub(crate) accommodations: Mapping<AccountId, ()>,
+        /// the proposals locking the accounts' tokens, once per token <owner, proposalIds>
+        pub(crate) escrow_index: Mapping<AccountId, Vec<ProposalId>>,
+        /// the swap policies of the courses, open if missing <courseId, policy>
+        pub(crate) swap_policies: Mapping<CourseId, SwapPolicy>,
+        /// the departments of the courses <courseId, departmentId>
+        pub(crate) course_departments: Mapping<CourseId, DepartmentId>,
+        /// how the swap proposals of the accounts ended <proposer, record>
+        pub(crate) swap_reputations: Mapping<AccountId, SwapReputation>,
+        /// true once the owner sealed the imports of the legacy records
+        pub(crate) bootstrap_finished: Lazy<bool>,
+        /// the hook contracts of the courses <courseId, hook>
+        pub(crate) registration_hooks: Mapping<CourseId, AccountId>,
+        /// the seats frozen while an academic-integrity case is open <(courseId, student), ()>
+        pub(crate) frozen_registrations: Mapping<(CourseId, AccountId), ()>,
+        /// the swaps settled between two accounts in a term <(termId, account, account), count>, the smaller account first
+        pub(crate) swap_pair_counts: Mapping<(TermId, AccountId, AccountId), u32>,
+        /// the interest in the courses the seats couldn't meet <CourseId, metrics>
+        pub(crate) demand_metrics: Mapping<CourseId, DemandMetrics>,
+        /// the students turned away by the full courses <(courseId, student), ()>
+        pub(crate) capacity_rejections: Mapping<(CourseId, AccountId), ()>,
+        /// the standing counter offers <(wantedCourseId, student), offeredCourseId>
+        pub(crate) standing_counters: Mapping<(CourseId, AccountId), CourseId>,
+        /// the students with a standing counter offer for a course, in the order they were set <wantedCourseId, students>
+        pub(crate) standing_counter_index: Mapping<CourseId, Vec<AccountId>>,
+        /// the office-hour slots of the courses <(courseId, slotId), slot>
+        pub(crate) office_hours: Mapping<(CourseId, SlotId), OfficeHourSlot>,
+        /// the number of office-hour slots published for the courses <courseId, count>
+        pub(crate) office_hour_counts: Mapping<CourseId, SlotId>,
+        /// the slot a student booked in a course <(courseId, student), slotId>
+        pub(crate) office_hour_bookings: Mapping<(CourseId, AccountId), SlotId>,
+        /// the seats of the rooms <RoomId, capacity>
+        pub(crate) rooms: Mapping<RoomId, u32>,
+        /// the rooms the courses are held in <CourseId, RoomId>
+        pub(crate) course_rooms: Mapping<CourseId, RoomId>,
+        /// the courses held in the rooms <RoomId, courseIds>
+        pub(crate) room_courses: Mapping<RoomId, Vec<CourseId>>,
+        /// the registration boundaries moved by hand <CourseId, transitions>
+        pub(crate) registration_transitions: Mapping<CourseId, RegistrationTransitions>,
+        /// the times the proposals were made <ProposalId, proposedAt>
+        pub(crate) proposal_times: Mapping<ProposalId, Timestamp>,
+        /// the number of open proposals
+        pub(crate) open_proposal_count: Lazy<u32>,
+        /// the periods the students held their seats, oldest first <(courseId, student), periods>
+        pub(crate) enrollment_periods: Mapping<(CourseId, AccountId), Vec<EnrollmentPeriod>>,
+        /// the tie-break policies of the courses, first come, first served when missing
+        pub(crate) tie_break_policies: Mapping<CourseId, TieBreakPolicy>,
+        /// the seat requests collected when registration opened, in request order
+        pub(crate) seat_requests: Mapping<CourseId, Vec<AccountId>>,
+        /// courses whose entries the migration step from the version has converted
+        pub(crate) migrated_courses: Mapping<(u32, CourseId), ()>,
+        /// the school-wide settings, read on access
+        pub(crate) school_config: Lazy<SchoolConfig>,
+        /// the progress of the migration steps <fromVersion, progress>
+        pub(crate) migration_progress: Mapping<u32, MigrationProgress>,
+        /// the wanted courses of the students' standing counter offers <student, courseIds>
+        pub(crate) standing_counter_courses: Mapping<AccountId, Vec<CourseId>>,
+        /// the courses the accounts requested seats in, until they're settled <account, courseIds>
+        pub(crate) seat_request_courses: Mapping<AccountId, Vec<CourseId>>,
+        /// the old entries planned for the migration steps, until they're converted <(fromVersion, key)>
+        pub(crate) planned_entries: Mapping<(u32, [u8; 32]), ()>,
+    }
+
+    /// Emitted right before the contract is terminated
+    #[ink(event)]
+    pub struct Decommissioned {
+        /// the account receiving the remaining balance
+        #[ink(topic)]
+        pub(crate) beneficiary: AccountId,
+        /// the balance swept to the beneficiary
+        pub(crate) balance: Balance,
+    }
+
+    /// Emitted when a course is created, announcing its registration period
+    #[ink(event)]
+    pub struct RegistrationOpening {
+        /// the created course
+        #[ink(topic)]
+        pub(crate) course_id: CourseId,
+        /// registration opens at this time
+        pub(crate) opens_at: Timestamp,
+        /// registration closes at this time
+        pub(crate) closes_at: Timestamp,
+    }
+
+    /// Emitted by the first registration attempt after registration to the course opened
+    #[ink(event)]
+    pub struct RegistrationOpened {
+        /// the course open for registration
+        #[ink(topic)]
+        pub(crate) course_id: CourseId,
+    }
+
+    /// Emitted by the first registration attempt after registration to the course closed
+    #[ink(event)]
+    pub struct RegistrationClosed {
+        /// the course closed for registration
+        #[ink(topic)]
+        pub(crate) course_id: CourseId,
+    }
+
+    /// Emitted once per batch admission
+    #[ink(event)]
+    pub struct BatchAdmitted {
+        /// true if the accounts were admitted as teachers
+        pub(crate) as_teacher: bool,
+        /// the number of admitted accounts
+        pub(crate) admitted: u32,
+        /// the number of accounts that were already members
+        pub(crate) failed: u32,
+        /// the Keccak256 hash of the SCALE encoded admitted accounts, in batch order
+        pub(crate) batch_hash: [u8; 32],
+    }
+
+    /// Emitted per admitted account of a batch of at most `MAX_ITEMIZED_BATCH` admissions
+    #[ink(event)]
+    pub struct MemberAdmitted {
+        /// the admitted account
+        #[ink(topic)]
+        pub(crate) account: AccountId,
+        /// true if the account was admitted as a teacher
+        pub(crate) as_teacher: bool,
+    }
+
+    /// Emitted once per batch revocation
+    #[ink(event)]
+    pub struct BatchRevoked {
+        /// the number of revoked memberships
+        pub(crate) revoked: u32,
+        /// the number of accounts that couldn't be revoked
+        pub(crate) failed: u32,
+        /// the Keccak256 hash of the SCALE encoded revoked accounts, in batch order
+        pub(crate) batch_hash: [u8; 32],
+    }
+
+    /// Emitted per revoked account of a batch of at most `MAX_ITEMIZED_BATCH` revocations
+    #[ink(event)]
+    pub struct MemberRevoked {
+        /// the revoked account
+        #[ink(topic)]
+        pub(crate) account: AccountId,
+    }
+
+    /// Emitted when a student leaves a course another of their courses requires
+    #[ink(event)]
+    pub struct CorequisiteDropped {
+        /// the student leaving the course
+        #[ink(topic)]
+        pub(crate) student: AccountId,
+        /// the course the student left
+        pub(crate) course_id: CourseId,
+        /// the registered course that has the left course as corequisite
+        pub(crate) required_by: CourseId,
+    }
+
+    /// Emitted with a notice sealed off-chain to the recipient's notification key
+    #[ink(event)]
+    pub struct SealedNotice {
+        /// the member the notice is for
+        #[ink(topic)]
+        pub(crate) recipient: AccountId,
+        /// the notification key the notice is sealed to
+        pub(crate) key: [u8; 32],
+        /// the sealed notice, only the holder of the key can open it
+        pub(crate) sealed: Vec<u8>,
+    }
+
+    /// Emitted when an accommodated student registers with the accommodation
+    #[ink(event)]
+    pub struct AccommodatedRegistration {
+        /// the accommodated student
+        #[ink(topic)]
+        pub(crate) student: AccountId,
+        /// the course the student registered to
+        #[ink(topic)]
+        pub(crate) course_id: CourseId,
+        /// true if the student took a seat past the capacity of the course
+        pub(crate) over_capacity: bool,
+    }
+
+    /// Emitted when a student takes a free seat in a course
+    #[ink(event)]
+    pub struct Registered {
+        /// the registered student
+        #[ink(topic)]
+        pub(crate) student: AccountId,
+        /// the course of the seat
+        #[ink(topic)]
+        pub(crate) course_id: CourseId,
+        /// the seats taken in the course before the registration
+        pub(crate) seats_taken_before: u32,
+        /// the seats taken in the course after the registration
+        pub(crate) seats_taken_after: u32,
+    }
+
+    /// Emitted when the seat of a student in a course is freed
+    #[ink(event)]
+    pub struct Dropped {
+        /// the student leaving the course
+        #[ink(topic)]
+        pub(crate) student: AccountId,
+        /// the course of the seat
+        #[ink(topic)]
+        pub(crate) course_id: CourseId,
+        /// the seats taken in the course before the seat was freed
+        pub(crate) seats_taken_before: u32,
+        /// the seats taken in the course after the seat was freed
+        pub(crate) seats_taken_after: u32,
+    }
+
+    /// Emitted when a swap proposal is settled with a counter offer
+    #[ink(event)]
+    pub struct SwapSettled {
+        /// the settled proposal
+        #[ink(topic)]
+        pub(crate) proposal_id: ProposalId,
+        /// the course of the proposer's seat
+        pub(crate) offered_course_id: CourseId,
+        /// the seats taken in the offered course before the settlement
+        pub(crate) offered_seats_taken_before: u32,
+        /// the seats taken in the offered course after the settlement
+        pub(crate) offered_seats_taken_after: u32,
+        /// the course of the accepted counter offer's seat
+        pub(crate) accepted_course_id: CourseId,
+        /// the seats taken in the accepted course before the settlement
+        pub(crate) accepted_seats_taken_before: u32,
+        /// the seats taken in the accepted course after the settlement
+        pub(crate) accepted_seats_taken_after: u32,
+    }
+
+    /// Emitted when the owner acts for the teacher of a course, an override of the teacher
+    #[ink(event)]
+    pub struct TeacherOverride {
+        /// the course acted on
+        #[ink(topic)]
+        pub(crate) course_id: CourseId,
+        /// the teacher the owner acted for
+        #[ink(topic)]
+        pub(crate) teacher: AccountId,
+        /// the owner who acted
+        pub(crate) owner: AccountId,
+        /// the action taken
+        pub(crate) action: TeacherAction,
+    }
+
+    /// Emitted when the turned away students of a course reach `HIGH_DEMAND_THRESHOLD`
+    #[ink(event)]
+    pub struct HighDemand {
+        /// the course in demand
+        #[ink(topic)]
+        pub(crate) course_id: CourseId,
+        /// the seats of the course
+        pub(crate) capacity: u32,
+        /// the students turned away by the full course
+        pub(crate) capacity_rejections: u32,
+    }
+
+    /// Emitted when a student's records move to a new key
+    #[ink(event)]
+    pub struct KeyRotated {
+        /// the retired key
+        #[ink(topic)]
+        pub(crate) old: AccountId,
+        /// the key now holding the records
+        #[ink(topic)]
+        pub(crate) new: AccountId,
+    }
+
+    /// Emitted when a student books an office-hour slot
+    #[ink(event)]
+    pub struct OfficeHourBooked {
+        /// the course of the slot
+        pub(crate) course_id: CourseId,
+        /// the booked slot
+        pub(crate) slot_id: SlotId,
+        /// the student who booked the slot
+        #[ink(topic)]
+        pub(crate) student: AccountId,
+        /// the teacher holding the office hour
+        #[ink(topic)]
+        pub(crate) teacher: AccountId,
+    }
+
+    /// Emitted when a student cancels an office-hour booking
+    #[ink(event)]
+    pub struct OfficeHourCancelled {
+        /// the course of the slot
+        pub(crate) course_id: CourseId,
+        /// the freed slot
+        pub(crate) slot_id: SlotId,
+        /// the student who cancelled the booking
+        #[ink(topic)]
+        pub(crate) student: AccountId,
+        /// the teacher holding the office hour
+        #[ink(topic)]
+        pub(crate) teacher: AccountId,
     }
 
-    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
-    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
-    pub enum Error {
-       InsufficientPermissions,
-       NonexistentCourse,
-       CourseCapacityFull,
-       AlreadyRegistered,
-       NoRegistrations,
-       CourseAlreadyStarted,
-       NoSwappableRegistrations,
-       NoProposedSwap,
+    /// Emitted when the registrar exchanges the seats of two students
+    #[ink(event)]
+    pub struct AdminSwapped {
+        /// the registrar who performed the exchange
+        pub(crate) registrar: AccountId,
+        /// the student leaving `course_a`
+        #[ink(topic)]
+        pub(crate) student_a: AccountId,
+        /// the course `student_a` left
+        pub(crate) course_a: CourseId,
+        /// the student leaving `course_b`
+        #[ink(topic)]
+        pub(crate) student_b: AccountId,
+        /// the course `student_b` left
+        pub(crate) course_b: CourseId,
     }
 
     impl CourseReg {
@@ -82,38 +580,223 @@ mod course_reg {
         pub fn new(owner: AccountId) -> Self {
             ink_lang::utils::initialize_contract(|contract: &mut Self| {
                 contract.owner = owner;
-                contract.school_members.insert(&owner, &true);
+                contract.school_members.insert(owner, &true);
+                contract.version.set(&STORAGE_VERSION);
+                contract.school_config.set(&SchoolConfig::default());
             })
         }
 
         /// Default constructor that initializes the necessary values
         #[ink(constructor)]
+        #[allow(clippy::should_implement_trait)]
         pub fn default() -> Self {
             ink_lang::utils::initialize_contract(|contract: &mut Self| {
                 let caller = Self::env().caller();
                 contract.owner = caller;
-                contract.school_members.insert(&caller, &true);
+                contract.school_members.insert(caller, &true);
+                contract.version.set(&STORAGE_VERSION);
+                contract.school_config.set(&SchoolConfig::default());
             })
         }
 
+        /// Replaces the code of the contract while keeping its storage
+        ///
+        /// only the owner can upgrade, the new code checks the stored
+        /// version before touching any data
+        #[ink(message)]
+        pub fn set_code(&mut self, code_hash: [u8; 32]) -> Result<(), Error> {
+            self.set_code_inner(code_hash)
+        }
+
+        /// Returns the nonce the next guarded call of the account has to carry
+        #[ink(message)]
+        pub fn get_nonce(&self, account: AccountId) -> u64 {
+            self.get_nonce_inner(account)
+        }
+
+        /// Returns the state version, bumped by every successful guarded call
+        ///
+        /// calls built against this version can pass it in their guard
+        /// to be refused if anything changed in the meantime
+        #[ink(message)]
+        pub fn get_state_version(&self) -> u64 {
+            self.state_version.get_or_default()
+        }
+
+        /// Returns the storage layout version of the contract
+        #[ink(message)]
+        pub fn get_version(&self) -> u32 {
+            self.storage_version()
+        }
+
+        /// Returns the `FEATURE_...` flags of the optional flows this deployment supports
+        ///
+        /// front-ends and integrating contracts check the bit of a flow before calling it
+        #[ink(message)]
+        pub fn supported_features(&self) -> u64 {
+            SUPPORTED_FEATURES & !self.feature_flags.get_or_default().disabled
+        }
+
+        /// Disables features on this deployment, only the owner can set the flags
+        ///
+        /// the messages of a disabled feature fail with `FeatureDisabled`,
+        /// its reads keep working
+        #[ink(message)]
+        pub fn set_feature_flags(&mut self, flags: FeatureFlags, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.set_feature_flags_inner(flags))
+        }
+
+        /// Gets the features the owner disabled
+        #[ink(message)]
+        pub fn get_feature_flags(&self) -> FeatureFlags {
+            self.feature_flags.get_or_default()
+        }
+
+        /// Adds old entries to the plan of the pending migration step
+        ///
+        /// the owner feeds the keys written under the old layout in batches,
+        /// only the keys still holding an old entry are planned, and `migrate`
+        /// refuses the step while any planned entry is left unconverted
+        #[ink(message)]
+        pub fn plan_migration(&mut self,
+                              accounts: Vec<AccountId>,
+                              course_ids: Vec<CourseId>) -> Result<(), Error> {
+            self.plan_migration_inner(accounts, course_ids)
+        }
+
+        /// Returns the progress of the migration step upgrading from `version`
+        #[ink(message)]
+        pub fn get_migration_progress(&self, version: u32) -> MigrationProgress {
+            self.migration_progress.get(version).unwrap_or_default()
+        }
+
+        /// Converts the given storage entries to the layout of the next version
+        ///
+        /// mappings can't be iterated, so the owner feeds the keys written
+        /// under the old layout in batches before finishing the step with `migrate`
+        #[ink(message)]
+        pub fn migrate_entries(&mut self,
+                               accounts: Vec<AccountId>,
+                               course_ids: Vec<CourseId>) -> Result<(), Error> {
+            self.migrate_entries_inner(accounts, course_ids)
+        }
+
+        /// Finishes the pending migration step and returns the new version
+        ///
+        /// has to be called once per version step after the code upgrade,
+        /// once no planned entry of the step is left; messages are
+        /// refused until the version matches the code again
+        #[ink(message)]
+        pub fn migrate(&mut self) -> Result<u32, Error> {
+            self.migrate_inner()
+        }
+
+        /// Shuts the contract down for good, sweeping its balance to the beneficiary
+        ///
+        /// only the owner can decommission, and only once every course
+        /// has started, so no scheduled course loses its registrations
+        #[ink(message)]
+        pub fn decommission(&mut self, beneficiary: AccountId) -> Result<(), Error> {
+            self.decommission_inner(beneficiary)
+        }
+
+        /// Replaces the school-wide settings, only the owner can set them
+        #[ink(message)]
+        pub fn set_school_config(&mut self, config: SchoolConfig, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.set_school_config_inner(config))
+        }
+
+        /// Gets the school-wide settings
+        #[ink(message)]
+        pub fn get_school_config(&self) -> SchoolConfig {
+            self.school_config.get_or_default()
+        }
+
         /// Admits the account to school_members, as a Teacher
+        ///
+        /// fails with `AlreadyMember` if the account is a member, see `promote_to_teacher`
         #[ink(message)]
-        pub fn admit_as_teacher(&mut self, account: AccountId) -> Result<(), Error> {
-            if !self.is_owner() {
-                return Err(Error::InsufficientPermissions);
-            }
-            self.school_members.insert(&account, &true);
-            return Ok(());
+        pub fn admit_as_teacher(&mut self, account: AccountId, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.admit_as_teacher_inner(account))
         }
 
         /// Admits the account to school_members, as a student
+        ///
+        /// fails with `AlreadyMember` if the account is a member, see `demote_to_student`,
+        /// alumni and students whose membership isn't active yet can be admitted again
+        #[ink(message)]
+        pub fn admit_as_student(&mut self, account: AccountId, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.admit_as_student_inner(account))
+        }
+
+        /// Admits the account to school_members, as a student from `active_from`
+        ///
+        /// the student can't take seats in courses before the activation
+        #[ink(message)]
+        pub fn admit_as_student_from(&mut self,
+                                     account: AccountId,
+                                     active_from: Timestamp,
+                                     guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.admit_as_student_from_inner(account, active_from))
+        }
+
+        /// Makes the student a teacher, only the owner can promote
+        ///
+        /// the student's registrations are kept
+        #[ink(message)]
+        pub fn promote_to_teacher(&mut self, account: AccountId, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.promote_to_teacher_inner(account))
+        }
+
+        /// Makes the teacher a student, only the owner can demote
+        ///
+        /// fails while any of the teacher's courses hasn't ended, the ended
+        /// courses keep the teacher on record
+        #[ink(message)]
+        pub fn demote_to_student(&mut self, account: AccountId, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.demote_to_student_inner(account))
+        }
+
+        /// Sets the end of the validity of the member's status, only the owner can renew
+        #[ink(message)]
+        pub fn renew_membership(&mut self,
+                                account: AccountId,
+                                new_until: Timestamp,
+                                guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.renew_membership_inner(account, new_until))
+        }
+
+        /// Returns the end of the validity of the member's status, if it lapses
+        #[ink(message)]
+        pub fn get_membership_expiry(&self, account: AccountId) -> Option<Timestamp> {
+            self.membership_expiries.get(account)
+        }
+
+        /// Turns the student into an alumnus, only the owner can graduate students
+        ///
+        /// alumni keep their transcript but can no longer register or swap,
+        /// a new admission makes them students again
+        #[ink(message)]
+        pub fn graduate(&mut self, account: AccountId, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.graduate_inner(account))
+        }
+
+        /// Returns true if the account graduated
         #[ink(message)]
-        pub fn admit_as_student(&mut self, account: AccountId) -> Result<(), Error> {
-            if !self.is_owner() {
-                return Err(Error::InsufficientPermissions);
-            }
-            self.school_members.insert(&account, &false);
-            return Ok(());
+        pub fn is_alumni(&self, account: AccountId) -> bool {
+            self.alumni.contains(account)
+        }
+
+        /// Returns the graduation date of the alumnus
+        #[ink(message)]
+        pub fn get_graduation_date(&self, account: AccountId) -> Option<Timestamp> {
+            self.alumni.get(account)
+        }
+
+        /// Returns the admitted students whose membership isn't active yet
+        #[ink(message)]
+        pub fn get_pending_activations(&self) -> Vec<PendingActivation> {
+            self.get_pending_activations_inner()
         }
 
         /// Returns true if the account is a school_member
@@ -122,8 +805,29 @@ mod course_reg {
             self.is_school_member_inner(account)
         }
 
-        fn is_school_member_inner(&self, account: AccountId) -> bool {
-            self.school_members.contains(account)
+        /// Admits up to `MAX_BATCH_SIZE` accounts in one call, only the owner can admit
+        ///
+        /// returns the indexes of the accounts that were already members,
+        /// those keep their role
+        #[ink(message)]
+        pub fn admit_batch(&mut self,
+                           accounts: Vec<AccountId>,
+                           as_teacher: bool,
+                           guard: Option<CallGuard>) -> Result<Vec<u32>, Error> {
+            self.guarded(guard, |contract| contract.admit_batch_inner(accounts, as_teacher))
+        }
+
+        /// Revokes the membership of up to `MAX_BATCH_SIZE` accounts in one call,
+        /// only the owner can revoke
+        ///
+        /// an account still holding a seat, token, proposal, standing counter
+        /// offer or seat request isn't revoked; returns the indexes of the
+        /// accounts that couldn't be revoked, with the reason
+        #[ink(message)]
+        pub fn revoke_batch(&mut self,
+                            accounts: Vec<AccountId>,
+                            guard: Option<CallGuard>) -> Result<Vec<(u32, Error)>, Error> {
+            self.guarded(guard, |contract| contract.revoke_batch_inner(accounts))
         }
 
         /// Returns true if the account is a teacher
@@ -132,29 +836,63 @@ mod course_reg {
             self.is_teacher_inner(account)
         }
 
-        fn is_teacher_inner(&self, account: AccountId) -> bool {
-            self.school_members.get(&account).unwrap_or(false)
+        /// Sets or, with None, removes the teacher's exception to the course quota
+        ///
+        /// only the owner can override the quota
+        #[ink(message)]
+        pub fn set_course_quota_override(&mut self,
+                                         teacher: AccountId,
+                                         quota: Option<u32>,
+                                         guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.set_course_quota_override_inner(teacher, quota))
+        }
+
+        /// Returns the number of active courses the teacher can have
+        #[ink(message)]
+        pub fn get_course_quota(&self, teacher: AccountId) -> u32 {
+            self.course_quota(teacher)
+        }
+
+        /// Returns the number of the teacher's courses that haven't ended yet
+        #[ink(message)]
+        pub fn get_active_course_count(&self, teacher: AccountId) -> u32 {
+            self.active_course_counts.get(teacher).unwrap_or(0)
         }
 
-        /// Creates a university course
+        /// Creates a university course in a term
+        ///
+        /// the start date is a future timestamp in milliseconds within
+        /// the term, whose deadlines apply to the course. A course held in
+        /// a room can't have more seats than the room. Ending a course
+        /// frees its place in the teacher's quota
         #[ink(message)]
         pub fn create_course(&mut self,
-                             course_id: [u8;32],
+                             course_id: CourseId,
                              course_cap: u32,
-                             course_start:Timestamp) -> Result<(),Error> {
-            let caller = Self::env().caller();
-            if !self.is_teacher_inner(caller) {
-                return Err(Error::InsufficientPermissions);
-            }
-            let course = Course {
-                teacher: caller,
-                capacity: course_cap,
-                course_id: course_id.clone(),
-                start_date: course_start,
-                registrations: Vec::default(),
-            };
-            self.courses.insert(&course_id, &course);
-            return Ok(())
+                             course_start:Timestamp,
+                             term_id: TermId,
+                             room: Option<RoomId>,
+                             guard: Option<CallGuard>) -> Result<(),Error> {
+            self.guarded(guard, |contract| contract.create_course_inner(course_id, course_cap, course_start, term_id, room))
+        }
+
+        /// Derives the id of a named course in a term
+        ///
+        /// the dApps use it instead of hashing the names themselves
+        #[ink(message)]
+        pub fn derive_course_id(&self, name: Vec<u8>, term: [u8; 32]) -> CourseId {
+            Self::derive_course_id_inner(&name, term)
+        }
+
+        /// Creates up to `MAX_BATCH_SIZE` courses in one call, only teachers can create them
+        ///
+        /// every spec is validated on its own, the invalid ones are skipped
+        /// and the result of each spec is returned in the order of the batch
+        #[ink(message)]
+        pub fn create_courses(&mut self,
+                              batch: Vec<CourseSpec>,
+                              guard: Option<CallGuard>) -> Result<Vec<Result<(), Error>>, Error> {
+            self.guarded(guard, |contract| contract.create_courses_inner(batch))
         }
 
         /// registers the caller to the university course
@@ -162,420 +900,1264 @@ mod course_reg {
         /// the caller must be an admitted member and can't
         /// register to the same course multiple times
         #[ink(message)]
-        pub fn register_to_course(&mut self, course_id: [u8; 32]) -> Result<(), Error> {
-            let caller = Self::env().caller();
-            if !self.is_school_member_inner(caller) {
-                return Err(Error::InsufficientPermissions);
-            }
-            if !self.courses.contains(course_id) {
-                return Err(Error::NonexistentCourse);
-            }
-            let mut course = self.courses.get(course_id).unwrap();
-            let alerady_registered = course.registrations.len();
-            if alerady_registered >= course.capacity.try_into().unwrap() {
-                return Err(Error::CourseCapacityFull);
-            }
-            if course.registrations.contains(&caller) {
-                return Err(Error::AlreadyRegistered);
-            }
-            let current_time = Self::env().block_timestamp();
-            if course.start_date <= current_time {
-                return Err(Error::CourseAlreadyStarted);
-            }
-            course.registrations.push(caller);
-            self.courses.insert(&course_id, &course);
-                self.add_registration(course_id, caller);
-                Ok(())
-            }
-
-            /// creates a CourseRegistration token for the course with course_id
-            /// and the caller becomes the owner of the token
-            fn add_registration(&mut self, course_id: [u8;32], owner: AccountId) {
-                let course_reg = CourseRegistration { owner, course_id};
-                if !self.registrations.contains(owner) {
-                    let mut registrations = Vec::new();
-                    registrations.push(course_reg);
-                    self.registrations.insert(&owner, &registrations);
-                    return;
-                }
-                let mut registrations = self.registrations.get(owner).unwrap();
-                registrations.push(course_reg);
-                self.registrations.insert(&owner, &registrations);
-            }
-
-            /// Gets the caller's CourseRegistration tokens
-            #[ink(message)]
-            pub fn get_own_registrations(&self) -> Result<Vec<CourseRegistration>,Error> {
-                let caller = Self::env().caller();
-                if !self.registrations.contains(caller) || 
-                    self.registrations.get(caller).unwrap().len() == 0 {
-                    return Err(Error::NoRegistrations);
-                }
-                let registrations = self.registrations.get(caller).unwrap();
-                Ok(registrations)
-            }
-
-            /// Gets the info of a university course
-            #[ink(message)]
-            pub fn get_course_info(&self, course_id: [u8; 32]) -> Result<Course,Error> {
-                if !self.courses.contains(&course_id){
-                    return Err(Error::NonexistentCourse);
-                }
-                Ok(self.courses.get(course_id).unwrap())
-            }
-
-            /// Proposes a course registration swap
-            #[ink(message)]
-            pub fn propose_swap(&mut self, course_id: [u8; 32]) -> Result<(),Error> {
-                let caller = Self::env().caller();
-                if !self.registrations.contains(caller) {
-                    return Err(Error::NoSwappableRegistrations);
-                }
-                let mut registrations = self.registrations.get(caller).unwrap();
-                let course = registrations.iter().position(|x| x.course_id == course_id);
-                if course.is_none() {
-                    return Err(Error::NoSwappableRegistrations);
-                }
-                let course = course.unwrap();
-                let course = registrations.remove(course);
-                self.registrations.insert(&caller, &registrations);
-
-
-                let proposal = CourseRegistrationSwapProposal {
-                    offer: course,
-                    counter_offers: Vec::default(),
-                };
-                self.add_proposal(course_id, proposal);
-                Ok(())
-            }
-
-            /// creates a Swap proposal token for the given course
-            /// and places it in the list of proposals for that course
-            fn add_proposal(&mut self,course_id: [u8; 32], proposal: CourseRegistrationSwapProposal) {
-                if !self.swaps.contains(course_id) {
-                    let mut swaps = Vec::new();
-                    swaps.push(proposal);
-                    self.swaps.insert(&course_id, &swaps);
-                    return;
-                }
-                let mut swaps = self.swaps.get(course_id).unwrap();
-                swaps.push(proposal);
-                self.swaps.insert(&course_id, &swaps);
-            }
-
-            /// retrieve swap proposals for a given course_id
-            #[ink(message)]
-            pub fn get_proposed_swaps(&self, course_id: [u8; 32]) -> Result<Vec<CourseRegistrationSwapProposal>, Error> {
-                if !self.swaps.contains(course_id) {
-                    return Err(Error::NoProposedSwap);
-                }
-                let swaps = self.swaps.get(course_id);
-                if swaps.as_ref().unwrap().len() == 0 {
-                    return Err(Error::NoProposedSwap);
-                }
-                Ok(swaps.unwrap())
-            }
-
-            /// Place a counter offer on a swap proposal
-            #[ink(message)]
-            pub fn counter_swap_proposal(&mut self, 
-                                         course_id: [u8;32],
-                                         offerer: AccountId,
-                                         counter_course_id: [u8; 32]) -> Result<(), Error> {
-                let caller = Self::env().caller();
-                // first we need to verify if the caller has the required
-                // registration to swap
-                let caller_regs = self.get_own_registrations();
-
-                if caller_regs.is_err() {
-                    return Err(Error::NoProposedSwap);
-                }
-
-                let mut caller_regs = caller_regs.unwrap();
-                let exchange_course = caller_regs.iter().position(|reg| reg.course_id == counter_course_id);
-
-                if exchange_course.is_none() {
-                    return Err(Error::NoProposedSwap);
-                }
-
-                let exchange_course = exchange_course.unwrap();
-                let exchange_course = caller_regs.remove(exchange_course);
-                self.registrations.insert(&caller, &caller_regs); // caller's reg is removed
-
-                // find the proposal the counter offer belongs to
-                if !self.swaps.contains(course_id) {
-                    return Err(Error::NoProposedSwap);
-                }
-
-                let mut proposals = self.swaps.get(course_id).unwrap();
-
-                let found_prop = proposals.iter().position(|prop| prop.offer.owner == offerer);
-                if found_prop.is_none() {
-                    return Err(Error::NoProposedSwap);
-                }
-                let found_prop = found_prop.unwrap();
-                let prop = &mut proposals[found_prop];
-                prop.counter_offers.push(exchange_course);
-
-                // result is saved
-                self.swaps.insert(&course_id, &proposals);
-
-                Ok(())
-            }
-
-            /// accepts a swap counter offer to a swap proposed by the caller
-            #[ink(message)]
-            pub fn accept_counter_offer(&mut self, 
-                                        offered_course_id: [u8;32],
-                                        accepted_course_id: [u8;32],
-                                        accepted_owner: AccountId) -> Result<(), Error> {
-                let caller = Self::env().caller();
-                if !self.swaps.contains(offered_course_id) {
-                    return Err(Error::NoProposedSwap)
-                }
-
-                // find the proposal of the caller
-                let mut proposals = self.swaps.get(offered_course_id).unwrap();
-                let found_prop = proposals.iter().position(|prop| prop.offer.owner == caller);
-
-                if found_prop.is_none() {
-                    return Err(Error::NoProposedSwap)
-                }
-
-                // remove the proposal from the active proposals
-                let found_prop = found_prop.unwrap();
-                let mut found_prop = proposals.remove(found_prop);
-                self.swaps.insert(&offered_course_id, &proposals);
-                
-                // find the accepted counter offer
-                let found_counter = found_prop.counter_offers.iter()
-                                    .position(|counter_off| 
-                                              counter_off.owner == accepted_owner
-                                              && counter_off.course_id == accepted_course_id);
-                if found_counter.is_none() {
-                    return Err(Error::NoProposedSwap);
-                }
-                let found_counter = found_counter.unwrap();
-                let found_counter = found_prop.counter_offers.remove(found_counter);
-                if found_counter.owner != accepted_owner {
-                    return Err(Error::NoProposedSwap);
-                }
-
-                // perform the token swap
-                self.add_registration(accepted_course_id, caller);
-                self.add_registration(offered_course_id, found_counter.owner);
-
-                // change registrations in the course reg list
-                let rep_res = self.replace_registration_in_reg_list(accepted_course_id, accepted_owner, caller);
-                if rep_res.is_err() {
-                    return rep_res;
-                }
-                self.replace_registration_in_reg_list(offered_course_id, caller, accepted_owner)
-            }
-
-            fn replace_registration_in_reg_list(&mut self, course_id: [u8;32], replace:AccountId, with:AccountId) -> 
-                Result<(),Error> {
-                    let replace_in = self.courses.get(course_id);
-                    if replace_in.is_none() {
-                        return Err(Error::NoProposedSwap);
-                    }
-                    let mut replace_in = replace_in.unwrap();
-                    let reg = replace_in.registrations.iter().position(|acc_id| acc_id == &replace);
-                    if reg.is_none() {
-                        return Err(Error::NoProposedSwap);
-                    }
-                    replace_in.registrations[reg.unwrap()] = with;
-                    Ok(())
-                }
-
-            /// returns true if the caller is the owner of the contract
-            fn is_owner(&self) -> bool {
-                let caller = Self::env().caller();
-                return caller == self.owner;
-            }
-            /// returns teh Keccak256 hash of the input bytes
-            pub fn hash_keccak_256(input: &[u8]) -> [u8; 32] {
-                let mut output = <hash::Keccak256 as hash::HashOutput>::Type::default();
-                ink_env::hash_bytes::<hash::Keccak256>(input, &mut output);
-                output
-            }
-    }
+        pub fn register_to_course(&mut self, course_id: CourseId, guard: Option<CallGuard>) -> Result<Receipt, Error> {
+            self.receipted(guard, |contract| {
+                contract.register_to_course_inner(course_id)?;
+                Ok(ink_prelude::vec![course_id])
+            })
+        }
+
+        /// registers the caller to every course of the batch or to none of them
+        ///
+        /// a course's corequisites can be registered to in the same batch
+        #[ink(message)]
+        pub fn register_to_courses(&mut self, course_ids: Vec<CourseId>, guard: Option<CallGuard>) -> Result<Receipt, Error> {
+            self.receipted(guard, |contract| {
+                contract.register_to_courses_inner(course_ids.clone())?;
+                Ok(course_ids)
+            })
+        }
+
+        /// Adds the course to the caller's draft plan
+        #[ink(message)]
+        pub fn add_to_plan(&mut self, course_id: CourseId, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.add_to_plan_inner(course_id))
+        }
+
+        /// Removes the course from the caller's draft plan
+        #[ink(message)]
+        pub fn remove_from_plan(&mut self, course_id: CourseId, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.remove_from_plan_inner(course_id))
+        }
+
+        /// Returns the student's draft plan
+        #[ink(message)]
+        pub fn get_plan(&self, student: AccountId) -> Vec<CourseId> {
+            self.plans.get(student).unwrap_or_default()
+        }
+
+        /// Returns why the caller's planned courses would fail to register right now
+        ///
+        /// an empty list means `commit_plan` would succeed
+        #[ink(message)]
+        pub fn validate_plan(&self) -> Result<Vec<PlanIssue>, Error> {
+            self.validate_plan_inner()
+        }
+
+        /// Registers the caller to every planned course, or to none of them
+        #[ink(message)]
+        pub fn commit_plan(&mut self, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.commit_plan_inner())
+        }
+
+        /// Gets the caller's CourseRegistration tokens
+        #[ink(message)]
+        pub fn get_own_registrations(&self) -> Result<Vec<RegistrationView>,Error> {
+            self.get_own_registrations_inner()
+        }
+
+        /// Gets the info of a university course
+        #[ink(message)]
+        pub fn get_course_info(&self, course_id: CourseId) -> Result<CourseView,Error> {
+            self.get_course_info_inner(course_id)
+        }
+
+        /// Ends a started course, only its teacher can end it
+        ///
+        /// grades can be recorded once the course has ended
+        #[ink(message)]
+        pub fn end_course(&mut self, course_id: CourseId, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.end_course_inner(course_id))
+        }
+
+        /// Sets the credits a course is worth, only its teacher can set them
+        #[ink(message)]
+        pub fn set_course_credits(&mut self,
+                                  course_id: CourseId,
+                                  credits: u32,
+                                  guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.set_course_credits_inner(course_id, credits))
+        }
+
+        /// Sets the URI of a course's off-chain metadata and the hash of its content,
+        /// only its teacher can set them
+        ///
+        /// the clients verify the fetched syllabus, schedule or reading
+        /// list against the hash
+        #[ink(message)]
+        pub fn set_course_uri(&mut self,
+                              course_id: CourseId,
+                              uri: Vec<u8>,
+                              content_hash: [u8; 32],
+                              guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.set_course_uri_inner(course_id, uri, content_hash))
+        }
+
+        /// Gets the off-chain metadata of a course
+        #[ink(message)]
+        pub fn get_course_metadata(&self, course_id: CourseId) -> Option<CourseMetadata> {
+            self.course_metadata.get(course_id)
+        }
+
+        /// Sets the courses that have to be taken together with a course,
+        /// only its teacher can set them
+        ///
+        /// the students register to the course only if they are registered
+        /// to every corequisite or register to them in the same batch
+        #[ink(message)]
+        pub fn set_corequisites(&mut self,
+                                course_id: CourseId,
+                                corequisites: Vec<CourseId>,
+                                guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.set_corequisites_inner(course_id, corequisites))
+        }
+
+        /// Gets the corequisites of a course
+        #[ink(message)]
+        pub fn get_corequisites(&self, course_id: CourseId) -> Vec<CourseId> {
+            self.corequisites.get(course_id).unwrap_or_default()
+        }
+
+        /// Gets the credits a course is worth
+        #[ink(message)]
+        pub fn get_course_credits(&self, course_id: CourseId) -> u32 {
+            self.course_credits.get(course_id).unwrap_or(0)
+        }
+
+        /// Creates a term, only the owner can create one
+        ///
+        /// its deadlines fall within the term, which starts at most
+        /// `MAX_SCHEDULE_AHEAD` from now; returns the id of the term
+        #[ink(message)]
+        pub fn create_term(&mut self, term: Term, guard: Option<CallGuard>) -> Result<TermId, Error> {
+            self.guarded(guard, |contract| contract.create_term_inner(term))
+        }
+
+        /// Gets a term of the school
+        #[ink(message)]
+        pub fn get_term(&self, term_id: TermId) -> Option<Term> {
+            self.terms.get(term_id)
+        }
+
+        /// Gets the term of a course
+        #[ink(message)]
+        pub fn get_course_term(&self, course_id: CourseId) -> Option<TermId> {
+            self.course_terms.get(course_id)
+        }
+
+        /// Returns true if the term of the course has ended
+        #[ink(message)]
+        pub fn is_course_archived(&self, course_id: CourseId) -> bool {
+            self.is_course_archived_inner(course_id)
+        }
+
+        /// Returns true if the account is registered to the course
+        #[ink(message)]
+        pub fn is_registered(&self, course_id: CourseId, account: AccountId) -> bool {
+            self.is_registered_inner(course_id, account)
+        }
 
+        /// Gets a page of the students registered to a course
+        ///
+        /// returns at most `count` students following the `cursor` of the
+        /// previous page, or from the first student without a cursor; the
+        /// next page resumes in order even if the last student seen dropped
+        #[ink(message)]
+        pub fn get_course_registrations(&self,
+                                        course_id: CourseId,
+                                        cursor: Option<Cursor>,
+                                        count: u32) -> Result<Page<AccountId>, Error> {
+            self.get_course_registrations_inner(course_id, cursor, count)
+        }
+
+        /// Proposes a course registration swap
+        ///
+        /// the caller's registration for the course is locked in the
+        /// proposal, returns the id of the new proposal
+        #[ink(message)]
+        pub fn propose_swap(&mut self, course_id: CourseId, guard: Option<CallGuard>) -> Result<ProposalId,Error> {
+            self.guarded(guard, |contract| contract.propose_swap_inner(course_id))
+        }
+
+        /// retrieve a page of the swap proposals for a given course_id
+        ///
+        /// returns at most `count` proposals following the `cursor` of the
+        /// previous page, or from the oldest proposal without a cursor
+        #[ink(message)]
+        pub fn get_proposed_swaps(&self,
+                                  course_id: CourseId,
+                                  cursor: Option<Cursor>,
+                                  count: u32) -> Result<Page<SwapView>, Error> {
+            self.get_proposed_swaps_inner(course_id, cursor, count)
+        }
+
+        /// Takes the caller's proposal back, every escrowed token returns to its owner
+        ///
+        /// allowed at any time, also after swaps closed
+        #[ink(message)]
+        pub fn cancel_swap_proposal(&mut self, proposal_id: ProposalId, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.cancel_swap_proposal_inner(proposal_id))
+        }
+
+        /// Takes the caller's counter offer back from the proposal
+        #[ink(message)]
+        pub fn withdraw_counter_offer(&mut self,
+                                      proposal_id: ProposalId,
+                                      course_id: CourseId,
+                                      guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.withdraw_counter_offer_inner(proposal_id, course_id))
+        }
+
+        /// Returns the account's tokens locked in swap proposals
+        #[ink(message)]
+        pub fn get_escrowed(&self, account: AccountId) -> Vec<EscrowedToken> {
+            self.get_escrowed_inner(account)
+        }
+
+        /// Reports every check accepting the counter offer would fail right now
+        ///
+        /// nothing is changed, an empty list of violations means the
+        /// proposer can accept the counter offer
+        #[ink(message)]
+        pub fn preview_swap(&self,
+                            proposal_id: ProposalId,
+                            accepted_course_id: CourseId,
+                            accepted_owner: AccountId) -> Result<SwapPreview, Error> {
+            self.preview_swap_inner(proposal_id, accepted_course_id, accepted_owner)
+        }
+
+        /// Dry runs `register_to_courses` as the caller, fails with the error the call would fail with
+        #[ink(message)]
+        pub fn validate_register(&self, course_ids: Vec<CourseId>) -> Result<(), Error> {
+            self.validate_register_inner(course_ids)
+        }
 
+        /// Dry runs `create_course` as the caller, returns every rule of the course spec it breaks
+        ///
+        /// an empty list means the spec is valid, the teacher's course quota isn't checked
+        #[ink(message)]
+        pub fn validate_course_spec(&self, spec: CourseSpec) -> Result<Vec<Error>, Error> {
+            self.validate_course_spec_inner(spec)
+        }
 
-    /// Unit tests
-    #[cfg(test)]
-    mod tests {
-        use super::*;
-
-        use ink_lang as ink;
-        use ink_env;
-
-        fn set_next_caller(caller: AccountId) {
-            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(caller);
-        }
-
-        fn get_current_time() -> Timestamp {
-            let since_the_epoch = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .expect("Time went backwards");
-            since_the_epoch.as_secs()
-                + since_the_epoch.subsec_nanos() as u64 / 1_000_000_000 
-        }
-
-        pub fn hash_keccak_256(input: &[u8]) -> [u8; 32] {
-            let mut output = <hash::Keccak256 as hash::HashOutput>::Type::default();
-            ink_env::hash_bytes::<hash::Keccak256>(input, &mut output);
-            output
-        }
-
-        /// Teacher admission test
-        #[ink::test]
-        fn teacher_admission() {
-            let owner = AccountId::from([0x0;32]);
-            set_next_caller(owner);
-            let mut course_reg = CourseReg::new(owner);
-            let teacher = AccountId::from([0x1; 32]);
-            assert_eq!(course_reg.admit_as_teacher(teacher), Ok(()));
-            assert_eq!(course_reg.is_school_member(teacher), true);
-            assert_eq!(course_reg.is_teacher(teacher), true);
-        }
-
-        /// Student admission test
-        #[ink::test]
-        fn student_admission() {
-            let owner = AccountId::from([0x0;32]);
-            set_next_caller(owner);
-            let mut course_reg = CourseReg::new(owner);
-            let student = AccountId::from([0x1; 32]);
-            assert_eq!(course_reg.admit_as_student(student), Ok(()));
-            assert_eq!(course_reg.is_school_member(student), true);
-            assert_eq!(course_reg.is_teacher(student), false);
-        }
-
-        /// Course creation test
-        #[ink::test]
-        fn course_creation() {
-            let owner = AccountId::from([0x0;32]);
-            set_next_caller(owner);
-            let mut course_reg = CourseReg::new(owner);
-            let teacher = AccountId::from([0x1; 32]);
-            let course_name = "test_course".as_bytes();
-            let course_id = hash_keccak_256(course_name);
-            let course_cap:u32 = 10;
-            let start_time = get_current_time();
-
-            assert_eq!(course_reg.admit_as_teacher(teacher), Ok(()));
-            assert_eq!(course_reg.is_school_member(teacher), true);
-            assert_eq!(course_reg.is_teacher(teacher), true);
-            set_next_caller(teacher);
-            assert_eq!(course_reg.create_course(course_id, course_cap, start_time), Ok(()));
-
-            assert_ne!(course_reg.get_course_info(course_id), Err(Error::NonexistentCourse));
-        }
-
-        /// Course registration test
-        #[ink::test]
-        fn course_registration() {
-            let owner = AccountId::from([0x0;32]);
-            set_next_caller(owner);
-            let mut course_reg = CourseReg::new(owner);
-            let teacher = AccountId::from([0x1; 32]);
-            let student = AccountId::from([0x2; 32]);
-            let course_name = "test_course".as_bytes();
-            let course_id = hash_keccak_256(course_name);
-            let course_cap:u32 = 10;
-            let start_time = get_current_time();
-
-            assert_eq!(course_reg.admit_as_teacher(teacher), Ok(()));
-            assert_eq!(course_reg.admit_as_student(student), Ok(()));
-            assert_eq!(course_reg.is_school_member(teacher), true);
-            assert_eq!(course_reg.is_teacher(teacher), true);
-            assert_eq!(course_reg.is_school_member(student), true);
-            set_next_caller(teacher);
-            assert_eq!(course_reg.create_course(course_id, course_cap, start_time), Ok(()));
-            assert_ne!(course_reg.get_course_info(course_id), Err(Error::NonexistentCourse));
-            set_next_caller(student);
-
-            assert_eq!(course_reg.register_to_course(course_id),Ok(()));
-            assert_ne!(course_reg.get_own_registrations(), Err(Error::NoRegistrations));
-        }
-
-        /// Swap proposal creation test
-        #[ink::test]
-        fn swap_proposal_creation() {
-            let owner = AccountId::from([0x0;32]);
-            set_next_caller(owner);
-            let mut course_reg = CourseReg::new(owner);
-            let teacher = AccountId::from([0x1; 32]);
-            let student = AccountId::from([0x2; 32]);
-            let course_name = "test_course".as_bytes();
-            let course_id = hash_keccak_256(course_name);
-            let course_cap:u32 = 10;
-            let start_time = get_current_time();
-
-            assert_eq!(course_reg.admit_as_teacher(teacher), Ok(()));
-            assert_eq!(course_reg.admit_as_student(student), Ok(()));
-            assert_eq!(course_reg.is_school_member(teacher), true);
-            assert_eq!(course_reg.is_teacher(teacher), true);
-            assert_eq!(course_reg.is_school_member(student), true);
-            set_next_caller(teacher);
-            assert_eq!(course_reg.create_course(course_id, course_cap, start_time), Ok(()));
-            assert_ne!(course_reg.get_course_info(course_id), Err(Error::NonexistentCourse));
-            set_next_caller(student);
-            assert_eq!(course_reg.register_to_course(course_id), Ok(()));
-            assert_ne!(course_reg.get_own_registrations(), Err(Error::NoRegistrations));
-
-            assert_eq!(course_reg.propose_swap(course_id), Ok(()));
-            assert_eq!(course_reg.get_proposed_swaps(course_id).unwrap().len(),1); 
-            assert_eq!(course_reg.get_own_registrations(), Err(Error::NoRegistrations));
-        }
-
-        /// Full happy path test
-        #[ink::test]
-        fn accept_counter_offer() {
-            let owner = AccountId::from([0x0;32]);
-            set_next_caller(owner);
-            let mut course_reg = CourseReg::new(owner);
-            let teacher = AccountId::from([0x1; 32]);
-            let student1 = AccountId::from([0x2; 32]);
-            let student2 = AccountId::from([0x3; 32]);
-            let course_name1 = "test_course1".as_bytes();
-            let course_id1 = hash_keccak_256(course_name1);
-            let course_name2 = "test_course2".as_bytes();
-            let course_id2 = hash_keccak_256(course_name2);
-            let course_cap:u32 = 10;
-            let start_time = get_current_time();
-
-            assert_eq!(course_reg.admit_as_teacher(teacher), Ok(()));
-            assert_eq!(course_reg.admit_as_student(student1), Ok(()));
-            assert_eq!(course_reg.admit_as_student(student2), Ok(()));
-            assert_eq!(course_reg.is_school_member(teacher), true);
-            assert_eq!(course_reg.is_teacher(teacher), true);
-            assert_eq!(course_reg.is_school_member(student1), true);
-            assert_eq!(course_reg.is_school_member(student2), true);
-            set_next_caller(teacher);
-            assert_eq!(course_reg.create_course(course_id1, course_cap, start_time), Ok(()));
-            assert_ne!(course_reg.get_course_info(course_id1), Err(Error::NonexistentCourse));
-            assert_eq!(course_reg.create_course(course_id2, course_cap, start_time), Ok(()));
-            assert_ne!(course_reg.get_course_info(course_id2), Err(Error::NonexistentCourse));
-            set_next_caller(student1);
-            assert_eq!(course_reg.register_to_course(course_id1), Ok(()));
-            assert_ne!(course_reg.get_own_registrations(), Err(Error::NoRegistrations));
-            assert_eq!(course_reg.propose_swap(course_id1), Ok(()));
-            assert_eq!(course_reg.get_proposed_swaps(course_id1).unwrap().len(),1); 
-            set_next_caller(student2);
-            assert_eq!(course_reg.register_to_course(course_id2), Ok(()));
-            assert_ne!(course_reg.get_own_registrations(), Err(Error::NoRegistrations));
-
-            assert_eq!(course_reg.counter_swap_proposal(course_id1, student1, course_id2), Ok(()));  
-            set_next_caller(student2);
-            assert_eq!(course_reg.get_own_registrations(), Err(Error::NoRegistrations));
-
-            set_next_caller(student1);
-            assert_eq!(course_reg.accept_counter_offer(course_id1, course_id2, student2), Ok(()));
-            let pos = course_reg.get_own_registrations().unwrap().iter().position(|course| course.course_id == course_id2);
-            assert!(!pos.is_none());
+        /// Dry runs `propose_swap` as the caller, fails with the error the call would fail with
+        #[ink(message)]
+        pub fn validate_propose_swap(&self, course_id: CourseId) -> Result<(), Error> {
+            self.validate_propose_swap_inner(course_id)
+        }
+
+        /// Dry runs `counter_swap_proposal` as the caller, fails with the error the call would fail with
+        #[ink(message)]
+        pub fn validate_counter_swap_proposal(&self,
+                                              proposal_id: ProposalId,
+                                              counter_course_id: CourseId) -> Result<(), Error> {
+            self.validate_counter_swap_proposal_inner(proposal_id, counter_course_id)
+        }
+
+        /// Dry runs `accept_counter_offer` as the caller, fails with the error the call would fail with
+        #[ink(message)]
+        pub fn validate_accept_counter_offer(&self,
+                                             proposal_id: ProposalId,
+                                             accepted_course_id: CourseId,
+                                             accepted_owner: AccountId) -> Result<(), Error> {
+            self.validate_accept_counter_offer_inner(proposal_id, accepted_course_id, accepted_owner)
+        }
+
+        /// retrieve a single swap proposal
+        #[ink(message)]
+        pub fn get_swap_proposal(&self, proposal_id: ProposalId) -> Result<SwapView, Error> {
+            self.get_swap_proposal_inner(proposal_id)
+        }
+
+        /// Place a counter offer on a swap proposal
+        #[ink(message)]
+        pub fn counter_swap_proposal(&mut self,
+                                     proposal_id: ProposalId,
+                                     counter_course_id: CourseId,
+                                     guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.counter_swap_proposal_inner(proposal_id, counter_course_id))
+        }
+
+        /// accepts a swap counter offer to a swap proposed by the caller
+        #[ink(message)]
+        pub fn accept_counter_offer(&mut self,
+                                    proposal_id: ProposalId,
+                                    accepted_course_id: CourseId,
+                                    accepted_owner: AccountId,
+                                    guard: Option<CallGuard>) -> Result<Receipt, Error> {
+            self.receipted(guard, |contract| {
+                let offered_course_id = contract.accept_counter_offer_inner(proposal_id, accepted_course_id, accepted_owner)?;
+                Ok(ink_prelude::vec![offered_course_id, accepted_course_id])
+            })
+        }
+
+        /// Exchanges the seats of two students in their courses, only the registrar can
+        ///
+        /// for exchanges agreed with the registrar's office, bypasses the
+        /// swap proposals and their deadlines and is logged with an event
+        #[ink(message)]
+        pub fn admin_swap(&mut self,
+                          student_a: AccountId,
+                          course_a: CourseId,
+                          student_b: AccountId,
+                          course_b: CourseId,
+                          guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.admin_swap_inner(student_a, course_a, student_b, course_b))
+        }
+
+        /// Accepts up to `MAX_BATCH_SIZE` counter offers to swaps proposed by the caller
+        ///
+        /// every acceptance is settled on its own, the failing ones are skipped
+        /// and the result of each is returned in the order of the batch
+        #[ink(message)]
+        pub fn accept_counter_offers(&mut self,
+                                     batch: Vec<CounterOfferRef>,
+                                     guard: Option<CallGuard>) -> Result<Vec<Result<(), Error>>, Error> {
+            self.guarded(guard, |contract| contract.accept_counter_offers_inner(batch))
+        }
+
+        /// Records the commitment to a student's grade in an ended course
+        ///
+        /// only the teacher of the course can record, the commitment is
+        /// `grade_commitment(grade, salt)` and the salt is shared with the student
+        #[ink(message)]
+        pub fn record_grade(&mut self,
+                            course_id: CourseId,
+                            student: AccountId,
+                            grade_commitment: [u8; 32],
+                            guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.record_grade_inner(course_id, student, grade_commitment))
+        }
+
+        /// Emits the notice of a recorded grade sealed to the student's notification key
+        ///
+        /// only the teacher of the course can notify, the notice is sealed
+        /// off-chain, e.g. the grade and the salt of the commitment
+        #[ink(message)]
+        pub fn notify_grade(&mut self,
+                            course_id: CourseId,
+                            student: AccountId,
+                            sealed_notice: Vec<u8>,
+                            guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.notify_grade_inner(course_id, student, sealed_notice))
+        }
+
+        /// Discloses the caller's grade in a course
+        ///
+        /// the grade and the salt have to match the recorded commitment
+        #[ink(message)]
+        pub fn reveal_grade(&mut self,
+                            course_id: CourseId,
+                            salt: [u8; 32],
+                            grade: Grade,
+                            guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.reveal_grade_inner(course_id, salt, grade))
+        }
+
+        /// Gets the grade commitments of a student and the revealed grades
+        #[ink(message)]
+        pub fn get_transcript(&self, student: AccountId) -> Vec<GradeRecord> {
+            self.get_transcript_inner(student)
+        }
+
+        /// Gets the credits and the grade point average of a student
+        ///
+        /// only revealed grades count, the average is weighted by the
+        /// credits of the courses and uses the school's grade scale
+        #[ink(message)]
+        pub fn get_academic_summary(&self, student: AccountId) -> Result<AcademicSummary, Error> {
+            self.get_academic_summary_inner(student)
+        }
+
+        /// Opens an attendance session of a course, only its teacher can open one
+        ///
+        /// students check in between `starts` and `ends` with the code
+        /// whose Keccak256 hash is `code_hash`, returns the id of the session
+        #[ink(message)]
+        pub fn open_session(&mut self,
+                            course_id: CourseId,
+                            starts: Timestamp,
+                            ends: Timestamp,
+                            code_hash: [u8; 32],
+                            guard: Option<CallGuard>) -> Result<SessionId, Error> {
+            self.guarded(guard, |contract| contract.open_session_inner(course_id, starts, ends, code_hash))
+        }
+
+        /// Checks the caller in to an open session of a course they are registered to
+        #[ink(message)]
+        pub fn check_in(&mut self,
+                        course_id: CourseId,
+                        session_id: SessionId,
+                        code: Vec<u8>,
+                        guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.check_in_inner(course_id, session_id, code))
+        }
+
+        /// Gets the number of sessions a student attended out of the opened ones
+        #[ink(message)]
+        pub fn get_attendance(&self, course_id: CourseId, student: AccountId) -> Attendance {
+            self.get_attendance_inner(course_id, student)
+        }
+
+        /// Creates an assignment of a course, only its teacher can create one
+        ///
+        /// returns the id of the assignment
+        #[ink(message)]
+        pub fn create_assignment(&mut self,
+                                 course_id: CourseId,
+                                 deadline: Timestamp,
+                                 guard: Option<CallGuard>) -> Result<AssignmentId, Error> {
+            self.guarded(guard, |contract| contract.create_assignment_inner(course_id, deadline))
+        }
+
+        /// Anchors the hash of the caller's submission to an assignment
+        ///
+        /// submissions after the deadline are accepted and flagged as late
+        #[ink(message)]
+        pub fn submit_assignment(&mut self,
+                                 course_id: CourseId,
+                                 assignment_id: AssignmentId,
+                                 content_hash: [u8; 32],
+                                 guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.submit_assignment_inner(course_id, assignment_id, content_hash))
+        }
+
+        /// Gets an assignment of a course
+        #[ink(message)]
+        pub fn get_assignment(&self, course_id: CourseId, assignment_id: AssignmentId) -> Option<Assignment> {
+            self.assignments.get((course_id, assignment_id))
+        }
+
+        /// Gets a student's submission to an assignment
+        #[ink(message)]
+        pub fn get_submission(&self,
+                              course_id: CourseId,
+                              assignment_id: AssignmentId,
+                              student: AccountId) -> Option<Submission> {
+            self.submissions.get((course_id, assignment_id, student))
+        }
+
+        /// Submits the commitment to the caller's evaluation of an ended course
+        ///
+        /// open to the registered students for `EVALUATION_COMMIT_PERIOD`
+        /// after the course ended
+        #[ink(message)]
+        pub fn submit_evaluation(&mut self,
+                                 course_id: CourseId,
+                                 commitment: [u8; 32],
+                                 guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.submit_evaluation_inner(course_id, commitment))
+        }
+
+        /// Reveals the caller's evaluation, counting its score in the ratings
+        ///
+        /// open for `EVALUATION_REVEAL_PERIOD` after the commit period,
+        /// the score is between 1 and 5
+        #[ink(message)]
+        pub fn reveal_evaluation(&mut self,
+                                 course_id: CourseId,
+                                 score: u8,
+                                 comment_hash: [u8; 32],
+                                 salt: [u8; 32],
+                                 guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.reveal_evaluation_inner(course_id, score, comment_hash, salt))
+        }
+
+        /// Gets the rating of a course from the revealed evaluations
+        #[ink(message)]
+        pub fn get_course_rating(&self, course_id: CourseId) -> Rating {
+            self.course_ratings.get(course_id).unwrap_or_default().rating()
+        }
+
+        /// Gets the rating of a teacher from the revealed evaluations of their courses
+        #[ink(message)]
+        pub fn get_teacher_rating(&self, teacher: AccountId) -> Rating {
+            self.teacher_ratings.get(teacher).unwrap_or_default().rating()
+        }
+
+        /// Defines a degree program, only the owner can define one
+        ///
+        /// returns the id of the program
+        #[ink(message)]
+        pub fn create_program(&mut self, program: Program, guard: Option<CallGuard>) -> Result<ProgramId, Error> {
+            self.guarded(guard, |contract| contract.create_program_inner(program))
+        }
+
+        /// Assigns a student to a degree program, only the owner can assign
+        #[ink(message)]
+        pub fn assign_program(&mut self,
+                              student: AccountId,
+                              program_id: ProgramId,
+                              guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.assign_program_inner(student, program_id))
+        }
+
+        /// Gets a degree program
+        #[ink(message)]
+        pub fn get_program(&self, program_id: ProgramId) -> Option<Program> {
+            self.programs.get(program_id)
+        }
+
+        /// Gets the satisfied and missing requirements of a student's program
+        #[ink(message)]
+        pub fn get_program_progress(&self, student: AccountId) -> Result<ProgramProgress, Error> {
+            self.get_program_progress_inner(student)
+        }
+
+        /// Sets the academic standing of a student, only the owner can set it
+        ///
+        /// suspended students can't register to courses, students on probation
+        /// can only register up to `PROBATION_CREDIT_CAP` credits in progress
+        #[ink(message)]
+        pub fn set_standing(&mut self,
+                            student: AccountId,
+                            standing: AcademicStanding,
+                            guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.set_standing_inner(student, standing))
+        }
+
+        /// Gets the academic standing of a student
+        #[ink(message)]
+        pub fn get_standing(&self, student: AccountId) -> AcademicStanding {
+            self.standings.get(student).unwrap_or_default()
+        }
+
+        /// Publishes a supervision slot, a course with a single seat
+        /// on the topic, only teachers can publish one
+        ///
+        /// students apply for the slot instead of registering to it
+        #[ink(message)]
+        pub fn publish_supervision_slot(&mut self,
+                                        course_id: CourseId,
+                                        course_start: Timestamp,
+                                        term_id: TermId,
+                                        topic_hash: [u8; 32],
+                                        guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| {
+                contract.publish_supervision_slot_inner(course_id, course_start, term_id, topic_hash)
+            })
+        }
+
+        /// Applies for a supervision slot with the hash of a proposal
+        #[ink(message)]
+        pub fn apply_for_supervision(&mut self,
+                                     course_id: CourseId,
+                                     proposal_hash: [u8; 32],
+                                     guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.apply_for_supervision_inner(course_id, proposal_hash))
+        }
+
+        /// Accepts an applicant to the slot, only the teacher of the slot can accept
+        ///
+        /// the applicant gets the registration of the slot's single seat
+        #[ink(message)]
+        pub fn accept_supervision_applicant(&mut self,
+                                            course_id: CourseId,
+                                            student: AccountId,
+                                            guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.accept_supervision_applicant_inner(course_id, student))
+        }
+
+        /// Gets the topic hash of a supervision slot
+        #[ink(message)]
+        pub fn get_supervision_topic(&self, course_id: CourseId) -> Option<[u8; 32]> {
+            self.supervision_topics.get(course_id)
+        }
+
+        /// Gets the applications to a supervision slot
+        #[ink(message)]
+        pub fn get_supervision_applications(&self, course_id: CourseId) -> Result<Vec<SupervisionApplication>, Error> {
+            self.get_supervision_applications_inner(course_id)
+        }
+
+        /// Creates an exam of a course, only its teacher can create one
+        ///
+        /// returns the id of the exam
+        #[ink(message)]
+        pub fn create_exam(&mut self, course_id: CourseId, exam: Exam, guard: Option<CallGuard>) -> Result<ExamId, Error> {
+            self.guarded(guard, |contract| contract.create_exam_inner(course_id, exam))
+        }
+
+        /// Signs the caller up for an exam of a course they are registered to
+        ///
+        /// a student sits one exam of a course per period, resits are
+        /// open only after a failed first attempt
+        #[ink(message)]
+        pub fn sign_up_for_exam(&mut self, course_id: CourseId, exam_id: ExamId, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.sign_up_for_exam_inner(course_id, exam_id))
+        }
+
+        /// Marks a student's attempt at an exam after it was held,
+        /// only the teacher of the course can mark it
+        #[ink(message)]
+        pub fn record_exam_result(&mut self,
+                                  course_id: CourseId,
+                                  exam_id: ExamId,
+                                  student: AccountId,
+                                  passed: bool,
+                                  guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.record_exam_result_inner(course_id, exam_id, student, passed))
+        }
+
+        /// Gets an exam of a course
+        #[ink(message)]
+        pub fn get_exam(&self, course_id: CourseId, exam_id: ExamId) -> Option<Exam> {
+            self.exams.get((course_id, exam_id))
+        }
+
+        /// Gets the exam a student sits in a period
+        #[ink(message)]
+        pub fn get_exam_sitting(&self, course_id: CourseId, period: u32, student: AccountId) -> Option<ExamId> {
+            self.exam_sittings.get((course_id, period, student))
+        }
+
+        /// Creates a named cohort, only the owner can create one
+        ///
+        /// returns the id of the cohort
+        #[ink(message)]
+        pub fn create_cohort(&mut self, name: Vec<u8>, guard: Option<CallGuard>) -> Result<CohortId, Error> {
+            self.guarded(guard, |contract| contract.create_cohort_inner(name))
+        }
+
+        /// Adds a school member to a cohort, only the owner can add one
+        #[ink(message)]
+        pub fn add_to_cohort(&mut self, cohort_id: CohortId, account: AccountId, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.add_to_cohort_inner(cohort_id, account))
+        }
+
+        /// Removes a member from a cohort, only the owner can remove one
+        #[ink(message)]
+        pub fn remove_from_cohort(&mut self,
+                                  cohort_id: CohortId,
+                                  account: AccountId,
+                                  guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.remove_from_cohort_inner(cohort_id, account))
+        }
+
+        /// Gets a cohort
+        #[ink(message)]
+        pub fn get_cohort(&self, cohort_id: CohortId) -> Option<Cohort> {
+            self.cohorts.get(cohort_id)
+        }
+
+        /// Returns true if the account is a member of the cohort
+        #[ink(message)]
+        pub fn is_in_cohort(&self, cohort_id: CohortId, account: AccountId) -> bool {
+            self.in_cohort(cohort_id, account)
+        }
+
+        /// Offers the caller's seat in the course to whoever claims it first
+        #[ink(message)]
+        pub fn offer_seat_release(&mut self, course_id: CourseId, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.offer_seat_release_inner(course_id))
+        }
+
+        /// Withdraws the caller's offer to release their seat in the course
+        #[ink(message)]
+        pub fn withdraw_seat_release(&mut self, course_id: CourseId, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.withdraw_seat_release_inner(course_id))
+        }
+
+        /// Claims the seat the releaser offered in the course
+        ///
+        /// the releaser is dropped and the caller registered in the same call,
+        /// so no one else can take the seat in between; both the offer and
+        /// the claim close at the drop deadline of the course's term
+        #[ink(message)]
+        pub fn claim_released_seat(&mut self,
+                                   course_id: CourseId,
+                                   releaser: AccountId,
+                                   guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.claim_released_seat_inner(course_id, releaser))
+        }
+
+        /// Returns true if the releaser offers their seat in the course
+        #[ink(message)]
+        pub fn is_seat_released(&self, course_id: CourseId, releaser: AccountId) -> bool {
+            self.seat_releases.contains((course_id, releaser))
+        }
+
+        /// Anchors the hashes of the caller's off-chain profile and contact details
+        ///
+        /// the registrar verifies an account belongs to a student by
+        /// hashing the student's records, no personal data goes on-chain
+        #[ink(message)]
+        pub fn set_profile(&mut self,
+                           profile_hash: [u8; 32],
+                           contact_hash: [u8; 32],
+                           guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.set_profile_inner(profile_hash, contact_hash))
+        }
+
+        /// Gets the profile hashes of an account, only the owner
+        /// and the account itself can read them
+        #[ink(message)]
+        pub fn get_profile(&self, account: AccountId) -> Result<Option<Profile>, Error> {
+            self.get_profile_inner(account)
+        }
+
+        /// Gets the counts of a student's registration changes in a term
+        ///
+        /// the registrars reconcile them with the student information system
+        /// and look for course shopping
+        #[ink(message)]
+        pub fn get_term_activity(&self, student: AccountId, term_id: TermId) -> TermActivity {
+            self.term_activity.get((student, term_id)).unwrap_or_default()
+        }
+
+        /// Checks the consistency of the registration structures of the
+        /// listed courses and accounts and reports every violation found
+        ///
+        /// the courses with open proposals all have to be listed, their
+        /// proposals hold the escrowed tokens. Only builds with the `audit`
+        /// feature walk the storage, others fail with `InvariantChecksDisabled`
+        #[ink(message)]
+        pub fn check_invariants(&self,
+                                course_ids: Vec<CourseId>,
+                                accounts: Vec<AccountId>) -> Result<Vec<InvariantViolation>, Error> {
+            self.check_invariants_inner(&course_ids, &accounts)
+        }
+
+        /// Performs the transitions of the course that fell due, anyone can poke
+        ///
+        /// marks the passed registration boundaries and, once swaps are
+        /// closed, expires the open proposals and returns the escrowed
+        /// tokens to their owners. Returns the number of expired proposals
+        #[ink(message)]
+        pub fn poke(&mut self, course_id: CourseId, guard: Option<CallGuard>) -> Result<u32, Error> {
+            self.guarded(guard, |contract| contract.poke_inner(course_id))
+        }
+
+        /// Stores the hash of the current roster of the course, only its teacher can snapshot
+        #[ink(message)]
+        pub fn snapshot_roster(&mut self, course_id: CourseId, guard: Option<CallGuard>) -> Result<SnapshotId, Error> {
+            self.guarded(guard, |contract| contract.snapshot_roster_inner(course_id))
+        }
+
+        /// Returns the roster snapshot of the course
+        #[ink(message)]
+        pub fn get_roster_snapshot(&self, course_id: CourseId, snapshot_id: SnapshotId) -> Option<RosterSnapshot> {
+            self.roster_snapshots.get((course_id, snapshot_id))
+        }
+
+        /// Checks a claimed roster, in any order, against the snapshot
+        #[ink(message)]
+        pub fn verify_roster_snapshot(&self,
+                                      course_id: CourseId,
+                                      snapshot_id: SnapshotId,
+                                      roster: Vec<AccountId>) -> Result<bool, Error> {
+            self.verify_roster_snapshot_inner(course_id, snapshot_id, roster)
+        }
+
+        /// Sets the caller's public key, sensitive notices are sealed to it
+        #[ink(message)]
+        pub fn set_notification_key(&mut self, pubkey: [u8; 32], guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.set_notification_key_inner(pubkey))
+        }
+
+        /// Returns the member's notification key
+        #[ink(message)]
+        pub fn get_notification_key(&self, account: AccountId) -> Option<[u8; 32]> {
+            self.notification_keys.get(account)
+        }
+
+        /// Opens the course to non-members or, with None, closes it, only its teacher can set it
+        ///
+        /// the public seats are a separate pool with a mandatory fee
+        #[ink(message)]
+        pub fn set_public_enrollment(&mut self,
+                                     course_id: CourseId,
+                                     enrollment: Option<PublicEnrollment>,
+                                     guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.set_public_enrollment_inner(course_id, enrollment))
+        }
+
+        /// Takes a public seat of the course, transferring exactly its fee
+        ///
+        /// anyone can register, once per `PUBLIC_REGISTRATION_COOLDOWN`;
+        /// a failed registration keeps nothing, the call is reverted on `Err`
+        /// together with the transfer of the fee
+        #[ink(message, payable)]
+        pub fn register_as_public(&mut self, course_id: CourseId, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.register_as_public_inner(course_id))
+        }
+
+        /// Returns the public pool of the course, if it's open to the public
+        #[ink(message)]
+        pub fn get_public_enrollment(&self, course_id: CourseId) -> Option<PublicEnrollment> {
+            self.public_enrollments.get(course_id)
+        }
+
+        /// Returns the number of public registrants of the course
+        #[ink(message)]
+        pub fn get_public_count(&self, course_id: CourseId) -> u32 {
+            self.public_counts.get(course_id).unwrap_or(0)
+        }
+
+        /// Returns true if the account took a public seat of the course
+        #[ink(message)]
+        pub fn is_public_registrant(&self, course_id: CourseId, account: AccountId) -> bool {
+            self.public_registrations.contains((course_id, account))
+        }
+
+        /// Attaches the hash of a question to the course or, with None, removes it
+        ///
+        /// only the teacher of the course can set it, students register
+        /// to the course with `register_with_answer` afterwards
+        #[ink(message)]
+        pub fn set_registration_question(&mut self,
+                                         course_id: CourseId,
+                                         question_hash: Option<[u8; 32]>,
+                                         guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.set_registration_question_inner(course_id, question_hash))
+        }
+
+        /// Returns the hash of the course's registration question
+        #[ink(message)]
+        pub fn get_registration_question(&self, course_id: CourseId) -> Option<[u8; 32]> {
+            self.registration_questions.get(course_id)
+        }
+
+        /// Registers the caller to the course, with the hash of the answer to its question
+        #[ink(message)]
+        pub fn register_with_answer(&mut self,
+                                    course_id: CourseId,
+                                    answer_hash: [u8; 32],
+                                    guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.register_with_answer_inner(course_id, answer_hash))
+        }
+
+        /// Returns a page of the answer hashes of the registered students, only the teacher can read them
+        ///
+        /// returns at most `count` answers following the `cursor` of the
+        /// previous page, in roster order
+        #[ink(message)]
+        pub fn get_registration_answers(&self,
+                                        course_id: CourseId,
+                                        cursor: Option<Cursor>,
+                                        count: u32) -> Result<Page<RegistrationAnswer>, Error> {
+            self.get_registration_answers_inner(course_id, cursor, count)
+        }
+
+        /// Sets whether the student is accommodated, only the owner can set it
+        #[ink(message)]
+        pub fn set_accommodation(&mut self,
+                                 student: AccountId,
+                                 accommodated: bool,
+                                 guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.set_accommodation_inner(student, accommodated))
+        }
+
+        /// Returns true if the student is accommodated
+        #[ink(message)]
+        pub fn has_accommodation(&self, student: AccountId) -> bool {
+            self.accommodations.contains(student)
+        }
+
+        /// Registers the accommodated caller to a required course
+        ///
+        /// past a full capacity the student takes one of the school's
+        /// accommodation buffer seats, every such registration is logged
+        #[ink(message)]
+        pub fn register_with_accommodation(&mut self, course_id: CourseId, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.register_with_accommodation_inner(course_id))
+        }
+
+        /// Sets the seats the course can be swapped against, only the owner can set it
+        #[ink(message)]
+        pub fn set_swap_policy(&mut self,
+                               course_id: CourseId,
+                               policy: SwapPolicy,
+                               guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.set_swap_policy_inner(course_id, policy))
+        }
+
+        /// Returns the swap policy of the course
+        #[ink(message)]
+        pub fn get_swap_policy(&self, course_id: CourseId) -> SwapPolicy {
+            self.swap_policies.get(course_id).unwrap_or_default()
+        }
+
+        /// Sets or clears the department of the course, only the owner can set it
+        #[ink(message)]
+        pub fn set_course_department(&mut self,
+                                     course_id: CourseId,
+                                     department: Option<DepartmentId>,
+                                     guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.set_course_department_inner(course_id, department))
+        }
+
+        /// Returns the department of the course
+        #[ink(message)]
+        pub fn get_course_department(&self, course_id: CourseId) -> Option<DepartmentId> {
+            self.course_departments.get(course_id)
+        }
+
+        /// Returns how the swap proposals of the account ended
+        #[ink(message)]
+        pub fn get_swap_reputation(&self, account: AccountId) -> SwapReputation {
+            self.swap_reputations.get(account).unwrap_or_default()
+        }
+
+        /// Exports a chunk of a storage section, only the owner can export
+        ///
+        /// the chunks of a section are read from cursor 0 on, following
+        /// `next_cursor`, a backup is consistent if all of its chunks
+        /// carry the same manifest hash
+        #[ink(message)]
+        pub fn export_state(&self, section: StateSection, cursor: u64) -> Result<StateChunk, Error> {
+            self.export_state_inner(section, cursor)
+        }
+
+        /// Returns true while the legacy records can be imported
+        #[ink(message)]
+        pub fn is_bootstrap_mode(&self) -> bool {
+            !self.bootstrap_finished.get_or_default()
+        }
+
+        /// Imports up to `MAX_BATCH_SIZE` members of a legacy system, only the owner can import
+        ///
+        /// returns the result of each member in the order of the batch
+        #[ink(message)]
+        pub fn import_members(&mut self,
+                              batch: Vec<MemberImport>,
+                              guard: Option<CallGuard>) -> Result<Vec<Result<(), Error>>, Error> {
+            self.guarded(guard, |contract| contract.import_members_inner(batch))
+        }
+
+        /// Imports up to `MAX_BATCH_SIZE` courses of a legacy system, only the owner can import
+        ///
+        /// the courses may have started, the result of each course is
+        /// returned in the order of the batch
+        #[ink(message)]
+        pub fn import_courses(&mut self,
+                              batch: Vec<CourseImport>,
+                              guard: Option<CallGuard>) -> Result<Vec<Result<(), Error>>, Error> {
+            self.guarded(guard, |contract| contract.import_courses_inner(batch))
+        }
+
+        /// Imports up to `MAX_BATCH_SIZE` registrations of a legacy system, only the owner can import
+        ///
+        /// the registration periods may have passed, the result of each
+        /// registration is returned in the order of the batch
+        #[ink(message)]
+        pub fn import_registrations(&mut self,
+                                    batch: Vec<RegistrationImport>,
+                                    guard: Option<CallGuard>) -> Result<Vec<Result<(), Error>>, Error> {
+            self.guarded(guard, |contract| contract.import_registrations_inner(batch))
+        }
+
+        /// Seals the imports of the legacy records for good, only the owner can seal them
+        #[ink(message)]
+        pub fn finish_bootstrap(&mut self, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.finish_bootstrap_inner())
+        }
+
+        /// Sets or clears the hook contract of the course, only the owner can set it
+        ///
+        /// the hook implements `RegistrationHook`, it's asked before every
+        /// registration to the course and told after every seat filled
+        #[ink(message)]
+        pub fn set_registration_hook(&mut self,
+                                     course_id: CourseId,
+                                     hook: Option<AccountId>,
+                                     guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.set_registration_hook_inner(course_id, hook))
+        }
+
+        /// Returns the hook contract of the course
+        #[ink(message)]
+        pub fn get_registration_hook(&self, course_id: CourseId) -> Option<AccountId> {
+            self.registration_hooks.get(course_id)
+        }
+
+        /// Takes an action of the teacher of the course in their stead, only the owner can act
+        ///
+        /// a break-glass path for teachers who lost their keys, every
+        /// use is logged with a `TeacherOverride` event
+        #[ink(message)]
+        pub fn act_as_teacher(&mut self,
+                              course_id: CourseId,
+                              action: TeacherAction,
+                              guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.act_as_teacher_inner(course_id, action))
+        }
+
+        /// Moves a student's membership, registrations and records from the old key to the new one
+        ///
+        /// approved by the registrar or sent by the old key, the new key
+        /// can't be a member or hold registrations
+        #[ink(message)]
+        pub fn rotate_key(&mut self, old: AccountId, new: AccountId, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.rotate_key_inner(old, new))
+        }
+
+        /// Freezes the student's registration to the course, only the registrar can freeze it
+        ///
+        /// a frozen seat can't be swapped, released or handed over, the
+        /// student's other registrations aren't touched
+        #[ink(message)]
+        pub fn freeze_registration(&mut self,
+                                   student: AccountId,
+                                   course_id: CourseId,
+                                   guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.freeze_registration_inner(student, course_id))
+        }
+
+        /// Unfreezes the student's registration to the course, only the registrar can unfreeze it
+        #[ink(message)]
+        pub fn unfreeze_registration(&mut self,
+                                     student: AccountId,
+                                     course_id: CourseId,
+                                     guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.unfreeze_registration_inner(student, course_id))
+        }
+
+        /// Returns true if the student's registration to the course is frozen
+        #[ink(message)]
+        pub fn is_registration_frozen(&self, student: AccountId, course_id: CourseId) -> bool {
+            self.frozen_registrations.contains((course_id, student))
+        }
+
+        /// Returns the number of swaps the two accounts settled with each other in the term
+        #[ink(message)]
+        pub fn get_swap_pair_count(&self, a: AccountId, b: AccountId, term_id: TermId) -> u32 {
+            self.get_swap_pair_count_inner(a, b, term_id)
+        }
+
+        /// Records that the full course turned the caller away, once per student
+        ///
+        /// a rejected registration is reverted, so it can't count itself; the
+        /// rejection counts only while the caller could register but for the
+        /// capacity, so registration is open and the caller holds no seat
+        #[ink(message)]
+        pub fn record_capacity_rejection(&mut self, course_id: CourseId, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.record_capacity_rejection_inner(course_id))
+        }
+
+        /// Returns the interest in the course the seats couldn't meet
+        #[ink(message)]
+        pub fn get_demand_metrics(&self, course_id: CourseId) -> Result<DemandMetrics, Error> {
+            self.get_demand_metrics_inner(course_id)
+        }
+
+        /// Returns the commitment to the student's schedule in the term
+        ///
+        /// the Keccak256 hash of the SCALE encoded `(SCHEDULE_DOMAIN, student, term_id, entries)`,
+        /// where the entries are the student's `ScheduleEntry`s of the term sorted by course id
+        #[ink(message)]
+        pub fn get_schedule_commitment(&self, student: AccountId, term_id: TermId) -> Result<[u8; 32], Error> {
+            self.get_schedule_commitment_inner(student, term_id)
+        }
+
+        /// Counters every future proposal of the wanted course with the caller's
+        /// registration to the offered course, replaces an earlier standing offer
+        ///
+        /// the counter offer is made inside `propose_swap` and skipped
+        /// if it fails the checks of `counter_swap_proposal`
+        #[ink(message)]
+        pub fn set_standing_counter(&mut self,
+                                    wanted_course_id: CourseId,
+                                    offered_course_id: CourseId,
+                                    guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.set_standing_counter_inner(wanted_course_id, offered_course_id))
+        }
+
+        /// Cancels the caller's standing counter offer for the wanted course
+        #[ink(message)]
+        pub fn cancel_standing_counter(&mut self, wanted_course_id: CourseId, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.cancel_standing_counter_inner(wanted_course_id))
+        }
+
+        /// Returns the course the student's standing offer counters the wanted course with
+        #[ink(message)]
+        pub fn get_standing_counter(&self, student: AccountId, wanted_course_id: CourseId) -> Option<CourseId> {
+            self.standing_counters.get((wanted_course_id, student))
+        }
+
+        /// Publishes office-hour slots of the course, each for a single student,
+        /// only the teacher of the course can publish them
+        ///
+        /// returns the ids of the slots, in the order of the windows
+        #[ink(message)]
+        pub fn publish_office_hours(&mut self,
+                                    course_id: CourseId,
+                                    windows: Vec<OfficeHourWindow>,
+                                    guard: Option<CallGuard>) -> Result<Vec<SlotId>, Error> {
+            self.guarded(guard, |contract| contract.publish_office_hours_inner(course_id, windows))
+        }
+
+        /// Books the office-hour slot for the caller, a registered student
+        /// of the course, who can hold one booking of the course at a time
+        #[ink(message)]
+        pub fn book_office_hour(&mut self, course_id: CourseId, slot_id: SlotId, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.book_office_hour_inner(course_id, slot_id))
+        }
+
+        /// Cancels the caller's office-hour booking of the course before the slot starts
+        #[ink(message)]
+        pub fn cancel_office_hour(&mut self, course_id: CourseId, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.cancel_office_hour_inner(course_id))
+        }
+
+        /// Returns a page of the office-hour slots of the course, in publishing order
+        ///
+        /// returns at most `count` slots following the `cursor` of the previous page
+        #[ink(message)]
+        pub fn get_office_hours(&self,
+                                course_id: CourseId,
+                                cursor: Option<Cursor>,
+                                count: u32) -> Result<Page<OfficeHourSlot>, Error> {
+            self.get_office_hours_inner(course_id, cursor, count)
+        }
+
+        /// Adds a room or changes its seats, only the owner can set it
+        ///
+        /// fails if a course held in the room has more seats than the new capacity
+        #[ink(message)]
+        pub fn set_room(&mut self, room_id: RoomId, capacity: u32, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.set_room_inner(room_id, capacity))
+        }
+
+        /// Returns the seats of the room
+        #[ink(message)]
+        pub fn get_room_capacity(&self, room_id: RoomId) -> Option<u32> {
+            self.rooms.get(room_id)
+        }
+
+        /// Moves the course to a room that seats its capacity, or out of any room with `None`,
+        /// only the teacher of the course or the owner can move it
+        #[ink(message)]
+        pub fn set_course_room(&mut self,
+                               course_id: CourseId,
+                               room_id: Option<RoomId>,
+                               guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.set_course_room_inner(course_id, room_id))
+        }
+
+        /// Returns the room the course is held in
+        #[ink(message)]
+        pub fn get_course_room(&self, course_id: CourseId) -> Option<RoomId> {
+            self.course_rooms.get(course_id)
+        }
+
+        /// Returns the name of the grade on the school's scale, `None` on a numeric scale
+        #[ink(message)]
+        pub fn get_grade_label(&self, grade: Grade) -> Option<Vec<u8>> {
+            self.get_grade_label_inner(grade)
+        }
+
+        /// Opens registration to an announced course before its term starts,
+        /// only the teacher of the course or the owner can open it
+        #[ink(message)]
+        pub fn open_registration(&mut self, course_id: CourseId, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.open_registration_inner(course_id))
+        }
+
+        /// Closes registration to the course before its add deadline,
+        /// only the teacher of the course or the owner can close it
+        ///
+        /// swaps stay open until the swap deadline of the term
+        #[ink(message)]
+        pub fn close_registration(&mut self, course_id: CourseId, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.close_registration_inner(course_id))
+        }
+
+        /// Cleans up the courses of the term after it's over, only the owner can close it
+        ///
+        /// ends the courses their teachers didn't end, expires their open
+        /// proposals and drops their standing counter offers, returns the
+        /// number of expired proposals of each course, in the order of the batch
+        #[ink(message)]
+        pub fn close_term(&mut self,
+                          term_id: TermId,
+                          course_ids: Vec<CourseId>,
+                          guard: Option<CallGuard>) -> Result<Vec<Result<u32, Error>>, Error> {
+            self.guarded(guard, |contract| contract.close_term_inner(term_id, course_ids))
+        }
+
+        /// Returns a page of the open proposals offering the `offering` course, that
+        /// the proposer could take the `wanting` course for, made after `newer_than`
+        ///
+        /// `total` counts the proposals searched, before the `wanting` and
+        /// `newer_than` filters, without an offered course a page walks at most
+        /// `MAX_SWAP_SCAN` proposal ids, so it can be short and still have a `next_cursor`
+        #[ink(message)]
+        pub fn find_swaps(&self,
+                          offering: Option<CourseId>,
+                          wanting: Option<CourseId>,
+                          newer_than: Option<Timestamp>,
+                          cursor: Option<Cursor>,
+                          count: u32) -> Result<Page<SwapView>, Error> {
+            self.find_swaps_inner(offering, wanting, newer_than, cursor, count)
+        }
+
+        /// Sets the order of the registrations that arrive when registration to
+        /// the course opens, only the teacher of the course and the owner can set it
+        ///
+        /// the policy is fixed once registration opened
+        #[ink(message)]
+        pub fn set_tie_break_policy(&mut self,
+                                    course_id: CourseId,
+                                    policy: TieBreakPolicy,
+                                    guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.set_tie_break_policy_inner(course_id, policy))
+        }
+
+        /// Returns the tie-break policy of the course
+        #[ink(message)]
+        pub fn get_tie_break_policy(&self, course_id: CourseId) -> TieBreakPolicy {
+            self.tie_break_policies.get(course_id).unwrap_or_default()
+        }
+
+        /// Requests a seat in the course during the collection window after
+        /// registration opens, registrations fail until the window passed
+        #[ink(message)]
+        pub fn request_seat(&mut self, course_id: CourseId, guard: Option<CallGuard>) -> Result<(), Error> {
+            self.guarded(guard, |contract| contract.request_seat_inner(course_id))
+        }
+
+        /// Returns the collected seat requests of the course, in request order
+        #[ink(message)]
+        pub fn get_seat_requests(&self, course_id: CourseId) -> Vec<AccountId> {
+            self.seat_requests.get(course_id).unwrap_or_default()
+        }
+
+        /// Seats the collected requests in the order of the course's policy,
+        /// anyone can settle once the collection window passed
+        ///
+        /// the first registration after the window settles them as well,
+        /// returns the number of seated requests
+        #[ink(message)]
+        pub fn settle_registrations(&mut self, course_id: CourseId, guard: Option<CallGuard>) -> Result<u32, Error> {
+            self.guarded(guard, |contract| contract.settle_registrations_inner(course_id))
+        }
+    }
+
+    impl CourseRegistryReader for CourseReg {
+        #[ink(message)]
+        fn is_member(&self, account: AccountId) -> bool {
+            self.is_school_member_inner(account)
+        }
+
+        #[ink(message)]
+        fn is_enrolled(&self, course_id: CourseId, account: AccountId) -> bool {
+            self.is_registered_inner(course_id, account)
+        }
+
+        #[ink(message)]
+        fn remaining_capacity(&self, course_id: CourseId) -> Option<u32> {
+            self.remaining_capacity_inner(course_id)
+        }
+
+        #[ink(message)]
+        fn course_state(&self, course_id: CourseId) -> Option<CourseState> {
+            self.course_state_inner(course_id)
+        }
+
+        #[ink(message)]
+        fn verify_enrollment(&self, student: AccountId, course_id: CourseId, as_of: Timestamp) -> bool {
+            self.verify_enrollment_inner(student, course_id, as_of)
         }
     }
 }
@@ -0,0 +1,540 @@
+//! Code upgrades, storage migrations and decommissioning of the contract
+
+use ink_lang::codegen::{EmitEvent, StaticEnv};
+use ink_prelude::vec::Vec;
+
+use crate::course_reg::{CourseReg, Decommissioned};
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn set_code_inner(&mut self, code_hash: [u8; 32]) -> Result<(), Error> {
+        self.require_role(Role::Owner)?;
+        ink_env::set_code_hash(&code_hash).map_err(|_| Error::CodeUpgradeFailed)
+    }
+
+    /// returns the layout version the storage was written with, the first
+    /// layout predates the version cell and leaves it empty
+    pub(crate) fn storage_version(&self) -> u32 {
+        self.version.get().unwrap_or(1)
+    }
+
+    /// fails if the storage was written with a different layout version
+    pub(crate) fn ensure_version(&self) -> Result<(), Error> {
+        if self.storage_version() != STORAGE_VERSION {
+            return Err(Error::StorageVersionMismatch);
+        }
+        Ok(())
+    }
+
+    /// fails unless a migration step is pending, returns the version it upgrades from
+    fn pending_migration(&self) -> Result<u32, Error> {
+        let version = self.storage_version();
+        if version >= STORAGE_VERSION {
+            return Err(Error::NoMigrationPending);
+        }
+        Ok(version)
+    }
+
+    /// adds the given keys that still hold an entry of the old layout to
+    /// the plan of the pending step, keys without one are skipped
+    pub(crate) fn plan_migration_inner(&mut self,
+                                       accounts: Vec<AccountId>,
+                                       course_ids: Vec<CourseId>) -> Result<(), Error> {
+        self.require_role(Role::Owner)?;
+        let version = self.pending_migration()?;
+        if accounts.is_empty() && course_ids.is_empty() {
+            return Err(Error::EmptyMigrationPlan);
+        }
+        let mut progress = self.migration_progress.get(version).unwrap_or_default();
+        let mut planned = progress.planned.unwrap_or(0);
+        for key in Self::migration_keys(&accounts, &course_ids) {
+            if self.holds_old_entry(version, key) && !self.planned_entries.contains((version, key)) {
+                self.planned_entries.insert((version, key), &());
+                planned = planned.checked_add(1).ok_or(Error::ArithmeticOverflow)?;
+            }
+        }
+        progress.planned = Some(planned);
+        self.migration_progress.insert(version, &progress);
+        Ok(())
+    }
+
+    pub(crate) fn migrate_entries_inner(&mut self,
+                                        accounts: Vec<AccountId>,
+                                        course_ids: Vec<CourseId>) -> Result<(), Error> {
+        self.require_role(Role::Owner)?;
+        let version = self.pending_migration()?;
+        self.convert_entries(version, &accounts, &course_ids);
+        let mut progress = self.migration_progress.get(version).unwrap_or_default();
+        for key in Self::migration_keys(&accounts, &course_ids) {
+            if self.planned_entries.contains((version, key)) && !self.holds_old_entry(version, key) {
+                self.planned_entries.remove((version, key));
+                progress.converted = progress.converted.saturating_add(1);
+            }
+        }
+        self.migration_progress.insert(version, &progress);
+        Ok(())
+    }
+
+    /// bumps the version once none of the planned entries of the pending step is left
+    pub(crate) fn migrate_inner(&mut self) -> Result<u32, Error> {
+        self.require_role(Role::Owner)?;
+        let version = self.pending_migration()?;
+        let progress = self.migration_progress.get(version).unwrap_or_default();
+        let planned = progress.planned.ok_or(Error::MigrationNotPlanned)?;
+        if progress.converted < planned {
+            return Err(Error::MigrationIncomplete { remaining: planned - progress.converted });
+        }
+        let version = version.checked_add(1).ok_or(Error::ArithmeticOverflow)?;
+        self.version.set(&version);
+        Ok(version)
+    }
+
+    /// returns the raw storage keys of the accounts and courses
+    fn migration_keys<'a>(accounts: &'a [AccountId],
+                          course_ids: &'a [CourseId]) -> impl Iterator<Item = [u8; 32]> + 'a {
+        accounts.iter().map(|account| *account.as_ref())
+            .chain(course_ids.iter().map(|course_id| course_id.0))
+    }
+
+    /// true if the key still holds an entry the step from the `from` layout converts
+    fn holds_old_entry(&self, from: u32, key: [u8; 32]) -> bool {
+        match from {
+            1 => self.registrations_v1.contains(AccountId::from(key)),
+            2 => self.courses_v2.contains(CourseId(key)),
+            3 => self.courses_v3.contains(CourseId(key)),
+            4 => self.swaps_v4.contains(CourseId(key)),
+            5 => self.courses.contains(CourseId(key)) && !self.migrated_courses.contains((5, CourseId(key))),
+            _ => false,
+        }
+    }
+
+    /// converts keyed entries from the `from` layout to the next one
+    ///
+    /// every layout change keeps its old types around (`...V1`) and adds
+    /// a converter here, and to `holds_old_entry`, for the version it upgrades from
+    fn convert_entries(&mut self, from: u32, accounts: &[AccountId], course_ids: &[CourseId]) {
+        match from {
+            1 => self.convert_registrations_v1(accounts),
+            2 => self.convert_courses_v2(course_ids),
+            3 => self.convert_courses_v3(course_ids),
+            4 => self.convert_swaps_v4(course_ids),
+            5 => self.convert_proposals_v5(course_ids),
+            _ => (),
+        }
+    }
+
+    /// moves the accounts' tokens from the per-owner Vec into the keyed storage
+    fn convert_registrations_v1(&mut self, accounts: &[AccountId]) {
+        for account in accounts {
+            if let Some(tokens) = self.registrations_v1.get(account) {
+                for token in tokens {
+                    self.mint(token.course_id, token.owner);
+                }
+                self.registrations_v1.remove(account);
+            }
+        }
+    }
+
+    /// counts the registrations of the courses and fills the enrollment set
+    fn convert_courses_v2(&mut self, course_ids: &[CourseId]) {
+        for course_id in course_ids {
+            if let Some(course) = self.courses_v2.get(course_id) {
+                for student in &course.registrations {
+                    self.enrollments.insert((*course_id, *student), &());
+                }
+                let converted = CourseV3 {
+                    teacher: course.teacher,
+                    course_id: course.course_id,
+                    capacity: course.capacity,
+                    enrolled_count: u32::try_from(course.registrations.len()).unwrap_or(u32::MAX),
+                    registrations: course.registrations,
+                    start_date: course.start_date,
+                };
+                self.courses_v3.insert(course_id, &converted);
+                self.courses_v2.remove(course_id);
+            }
+        }
+    }
+
+    /// moves the rosters of the courses into their own storage cells
+    fn convert_courses_v3(&mut self, course_ids: &[CourseId]) {
+        for course_id in course_ids {
+            if let Some(course) = self.courses_v3.get(course_id) {
+                let converted = Course {
+                    teacher: course.teacher,
+                    course_id: course.course_id,
+                    capacity: course.capacity,
+                    start_date: course.start_date,
+                    enrolled_count: course.enrolled_count,
+                };
+                self.courses.insert(course_id, &converted);
+                self.rosters.insert(course_id, &course.registrations);
+                self.courses_v3.remove(course_id);
+                self.count_legacy_course(course.teacher, course.start_date);
+            }
+        }
+    }
+
+    /// counts a course written before the teachers' active courses and the
+    /// latest course start were kept, every legacy course passes the V3
+    /// converter once, whichever layout it was created under
+    fn count_legacy_course(&mut self, teacher: AccountId, start_date: Timestamp) {
+        let active_count = self.active_course_counts.get(teacher).unwrap_or(0).saturating_add(1);
+        self.active_course_counts.insert(teacher, &active_count);
+        if start_date > self.latest_course_start.get_or_default() {
+            self.latest_course_start.set(&start_date);
+        }
+    }
+
+    /// assigns ids to the proposals of the courses and stores them by id
+    fn convert_swaps_v4(&mut self, course_ids: &[CourseId]) {
+        for course_id in course_ids {
+            if let Some(swaps) = self.swaps_v4.get(course_id) {
+                let mut index = self.course_proposals.get(course_id).unwrap_or_default();
+                for swap in swaps {
+                    let proposal_id = self.next_proposal_id.get_or_default();
+                    let proposal = CourseRegistrationSwapProposal {
+                        proposal_id,
+                        offer: swap.offer,
+                        counter_offers: swap.counter_offers,
+                    };
+                    self.next_proposal_id.set(&proposal_id.next().unwrap_or(proposal_id));
+                    index.push(proposal_id);
+                    self.proposals.insert(proposal_id, &proposal);
+                }
+                self.course_proposals.insert(course_id, &index);
+                self.swaps_v4.remove(course_id);
+            }
+        }
+    }
+
+    /// counts the open proposals of the courses and lists their tokens
+    /// in the escrow index, both are empty in the layout of version 5
+    ///
+    /// nothing old is removed here, so converted courses are marked
+    /// to keep a repeated batch from counting them twice
+    fn convert_proposals_v5(&mut self, course_ids: &[CourseId]) {
+        for course_id in course_ids {
+            if !self.courses.contains(course_id) || self.migrated_courses.contains((5, *course_id)) {
+                continue;
+            }
+            let proposal_ids = self.course_proposals.get(course_id).unwrap_or_default();
+            for proposal_id in &proposal_ids {
+                let proposal = match self.proposals.get(proposal_id) {
+                    Some(proposal) => proposal,
+                    None => continue,
+                };
+                self.lock_in_escrow(proposal.proposal_id, proposal.offer.owner);
+                for counter_offer in &proposal.counter_offers {
+                    self.lock_in_escrow(proposal.proposal_id, counter_offer.owner);
+                }
+            }
+            let open_proposal_count = self.open_proposal_count.get_or_default()
+                .saturating_add(u32::try_from(proposal_ids.len()).unwrap_or(u32::MAX));
+            self.open_proposal_count.set(&open_proposal_count);
+            self.migrated_courses.insert((5, *course_id), &());
+        }
+    }
+
+    pub(crate) fn decommission_inner(&mut self, beneficiary: AccountId) -> Result<(), Error> {
+        self.require_role(Role::Owner)?;
+        if self.latest_course_start.get_or_default() > Self::now() {
+            return Err(Error::CoursesStillScheduled);
+        }
+        Self::env().emit_event(Decommissioned {
+            beneficiary,
+            balance: Self::env().balance(),
+        });
+        Self::env().terminate_contract(beneficiary)
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+    use ink_prelude::vec::Vec;
+    use ink_primitives::Key;
+    use ink_storage::Mapping;
+    use ink_storage::traits::{allocate_spread_root, pull_spread_root, push_spread_root, SpreadAllocate, SpreadLayout};
+
+    /// The root storage of the first layout, written before the version cell existed
+    #[derive(SpreadLayout, SpreadAllocate)]
+    struct CourseRegV1 {
+        owner: AccountId,
+        school_members: Mapping<AccountId, bool>,
+        courses: Mapping<CourseId, CourseV2>,
+        swaps: Mapping<CourseId, Vec<SwapProposalV4>>,
+        registrations: Mapping<AccountId, Vec<CourseRegistration>>,
+    }
+
+    /// Code upgrade permission and version check test
+    #[ink::test]
+    fn code_upgrade() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        assert_eq!(course_reg.get_version(), STORAGE_VERSION);
+        assert_eq!(course_reg.supported_features() & FEATURE_SWAPS, FEATURE_SWAPS);
+        set_next_caller(student);
+        assert_eq!(course_reg.set_code([0x1; 32]), Err(Error::InsufficientPermissions));
+
+        // storage written by another version is refused until migrated
+        set_next_caller(owner);
+        course_reg.version.set(&(STORAGE_VERSION - 1));
+        assert_eq!(course_reg.admit_as_student(student, None), Err(Error::StorageVersionMismatch));
+    }
+
+    /// Storage migration test
+    #[ink::test]
+    fn storage_migration() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let missing_course_id = CourseId(hash_keccak_256("missing_course".as_bytes()));
+        assert_eq!(course_reg.migrate(), Err(Error::NoMigrationPending));
+
+        let course = Course { teacher: owner, course_id, capacity: 10, start_date: 0, enrolled_count: 0 };
+        course_reg.courses.insert(course_id, &course);
+        course_reg.version.set(&(STORAGE_VERSION - 1));
+        set_next_caller(student);
+        assert_eq!(course_reg.migrate(), Err(Error::InsufficientPermissions));
+        assert_eq!(course_reg.plan_migration(Vec::new(), ink_prelude::vec![course_id]),
+                   Err(Error::InsufficientPermissions));
+
+        // only the keys still holding an old entry are planned, once each
+        set_next_caller(owner);
+        assert_eq!(course_reg.migrate(), Err(Error::MigrationNotPlanned));
+        assert_eq!(course_reg.plan_migration(Vec::new(), Vec::new()), Err(Error::EmptyMigrationPlan));
+        for _ in 0..2 {
+            assert_eq!(course_reg.plan_migration(Vec::new(), ink_prelude::vec![course_id, missing_course_id]), Ok(()));
+        }
+        assert_eq!(course_reg.get_migration_progress(STORAGE_VERSION - 1), MigrationProgress { planned: Some(1), converted: 0 });
+
+        // the step can't be finished while a planned entry is left
+        assert_eq!(course_reg.migrate_entries(Vec::new(), ink_prelude::vec![missing_course_id]), Ok(()));
+        assert_eq!(course_reg.migrate(), Err(Error::MigrationIncomplete { remaining: 1 }));
+        assert_eq!(course_reg.migrate_entries(Vec::new(), ink_prelude::vec![course_id]), Ok(()));
+        assert_eq!(course_reg.migrate(), Ok(STORAGE_VERSION));
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+    }
+
+    /// Registration token migration from the per-owner Vec layout test
+    #[ink::test]
+    fn registration_token_migration() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let legacy = ink_prelude::vec![CourseRegistration { owner: student, course_id }];
+        course_reg.registrations_v1.insert(student, &legacy);
+        course_reg.version.set(&1);
+
+        assert_eq!(course_reg.plan_migration(ink_prelude::vec![student], Vec::new()), Ok(()));
+        assert_eq!(course_reg.migrate_entries(ink_prelude::vec![student], Vec::new()), Ok(()));
+        assert_eq!(course_reg.migrate(), Ok(2));
+        assert!(!course_reg.registrations_v1.contains(student));
+        set_next_caller(student);
+        let registration = RegistrationView { version: VIEW_VERSION, course_id, owner: student };
+        assert_eq!(course_reg.get_own_registrations(), Ok(ink_prelude::vec![registration]));
+    }
+
+    /// Course migration to counted enrollments and separate rosters test
+    #[ink::test]
+    fn course_enrollment_migration() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let legacy = CourseV2 {
+            teacher: owner,
+            course_id,
+            capacity: 10,
+            registrations: ink_prelude::vec![student],
+            start_date: 0,
+        };
+        course_reg.courses_v2.insert(course_id, &legacy);
+        course_reg.version.set(&2);
+
+        assert_eq!(course_reg.plan_migration(Vec::new(), ink_prelude::vec![course_id]), Ok(()));
+        assert_eq!(course_reg.migrate_entries(Vec::new(), ink_prelude::vec![course_id]), Ok(()));
+        assert_eq!(course_reg.migrate(), Ok(3));
+        assert_eq!(course_reg.plan_migration(Vec::new(), ink_prelude::vec![course_id]), Ok(()));
+        assert_eq!(course_reg.migrate_entries(Vec::new(), ink_prelude::vec![course_id]), Ok(()));
+        assert_eq!(course_reg.migrate(), Ok(4));
+        assert_eq!(course_reg.get_course_info(course_id).unwrap().enrolled_count, 1);
+        assert!(course_reg.is_registered(course_id, student));
+        assert_eq!(course_reg.get_course_registrations(course_id, None, 10).map(|page| page.items), Ok(ink_prelude::vec![student]));
+    }
+
+    /// Swap proposal migration to proposal ids test
+    #[ink::test]
+    fn swap_proposal_migration() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let other = AccountId::from([0x2; 32]);
+        let other_course_id = CourseId(hash_keccak_256("other_course".as_bytes()));
+        let legacy = SwapProposalV4 {
+            offer: CourseRegistration { owner: student, course_id },
+            counter_offers: ink_prelude::vec![CourseRegistration { owner: other, course_id: other_course_id }],
+        };
+        course_reg.swaps_v4.insert(course_id, &ink_prelude::vec![legacy]);
+        let course = Course { teacher: owner, course_id, capacity: 10, start_date: 0, enrolled_count: 1 };
+        course_reg.courses.insert(course_id, &course);
+        course_reg.version.set(&4);
+
+        assert_eq!(course_reg.plan_migration(Vec::new(), ink_prelude::vec![course_id]), Ok(()));
+        assert_eq!(course_reg.migrate_entries(Vec::new(), ink_prelude::vec![course_id]), Ok(()));
+        assert_eq!(course_reg.migrate(), Ok(5));
+
+        // the open proposals are counted and escrowed once however often a course is fed
+        assert_eq!(course_reg.plan_migration(Vec::new(), ink_prelude::vec![course_id]), Ok(()));
+        for _ in 0..2 {
+            assert_eq!(course_reg.migrate_entries(Vec::new(), ink_prelude::vec![course_id]), Ok(()));
+        }
+        assert_eq!(course_reg.migrate(), Ok(6));
+        assert_eq!(course_reg.open_proposal_count.get(), Some(1));
+        assert_eq!(course_reg.get_escrowed(student), ink_prelude::vec![EscrowedToken { proposal_id: ProposalId(0), course_id }]);
+        assert_eq!(course_reg.get_escrowed(other), ink_prelude::vec![EscrowedToken { proposal_id: ProposalId(0), course_id: other_course_id }]);
+        assert_eq!(course_reg.escrow_index.get(other), Some(ink_prelude::vec![ProposalId(0)]));
+        let proposal = course_reg.get_swap_proposal(ProposalId(0)).unwrap();
+        assert_eq!((proposal.course_id, proposal.proposer), (course_id, student));
+        assert_eq!(course_reg.get_proposed_swaps(course_id, None, 10).map(|page| page.items), Ok(ink_prelude::vec![proposal]));
+    }
+
+    /// Upgrade from the storage written by the first layout test
+    #[ink::test]
+    fn upgrade_from_first_layout() {
+        let owner = AccountId::from([0x0;32]);
+        let student = AccountId::from([0x1; 32]);
+        let other = AccountId::from([0x2; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let other_course_id = CourseId(hash_keccak_256("other_course".as_bytes()));
+        set_next_caller(owner);
+        let root_key = Key::from([0x0; 32]);
+        let mut old: CourseRegV1 = allocate_spread_root(&root_key);
+        old.owner = owner;
+        old.school_members.insert(owner, &true);
+        for (course_id, student) in [(course_id, student), (other_course_id, other)] {
+            old.school_members.insert(student, &false);
+            let course = CourseV2 {
+                teacher: owner,
+                course_id,
+                capacity: 10,
+                registrations: ink_prelude::vec![student],
+                start_date: get_start_time(),
+            };
+            old.courses.insert(course_id, &course);
+        }
+        old.registrations.insert(student, &ink_prelude::vec![CourseRegistration { owner: student, course_id }]);
+        old.registrations.insert(other, &Vec::<CourseRegistration>::new());
+        let proposal = SwapProposalV4 {
+            offer: CourseRegistration { owner: other, course_id: other_course_id },
+            counter_offers: Vec::new(),
+        };
+        old.swaps.insert(other_course_id, &ink_prelude::vec![proposal]);
+        push_spread_root(&old, &root_key);
+
+        // the new code loads the old root without touching the cells it added
+        let mut course_reg: CourseReg = pull_spread_root(&root_key);
+        assert_eq!(course_reg.get_version(), 1);
+        assert_eq!(course_reg.admit_as_student(owner, None), Err(Error::StorageVersionMismatch));
+        assert_eq!(course_reg.migrate(), Err(Error::MigrationNotPlanned));
+        assert_eq!(course_reg.plan_migration(ink_prelude::vec![owner, student, other], Vec::new()), Ok(()));
+        assert_eq!(course_reg.migrate_entries(ink_prelude::vec![student], Vec::new()), Ok(()));
+        assert_eq!(course_reg.migrate(), Err(Error::MigrationIncomplete { remaining: 1 }));
+        assert_eq!(course_reg.migrate_entries(ink_prelude::vec![student, other], Vec::new()), Ok(()));
+        assert_eq!(course_reg.get_migration_progress(1), MigrationProgress { planned: Some(2), converted: 2 });
+        assert_eq!(course_reg.migrate(), Ok(2));
+        for (version, course_ids) in [(3, ink_prelude::vec![course_id, other_course_id]),
+                                      (4, ink_prelude::vec![course_id, other_course_id]),
+                                      (5, ink_prelude::vec![course_id, other_course_id]),
+                                      (6, ink_prelude::vec![course_id, other_course_id])] {
+            assert_eq!(course_reg.plan_migration(Vec::new(), course_ids.clone()), Ok(()));
+            assert_eq!(course_reg.migrate_entries(Vec::new(), course_ids), Ok(()));
+            assert_eq!(course_reg.migrate(), Ok(version));
+        }
+        assert_eq!(course_reg.get_version(), STORAGE_VERSION);
+
+        assert!(course_reg.is_registered(course_id, student));
+        assert_eq!(course_reg.get_course_info(course_id).unwrap().enrolled_count, 1);
+        let proposal = course_reg.get_swap_proposal(ProposalId(0)).unwrap();
+        assert_eq!((proposal.course_id, proposal.proposer), (other_course_id, other));
+        assert_eq!(course_reg.open_proposal_count.get(), Some(1));
+        assert_eq!(course_reg.admit_as_student(AccountId::from([0x3; 32]), None), Ok(()));
+    }
+
+    /// Decommissioning test
+    #[ink::test]
+    fn decommission() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let teacher = AccountId::from([0x1; 32]);
+        let beneficiary = AccountId::from([0x2; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_teacher(teacher, None), Ok(()));
+        set_next_caller(teacher);
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.decommission(beneficiary), Err(Error::InsufficientPermissions));
+        set_next_caller(owner);
+        assert_eq!(course_reg.decommission(beneficiary), Err(Error::CoursesStillScheduled));
+
+        // once every course has started the balance is swept
+        TestClock::set(start_time);
+        let contract = ink_env::test::callee::<ink_env::DefaultEnvironment>();
+        ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract, 100);
+        let should_terminate = move || course_reg.decommission(beneficiary).unwrap();
+        ink_env::test::assert_contract_termination::<ink_env::DefaultEnvironment, _>(
+            should_terminate, beneficiary, 100);
+        // the course's registration announcement and the decommissioning
+        assert_eq!(ink_env::test::recorded_events().count(), 2);
+    }
+
+    /// Decommissioning after upgrading a future course test
+    #[ink::test]
+    fn decommission_after_upgrade() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let teacher = AccountId::from([0x1; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let legacy = CourseV2 {
+            teacher,
+            course_id,
+            capacity: 10,
+            registrations: Vec::new(),
+            start_date: start_time,
+        };
+        course_reg.courses_v2.insert(course_id, &legacy);
+        course_reg.version.set(&2);
+        for version in [3, 4] {
+            assert_eq!(course_reg.plan_migration(Vec::new(), ink_prelude::vec![course_id]), Ok(()));
+            assert_eq!(course_reg.migrate_entries(Vec::new(), ink_prelude::vec![course_id]), Ok(()));
+            assert_eq!(course_reg.migrate(), Ok(version));
+        }
+        course_reg.version.set(&STORAGE_VERSION);
+
+        // the legacy course counts for its teacher and keeps the contract alive until it starts
+        assert_eq!(course_reg.get_active_course_count(teacher), 1);
+        assert_eq!(course_reg.decommission(AccountId::from([0x2; 32])), Err(Error::CoursesStillScheduled));
+    }
+}
@@ -0,0 +1,93 @@
+//! Permissionless maintenance of the courses, the transitions that fall due with time
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::{CourseId, SwapOutcome};
+
+impl CourseReg {
+    /// performs the due transitions of the course: marks the passed
+    /// registration boundaries and, once swaps are closed, expires the
+    /// open proposals and returns the escrowed tokens to their owners
+    ///
+    /// returns the number of expired proposals
+    pub(crate) fn poke_inner(&mut self, course_id: CourseId) -> Result<u32, Error> {
+        self.ensure_version()?;
+        if !self.courses.contains(course_id) {
+            return Err(Error::NonexistentCourse);
+        }
+        self.mark_registration_phase(course_id);
+        if self.ensure_swaps_open(course_id).is_ok() {
+            return Ok(0);
+        }
+        Ok(self.expire_proposals(course_id))
+    }
+
+    /// returns the tokens of the course's proposals to their owners
+    ///
+    /// returns the number of proposals expired, at most `MAX_PROPOSALS_PER_COURSE`
+    pub(crate) fn expire_proposals(&mut self, course_id: CourseId) -> u32 {
+        let mut expired: u32 = 0;
+        for proposal_id in self.course_proposals.get(course_id).unwrap_or_default() {
+            if let Some(proposal) = self.proposals.get(proposal_id) {
+                self.remove_proposal(&proposal);
+                self.record_swap_outcome(proposal.offer.owner, SwapOutcome::Expired);
+                self.mint(proposal.offer.course_id, proposal.offer.owner);
+                for counter_offer in proposal.counter_offers {
+                    self.mint(counter_offer.course_id, counter_offer.owner);
+                }
+                expired = expired.saturating_add(1);
+            }
+        }
+        expired
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Expiry of the proposals after the swap deadline test
+    #[ink::test]
+    fn poke_expires_proposals() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student1 = AccountId::from([0x1; 32]);
+        let student2 = AccountId::from([0x2; 32]);
+        let keeper = AccountId::from([0x3; 32]);
+        let course_id1 = CourseId(hash_keccak_256("test_course1".as_bytes()));
+        let course_id2 = CourseId(hash_keccak_256("test_course2".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_student(student1, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student2, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id1, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id2, 10, start_time, term_id, None, None), Ok(()));
+        set_next_caller(student1);
+        assert_eq!(course_reg.register_to_course(course_id1, None).map(|_| ()), Ok(()));
+        let proposal_id = course_reg.propose_swap(course_id1, None).unwrap();
+        set_next_caller(student2);
+        assert_eq!(course_reg.register_to_course(course_id2, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id2, None), Ok(()));
+
+        // anyone can poke, nothing is due while swaps are open
+        set_next_caller(keeper);
+        assert_eq!(course_reg.poke(CourseId([0x1; 32]), None), Err(Error::NonexistentCourse));
+        assert_eq!(course_reg.poke(course_id1, None), Ok(0));
+        TestClock::set(start_time);
+        assert_eq!(course_reg.poke(course_id1, None), Ok(1));
+        assert_eq!(course_reg.get_swap_proposal(proposal_id), Err(Error::SwapProposalNotFound));
+        assert_eq!(course_reg.poke(course_id1, None), Ok(0));
+        set_next_caller(student1);
+        assert_eq!(course_reg.get_own_registrations().unwrap().len(), 1);
+        set_next_caller(student2);
+        assert_eq!(course_reg.get_own_registrations().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,515 @@
+//! Admission of teachers and students to the school
+
+use ink_lang::codegen::{EmitEvent, StaticEnv};
+use ink_prelude::vec::Vec;
+use scale::Encode;
+
+use crate::course_reg::{BatchAdmitted, BatchRevoked, CourseReg, MemberAdmitted, MemberRevoked};
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn admit_as_teacher_inner(&mut self, account: AccountId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.require_role(Role::Owner)?;
+        if self.is_school_member_inner(account) && !self.alumni.contains(account) {
+            return Err(Error::AlreadyMember);
+        }
+        self.school_members.insert(account, &true);
+        self.activation_dates.remove(account);
+        self.membership_expiries.remove(account);
+        self.alumni.remove(account);
+        Ok(())
+    }
+
+    pub(crate) fn admit_as_student_inner(&mut self, account: AccountId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.require_role(Role::Owner)?;
+        self.ensure_admissible_as_student(account)?;
+        let valid_until = Self::now().saturating_add(MEMBERSHIP_VALIDITY);
+        self.school_members.insert(account, &false);
+        self.activation_dates.remove(account);
+        self.membership_expiries.insert(account, &valid_until);
+        self.alumni.remove(account);
+        Ok(())
+    }
+
+    /// admits the account as a student whose membership activates at `active_from`
+    pub(crate) fn admit_as_student_from_inner(&mut self, account: AccountId, active_from: Timestamp) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.require_role(Role::Owner)?;
+        self.ensure_admissible_as_student(account)?;
+        Self::validate_future_timestamp(active_from)?;
+        let mut pending: Vec<AccountId> = self.pending_activations.get_or_default().into_iter()
+            .filter(|pending| *pending != account && self.activation_date(*pending).is_some())
+            .collect();
+        if pending.len() >= MAX_PENDING_ACTIVATIONS as usize {
+            return Err(Error::TooManyPendingActivations);
+        }
+        pending.push(account);
+        self.pending_activations.set(&pending);
+        self.school_members.insert(account, &false);
+        self.activation_dates.insert(account, &active_from);
+        self.membership_expiries.insert(account, &active_from.saturating_add(MEMBERSHIP_VALIDITY));
+        self.alumni.remove(account);
+        Ok(())
+    }
+
+    /// fails if the account is already a member, alumni and students
+    /// whose membership isn't active yet can be admitted again
+    fn ensure_admissible_as_student(&self, account: AccountId) -> Result<(), Error> {
+        let readmission = self.alumni.contains(account)
+            || (!self.is_teacher_inner(account) && self.activation_date(account).is_some());
+        if self.is_school_member_inner(account) && !readmission {
+            return Err(Error::AlreadyMember);
+        }
+        Ok(())
+    }
+
+    /// makes the student a teacher, the student's registrations are kept
+    pub(crate) fn promote_to_teacher_inner(&mut self, account: AccountId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.require_role(Role::Owner)?;
+        if !self.is_school_member_inner(account) {
+            return Err(Error::NotSchoolMember);
+        }
+        if self.is_teacher_inner(account) || self.alumni.contains(account) {
+            return Err(Error::InvalidRoleTransition);
+        }
+        self.school_members.insert(account, &true);
+        self.activation_dates.remove(account);
+        self.membership_expiries.remove(account);
+        let mut pending = self.pending_activations.get_or_default();
+        pending.retain(|pending| *pending != account);
+        self.pending_activations.set(&pending);
+        Ok(())
+    }
+
+    /// makes the teacher a student, once none of the teacher's courses is running
+    ///
+    /// the ended courses keep the teacher on record, the registrations are kept
+    pub(crate) fn demote_to_student_inner(&mut self, account: AccountId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.require_role(Role::Owner)?;
+        if !self.is_school_member_inner(account) {
+            return Err(Error::NotSchoolMember);
+        }
+        if !self.is_teacher_inner(account) || account == self.owner {
+            return Err(Error::InvalidRoleTransition);
+        }
+        if self.active_course_counts.get(account).unwrap_or(0) > 0 {
+            return Err(Error::TeacherHasActiveCourses);
+        }
+        self.school_members.insert(account, &false);
+        self.membership_expiries.insert(account, &Self::now().saturating_add(MEMBERSHIP_VALIDITY));
+        Ok(())
+    }
+
+    /// turns the student into an alumnus, whose records stay readable
+    /// but who can no longer take part in courses
+    pub(crate) fn graduate_inner(&mut self, account: AccountId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.require_role(Role::Owner)?;
+        if !self.is_school_member_inner(account) {
+            return Err(Error::NotSchoolMember);
+        }
+        if self.is_teacher_inner(account) || self.alumni.contains(account) {
+            return Err(Error::InvalidRoleTransition);
+        }
+        self.activation_dates.remove(account);
+        self.membership_expiries.remove(account);
+        self.alumni.insert(account, &Self::now());
+        Ok(())
+    }
+
+    /// extends or shortens the validity of the member's status
+    pub(crate) fn renew_membership_inner(&mut self, account: AccountId, new_until: Timestamp) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.require_role(Role::Owner)?;
+        if !self.is_school_member_inner(account) {
+            return Err(Error::NotSchoolMember);
+        }
+        if new_until <= Self::now() {
+            return Err(Error::InvalidTimestamp);
+        }
+        self.membership_expiries.insert(account, &new_until);
+        Ok(())
+    }
+
+    /// returns the activation date of the account's membership, if it's in the future
+    pub(crate) fn activation_date(&self, account: AccountId) -> Option<Timestamp> {
+        self.activation_dates.get(account).filter(|active_from| *active_from > Self::now())
+    }
+
+    /// fails if the account's membership isn't active yet, already lapsed
+    /// or the account graduated
+    pub(crate) fn ensure_membership_active(&self, account: AccountId) -> Result<(), Error> {
+        if self.alumni.contains(account) {
+            return Err(Error::AlumniReadOnly);
+        }
+        if let Some(active_from) = self.activation_date(account) {
+            return Err(Error::MembershipNotActive { active_from });
+        }
+        match self.membership_expiries.get(account) {
+            Some(valid_until) if valid_until <= Self::now() => Err(Error::MembershipExpired { expired_at: valid_until }),
+            _ => Ok(()),
+        }
+    }
+
+    /// returns the admitted students whose membership isn't active yet
+    pub(crate) fn get_pending_activations_inner(&self) -> Vec<PendingActivation> {
+        self.pending_activations.get_or_default().into_iter()
+            .filter_map(|account| self.activation_date(account)
+                        .map(|active_from| PendingActivation { account, active_from }))
+            .collect()
+    }
+
+    /// admits the accounts that aren't members yet
+    ///
+    /// returns the indexes of the accounts that were already members
+    pub(crate) fn admit_batch_inner(&mut self, accounts: Vec<AccountId>, as_teacher: bool) -> Result<Vec<u32>, Error> {
+        self.ensure_version()?;
+        self.require_role(Role::Owner)?;
+        if accounts.len() > MAX_BATCH_SIZE as usize {
+            return Err(Error::BatchTooLarge);
+        }
+        let valid_until = Self::now().saturating_add(MEMBERSHIP_VALIDITY);
+        let mut failed = Vec::new();
+        let mut admitted = Vec::new();
+        for (index, account) in (0u32..).zip(accounts.iter()) {
+            if self.is_school_member_inner(*account) {
+                failed.push(index);
+                continue;
+            }
+            self.school_members.insert(account, &as_teacher);
+            if !as_teacher {
+                self.membership_expiries.insert(account, &valid_until);
+            }
+            admitted.push(*account);
+        }
+        if admitted.len() <= MAX_ITEMIZED_BATCH as usize {
+            for account in &admitted {
+                Self::env().emit_event(MemberAdmitted { account: *account, as_teacher });
+            }
+        }
+        Self::env().emit_event(BatchAdmitted {
+            as_teacher,
+            admitted: admitted.len() as u32,
+            failed: failed.len() as u32,
+            batch_hash: Self::hash_keccak_256(&admitted.encode()),
+        });
+        Ok(failed)
+    }
+
+    /// revokes the membership of the accounts, the owner stays a member
+    ///
+    /// returns the indexes of the accounts that couldn't be revoked, with the reason
+    pub(crate) fn revoke_batch_inner(&mut self, accounts: Vec<AccountId>) -> Result<Vec<(u32, Error)>, Error> {
+        self.ensure_version()?;
+        self.require_role(Role::Owner)?;
+        if accounts.len() > MAX_BATCH_SIZE as usize {
+            return Err(Error::BatchTooLarge);
+        }
+        let mut failed = Vec::new();
+        let mut revoked = Vec::new();
+        for (index, account) in (0u32..).zip(accounts.iter()) {
+            if let Err(error) = self.check_revoke(*account) {
+                failed.push((index, error));
+                continue;
+            }
+            self.school_members.remove(account);
+            self.activation_dates.remove(account);
+            self.membership_expiries.remove(account);
+            self.alumni.remove(account);
+            revoked.push(*account);
+        }
+        if revoked.len() <= MAX_ITEMIZED_BATCH as usize {
+            for account in &revoked {
+                Self::env().emit_event(MemberRevoked { account: *account });
+            }
+        }
+        Self::env().emit_event(BatchRevoked {
+            revoked: revoked.len() as u32,
+            failed: failed.len() as u32,
+            batch_hash: Self::hash_keccak_256(&revoked.encode()),
+        });
+        Ok(failed)
+    }
+
+    /// fails unless the account is a member other than the owner who holds
+    /// no seat, token, proposal, standing counter offer or seat request,
+    /// which the account couldn't act on once revoked
+    fn check_revoke(&self, account: AccountId) -> Result<(), Error> {
+        if account == self.owner {
+            return Err(Error::InvalidRoleTransition);
+        }
+        if !self.is_school_member_inner(account) {
+            return Err(Error::NotSchoolMember);
+        }
+        if !self.registration_index.get(account).unwrap_or_default().is_empty()
+            || !self.escrow_index.get(account).unwrap_or_default().is_empty()
+            || !self.standing_counter_courses.get(account).unwrap_or_default().is_empty()
+            || !self.seat_request_courses.get(account).unwrap_or_default().is_empty() {
+            return Err(Error::MemberHoldsRegistrations);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn is_school_member_inner(&self, account: AccountId) -> bool {
+        self.school_members.contains(account)
+    }
+
+    pub(crate) fn is_teacher_inner(&self, account: AccountId) -> bool {
+        self.school_members.get(account).unwrap_or(false)
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Teacher admission test
+    #[ink::test]
+    fn teacher_admission() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let teacher = AccountId::from([0x1; 32]);
+        assert_eq!(course_reg.admit_as_teacher(teacher, None), Ok(()));
+        assert!(course_reg.is_school_member(teacher));
+        assert!(course_reg.is_teacher(teacher));
+    }
+
+    /// Student admission test
+    #[ink::test]
+    fn student_admission() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert!(course_reg.is_school_member(student));
+        assert!(!course_reg.is_teacher(student));
+    }
+
+    /// Deferred student admission test
+    #[ink::test]
+    fn deferred_admission() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let other = AccountId::from([0x2; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        let active_from = start_time - 1;
+        assert_eq!(course_reg.admit_as_student_from(student, start_time - 7 * MILLIS_PER_DAY, None), Err(Error::InvalidTimestamp));
+        assert_eq!(course_reg.admit_as_student_from(student, active_from, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student_from(other, active_from, None), Ok(()));
+        assert!(course_reg.is_school_member(student));
+        let pending = PendingActivation { account: student, active_from };
+        assert_eq!(course_reg.get_pending_activations().len(), 2);
+        assert_eq!(course_reg.get_pending_activations()[0], pending);
+
+        // the immediate admission activates the membership
+        assert_eq!(course_reg.admit_as_student(other, None), Ok(()));
+        assert_eq!(course_reg.get_pending_activations(), ink_prelude::vec![pending]);
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(course_id, None), Err(Error::MembershipNotActive { active_from }));
+        TestClock::set(active_from);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.get_pending_activations(), ink_prelude::vec![]);
+    }
+
+    /// Membership expiry and renewal test
+    #[ink::test]
+    fn membership_expiry() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let now = start_time - 7 * MILLIS_PER_DAY;
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.renew_membership(student, start_time, None), Err(Error::NotSchoolMember));
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert_eq!(course_reg.get_membership_expiry(student), Some(now + MEMBERSHIP_VALIDITY));
+        assert_eq!(course_reg.get_membership_expiry(owner), None);
+
+        // the status lapses before the course starts
+        let expired_at = start_time - 1;
+        assert_eq!(course_reg.renew_membership(student, now, None), Err(Error::InvalidTimestamp));
+        assert_eq!(course_reg.renew_membership(student, expired_at, None), Ok(()));
+        TestClock::set(expired_at);
+        set_next_caller(student);
+        assert_eq!(course_reg.renew_membership(student, start_time, None), Err(Error::InsufficientPermissions));
+        assert_eq!(course_reg.register_to_course(course_id, None), Err(Error::MembershipExpired { expired_at }));
+        set_next_caller(owner);
+        assert_eq!(course_reg.renew_membership(student, start_time, None), Ok(()));
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+    }
+
+    /// Graduation to the alumni role test
+    #[ink::test]
+    fn alumni_role() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let teacher = AccountId::from([0x2; 32]);
+        let course_id1 = CourseId(hash_keccak_256("test_course1".as_bytes()));
+        let course_id2 = CourseId(hash_keccak_256("test_course2".as_bytes()));
+        let start_time = get_start_time();
+        let now = start_time - 7 * MILLIS_PER_DAY;
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.create_course(course_id1, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id2, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.admit_as_teacher(teacher, None), Ok(()));
+        assert_eq!(course_reg.graduate(student, None), Err(Error::NotSchoolMember));
+        assert_eq!(course_reg.graduate(teacher, None), Err(Error::InvalidRoleTransition));
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(course_id1, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.graduate(student, None), Err(Error::InsufficientPermissions));
+
+        set_next_caller(owner);
+        assert_eq!(course_reg.graduate(student, None), Ok(()));
+        assert_eq!(course_reg.graduate(student, None), Err(Error::InvalidRoleTransition));
+        assert!(course_reg.is_alumni(student));
+        assert_eq!(course_reg.get_graduation_date(student), Some(now));
+        assert_eq!(course_reg.get_membership_expiry(student), None);
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(course_id2, None), Err(Error::AlumniReadOnly));
+        assert_eq!(course_reg.propose_swap(course_id1, None), Err(Error::AlumniReadOnly));
+        assert_eq!(course_reg.get_transcript(student), ink_prelude::vec![]);
+
+        // a new admission makes them a student again
+        set_next_caller(owner);
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert!(!course_reg.is_alumni(student));
+    }
+
+    /// Role transitions of admitted members test
+    #[ink::test]
+    fn role_transitions() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let teacher = AccountId::from([0x1; 32]);
+        let student = AccountId::from([0x2; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let other_course_id = CourseId(hash_keccak_256("other_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.admit_as_teacher(teacher, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(teacher, None), Err(Error::AlreadyMember));
+        assert_eq!(course_reg.admit_as_teacher(student, None), Err(Error::AlreadyMember));
+        assert_eq!(course_reg.admit_as_student(student, None), Err(Error::AlreadyMember));
+        assert_eq!(course_reg.promote_to_teacher(teacher, None), Err(Error::InvalidRoleTransition));
+        assert_eq!(course_reg.demote_to_student(student, None), Err(Error::InvalidRoleTransition));
+        assert_eq!(course_reg.demote_to_student(owner, None), Err(Error::InvalidRoleTransition));
+
+        // the promoted student keeps the seat
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.promote_to_teacher(student, None), Err(Error::InsufficientPermissions));
+        set_next_caller(owner);
+        assert_eq!(course_reg.promote_to_teacher(student, None), Ok(()));
+        assert!(course_reg.is_teacher(student));
+        assert!(course_reg.is_registered(course_id, student));
+        assert_eq!(course_reg.get_membership_expiry(student), None);
+
+        // a teacher with a running course stays a teacher
+        set_next_caller(teacher);
+        assert_eq!(course_reg.create_course(other_course_id, 10, start_time, term_id, None, None), Ok(()));
+        set_next_caller(owner);
+        assert_eq!(course_reg.demote_to_student(teacher, None), Err(Error::TeacherHasActiveCourses));
+        set_next_caller(teacher);
+        TestClock::set(start_time);
+        assert_eq!(course_reg.end_course(other_course_id, None), Ok(()));
+        set_next_caller(owner);
+        assert_eq!(course_reg.demote_to_student(teacher, None), Ok(()));
+        assert!(!course_reg.is_teacher(teacher));
+        assert!(course_reg.get_membership_expiry(teacher).is_some());
+    }
+
+    /// Batch admission and revocation test
+    #[ink::test]
+    fn batch_membership() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let first = AccountId::from([0x1; 32]);
+        let second = AccountId::from([0x2; 32]);
+        let oversized = ink_prelude::vec![first; MAX_BATCH_SIZE as usize + 1];
+        assert_eq!(course_reg.admit_batch(oversized, false, None), Err(Error::BatchTooLarge));
+        assert_eq!(course_reg.admit_batch(ink_prelude::vec![first, owner, second, first], false, None),
+                   Ok(ink_prelude::vec![1, 3]));
+        assert!(course_reg.is_school_member(second));
+        assert!(!course_reg.is_teacher(second));
+        assert_eq!(course_reg.revoke_batch(ink_prelude::vec![owner, second, second], None),
+                   Ok(ink_prelude::vec![(0, Error::InvalidRoleTransition), (2, Error::NotSchoolMember)]));
+        assert!(!course_reg.is_school_member(second));
+        assert!(course_reg.is_school_member(owner));
+        assert_eq!(ink_env::test::recorded_events().count(), 5);
+
+        // a large batch is only summarized
+        let accounts: Vec<AccountId> = (0..=MAX_ITEMIZED_BATCH as u8).map(|i| AccountId::from([0x10 + i; 32])).collect();
+        assert_eq!(course_reg.admit_batch(accounts, false, None), Ok(ink_prelude::vec![]));
+        assert_eq!(ink_env::test::recorded_events().count(), 6);
+
+        set_next_caller(first);
+        assert_eq!(course_reg.revoke_batch(ink_prelude::vec![first], None), Err(Error::InsufficientPermissions));
+    }
+
+    /// Revocation of a registered student test
+    #[ink::test]
+    fn revoke_registered_student() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let other = AccountId::from([0x2; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.admit_batch(ink_prelude::vec![student, other], false, None), Ok(ink_prelude::vec![]));
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+
+        // the seat stays with a member who can act on it
+        set_next_caller(owner);
+        assert_eq!(course_reg.revoke_batch(ink_prelude::vec![student, other], None),
+                   Ok(ink_prelude::vec![(0, Error::MemberHoldsRegistrations)]));
+        assert!(course_reg.is_school_member(student));
+        assert!(!course_reg.is_school_member(other));
+
+        // nor while the token is escrowed by a proposal
+        set_next_caller(student);
+        let proposal_id = course_reg.propose_swap(course_id, None).unwrap();
+        set_next_caller(owner);
+        assert_eq!(course_reg.revoke_batch(ink_prelude::vec![student], None),
+                   Ok(ink_prelude::vec![(0, Error::MemberHoldsRegistrations)]));
+        set_next_caller(student);
+        assert_eq!(course_reg.cancel_swap_proposal(proposal_id, None), Ok(()));
+        assert_eq!(course_reg.move_registration(course_id, Some(student), None), Ok(()));
+        set_next_caller(owner);
+        assert_eq!(course_reg.revoke_batch(ink_prelude::vec![student], None), Ok(ink_prelude::vec![]));
+        assert!(!course_reg.is_school_member(student));
+    }
+}
@@ -0,0 +1,93 @@
+//! Notification keys of the members and the notices sealed to them
+
+use ink_lang::codegen::{EmitEvent, StaticEnv};
+use ink_prelude::vec::Vec;
+
+use crate::course_reg::{CourseReg, SealedNotice};
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn set_notification_key_inner(&mut self, pubkey: [u8; 32]) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_NOTIFICATIONS)?;
+        let caller = Self::env().caller();
+        self.require_role(Role::Member)?;
+        self.notification_keys.insert(caller, &pubkey);
+        Ok(())
+    }
+
+    /// emits the notice of the recorded grade, sealed off-chain by the
+    /// teacher to the student's notification key, e.g. the grade and salt
+    pub(crate) fn notify_grade_inner(&mut self,
+                                     course_id: CourseId,
+                                     student: AccountId,
+                                     sealed_notice: Vec<u8>) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_NOTIFICATIONS)?;
+        self.require_course_teacher(course_id, Self::env().caller())?;
+        if !self.grades.contains((course_id, student)) {
+            return Err(Error::NoGradeRecorded);
+        }
+        let key = self.notification_keys.get(student).ok_or(Error::NoNotificationKey)?;
+        if sealed_notice.is_empty() || sealed_notice.len() > MAX_SEALED_NOTICE_LENGTH as usize {
+            return Err(Error::InvalidNotice);
+        }
+        Self::env().emit_event(SealedNotice {
+            recipient: student,
+            key,
+            sealed: sealed_notice,
+        });
+        Ok(())
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Notification key and sealed grade notice test
+    #[ink::test]
+    fn sealed_grade_notice() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let other = AccountId::from([0x2; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let pubkey = [0x7; 32];
+        let sealed = ink_prelude::vec![0x42; 64];
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        set_next_caller(other);
+        assert_eq!(course_reg.set_notification_key(pubkey, None), Err(Error::InsufficientPermissions));
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        TestClock::set(start_time);
+        set_next_caller(owner);
+        assert_eq!(course_reg.end_course(course_id, None), Ok(()));
+        assert_eq!(course_reg.notify_grade(course_id, student, sealed.clone(), None), Err(Error::NoGradeRecorded));
+        let commitment = CourseReg::grade_commitment(5, [0x1; 32]);
+        assert_eq!(course_reg.record_grade(course_id, student, commitment, None), Ok(()));
+        assert_eq!(course_reg.notify_grade(course_id, student, sealed.clone(), None), Err(Error::NoNotificationKey));
+
+        set_next_caller(student);
+        assert_eq!(course_reg.set_notification_key(pubkey, None), Ok(()));
+        assert_eq!(course_reg.get_notification_key(student), Some(pubkey));
+        assert_eq!(course_reg.notify_grade(course_id, student, sealed.clone(), None), Err(Error::InsufficientPermissions));
+        set_next_caller(owner);
+        assert_eq!(course_reg.notify_grade(course_id, student, ink_prelude::vec![], None), Err(Error::InvalidNotice));
+        let events = ink_env::test::recorded_events().count();
+        assert_eq!(course_reg.notify_grade(course_id, student, sealed, None), Ok(()));
+        assert_eq!(ink_env::test::recorded_events().count(), events + 1);
+    }
+}
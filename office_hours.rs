@@ -0,0 +1,162 @@
+//! Office-hour slots the teachers publish and the registered students book
+
+use ink_lang::codegen::{EmitEvent, StaticEnv};
+use ink_prelude::vec::Vec;
+
+use crate::course_reg::{CourseReg, OfficeHourBooked, OfficeHourCancelled};
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    /// publishes every window as a slot for a single student, or none of them
+    pub(crate) fn publish_office_hours_inner(&mut self,
+                                             course_id: CourseId,
+                                             windows: Vec<OfficeHourWindow>) -> Result<Vec<SlotId>, Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_OFFICE_HOURS)?;
+        self.require_course_teacher(course_id, Self::env().caller())?;
+        if self.course_ends.contains(course_id) {
+            return Err(Error::CourseAlreadyEnded);
+        }
+        let now = Self::now();
+        if windows.iter().any(|window| window.starts >= window.ends || window.starts <= now) {
+            return Err(Error::InvalidOfficeHourWindow);
+        }
+        let first = self.office_hour_counts.get(course_id).unwrap_or(0);
+        let slot_count = first.checked_add(windows.len() as u32).ok_or(Error::ArithmeticOverflow)?;
+        if slot_count > MAX_OFFICE_HOURS_PER_COURSE {
+            return Err(Error::TooManyOfficeHours);
+        }
+        let mut slot_ids = Vec::with_capacity(windows.len());
+        for (slot_id, window) in (first..).zip(windows) {
+            let slot = OfficeHourSlot { starts: window.starts, ends: window.ends, booked_by: None };
+            self.office_hours.insert((course_id, slot_id), &slot);
+            slot_ids.push(slot_id);
+        }
+        self.office_hour_counts.insert(course_id, &slot_count);
+        Ok(slot_ids)
+    }
+
+    /// books the slot for the caller, a student holds one booking per course
+    pub(crate) fn book_office_hour_inner(&mut self, course_id: CourseId, slot_id: SlotId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_OFFICE_HOURS)?;
+        let caller = Self::env().caller();
+        self.ensure_membership_active(caller)?;
+        let course = self.courses.get(course_id).ok_or(Error::NonexistentCourse)?;
+        let mut slot = self.office_hours.get((course_id, slot_id)).ok_or(Error::OfficeHourNotFound)?;
+        if !self.enrollments.contains((course_id, caller)) {
+            return Err(Error::NotRegistered);
+        }
+        if slot.starts <= Self::now() {
+            return Err(Error::OfficeHourStarted);
+        }
+        if slot.booked_by.is_some() {
+            return Err(Error::OfficeHourBooked);
+        }
+        if self.office_hour_bookings.contains((course_id, caller)) {
+            return Err(Error::AlreadyBooked);
+        }
+        slot.booked_by = Some(caller);
+        self.office_hours.insert((course_id, slot_id), &slot);
+        self.office_hour_bookings.insert((course_id, caller), &slot_id);
+        Self::env().emit_event(OfficeHourBooked { course_id, slot_id, student: caller, teacher: course.teacher });
+        Ok(())
+    }
+
+    /// frees the caller's booked slot of the course before it starts
+    pub(crate) fn cancel_office_hour_inner(&mut self, course_id: CourseId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_OFFICE_HOURS)?;
+        let caller = Self::env().caller();
+        let course = self.courses.get(course_id).ok_or(Error::NonexistentCourse)?;
+        let slot_id = self.office_hour_bookings.get((course_id, caller)).ok_or(Error::BookingNotFound)?;
+        let mut slot = self.office_hours.get((course_id, slot_id)).ok_or(Error::OfficeHourNotFound)?;
+        if slot.starts <= Self::now() {
+            return Err(Error::OfficeHourStarted);
+        }
+        slot.booked_by = None;
+        self.office_hours.insert((course_id, slot_id), &slot);
+        self.office_hour_bookings.remove((course_id, caller));
+        Self::env().emit_event(OfficeHourCancelled { course_id, slot_id, student: caller, teacher: course.teacher });
+        Ok(())
+    }
+
+    pub(crate) fn get_office_hours_inner(&self,
+                                         course_id: CourseId,
+                                         cursor: Option<Cursor>,
+                                         count: u32) -> Result<Page<OfficeHourSlot>, Error> {
+        if !self.courses.contains(course_id) {
+            return Err(Error::NonexistentCourse);
+        }
+        let slot_ids: Vec<SlotId> = (0..self.office_hour_counts.get(course_id).unwrap_or(0)).collect();
+        let page = Self::page(slot_ids, cursor, count, |slot_id| Self::id_key(u64::from(*slot_id)))?;
+        let items = page.items.into_iter()
+            .filter_map(|slot_id| self.office_hours.get((course_id, slot_id)))
+            .collect();
+        Ok(Page { items, next_cursor: page.next_cursor, total: page.total })
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+    use ink_prelude::vec;
+
+    /// Office-hour booking test
+    #[ink::test]
+    fn office_hours() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let teacher = AccountId::from([0x1; 32]);
+        let student1 = AccountId::from([0x2; 32]);
+        let student2 = AccountId::from([0x3; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_teacher(teacher, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student1, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student2, None), Ok(()));
+        set_next_caller(teacher);
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        let windows = vec![
+            OfficeHourWindow { starts: start_time, ends: start_time + 1000 },
+            OfficeHourWindow { starts: start_time + 1000, ends: start_time + 2000 },
+        ];
+        let invalid = vec![OfficeHourWindow { starts: start_time, ends: start_time }];
+        assert_eq!(course_reg.publish_office_hours(course_id, invalid, None), Err(Error::InvalidOfficeHourWindow));
+        assert_eq!(course_reg.publish_office_hours(course_id, windows, None), Ok(vec![0, 1]));
+
+        set_next_caller(student1);
+        assert_eq!(course_reg.book_office_hour(course_id, 0, None), Err(Error::NotRegistered));
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        let events = ink_env::test::recorded_events().count();
+        assert_eq!(course_reg.book_office_hour(course_id, 0, None), Ok(()));
+        assert_eq!(ink_env::test::recorded_events().count(), events + 1);
+        assert_eq!(course_reg.book_office_hour(course_id, 1, None), Err(Error::AlreadyBooked));
+        set_next_caller(student2);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.book_office_hour(course_id, 0, None), Err(Error::OfficeHourBooked));
+        assert_eq!(course_reg.book_office_hour(course_id, 2, None), Err(Error::OfficeHourNotFound));
+        assert_eq!(course_reg.cancel_office_hour(course_id, None), Err(Error::BookingNotFound));
+
+        // the cancelled slot can be booked by another student
+        set_next_caller(student1);
+        assert_eq!(course_reg.cancel_office_hour(course_id, None), Ok(()));
+        set_next_caller(student2);
+        assert_eq!(course_reg.book_office_hour(course_id, 0, None), Ok(()));
+        assert_eq!(course_reg.get_office_hours(course_id, None, 10).unwrap().items[0].booked_by, Some(student2));
+        TestClock::set(start_time);
+        assert_eq!(course_reg.cancel_office_hour(course_id, None), Err(Error::OfficeHourStarted));
+        set_next_caller(student1);
+        assert_eq!(course_reg.book_office_hour(course_id, 1, None), Ok(()));
+    }
+}
@@ -0,0 +1,71 @@
+//! The owner's break-glass path for the actions of a course's teacher
+
+use ink_lang::codegen::{EmitEvent, StaticEnv};
+
+use crate::course_reg::{CourseReg, TeacherOverride};
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    /// takes the action as the teacher of the course and logs the override
+    pub(crate) fn act_as_teacher_inner(&mut self, course_id: CourseId, action: TeacherAction) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.require_role(Role::Owner)?;
+        let teacher = self.courses.get(course_id).ok_or(Error::NonexistentCourse)?.teacher;
+        match action.clone() {
+            TeacherAction::EndCourse => self.end_course_by(course_id, teacher)?,
+            TeacherAction::SetCredits { credits } => self.set_course_credits_by(course_id, credits, teacher)?,
+            TeacherAction::SetUri { uri, content_hash } => self.set_course_uri_by(course_id, uri, content_hash, teacher)?,
+            TeacherAction::AcceptSupervisionApplicant { student } =>
+                self.accept_supervision_applicant_by(course_id, student, teacher)?,
+        }
+        Self::env().emit_event(TeacherOverride {
+            course_id,
+            teacher,
+            owner: Self::env().caller(),
+            action,
+        });
+        Ok(())
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Owner acting for a teacher test
+    #[ink::test]
+    fn act_as_teacher() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let teacher = AccountId::from([0x1; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_teacher(teacher, None), Ok(()));
+        set_next_caller(teacher);
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.act_as_teacher(course_id, TeacherAction::EndCourse, None),
+                   Err(Error::InsufficientPermissions));
+
+        set_next_caller(owner);
+        let events = ink_env::test::recorded_events().count();
+        assert_eq!(course_reg.act_as_teacher(course_id, TeacherAction::SetCredits { credits: 5 }, None), Ok(()));
+        assert_eq!(course_reg.get_course_credits(course_id), 5);
+        assert_eq!(course_reg.act_as_teacher(course_id, TeacherAction::EndCourse, None),
+                   Err(Error::CourseNotStarted));
+        assert_eq!(ink_env::test::recorded_events().count(), events + 1);
+        TestClock::set(start_time);
+        assert_eq!(course_reg.act_as_teacher(course_id, TeacherAction::EndCourse, None), Ok(()));
+        assert_eq!(course_reg.get_course_info(course_id).unwrap().state, CourseState::Ended);
+        assert_eq!(ink_env::test::recorded_events().count(), events + 2);
+    }
+}
@@ -0,0 +1,131 @@
+//! The caller of a message, the roles it holds and the guards requiring them
+
+use ink_lang::codegen::StaticEnv;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    /// resolves the caller and its school roles, once per message
+    pub(crate) fn ctx(&self) -> Ctx {
+        let caller = Self::env().caller();
+        Ctx {
+            caller,
+            owner: caller == self.owner,
+            teacher: self.is_teacher_inner(caller),
+            member: self.is_school_member_inner(caller),
+        }
+    }
+
+    /// returns the caller's context, fails unless the caller holds the role
+    pub(crate) fn require_role(&self, role: Role) -> Result<Ctx, Error> {
+        let ctx = self.ctx();
+        if !ctx.has_role(role) {
+            return Err(Error::InsufficientPermissions);
+        }
+        Ok(ctx)
+    }
+
+    /// returns the caller's context, fails unless the caller is `account`
+    /// itself or holds the role
+    pub(crate) fn require_self_or_role(&self, account: AccountId, role: Role) -> Result<Ctx, Error> {
+        let ctx = self.ctx();
+        if ctx.caller != account && !ctx.has_role(role) {
+            return Err(Error::InsufficientPermissions);
+        }
+        Ok(ctx)
+    }
+
+    /// returns the role of the caller in the course
+    pub(crate) fn course_role(&self, ctx: &Ctx, course: &Course) -> CourseRole {
+        if course.teacher == ctx.caller {
+            CourseRole::Teacher
+        } else if self.enrollments.contains((course.course_id, ctx.caller)) {
+            CourseRole::Student
+        } else {
+            CourseRole::Outsider
+        }
+    }
+
+    /// returns the course, fails unless `actor` teaches it
+    pub(crate) fn require_course_teacher(&self, course_id: CourseId, actor: AccountId) -> Result<Course, Error> {
+        let course = self.courses.get(course_id).ok_or(Error::NonexistentCourse)?;
+        if course.teacher != actor {
+            return Err(Error::InsufficientPermissions);
+        }
+        Ok(course)
+    }
+
+    /// returns the course, fails unless the caller teaches it or is the owner
+    pub(crate) fn require_course_admin(&self, course_id: CourseId) -> Result<Course, Error> {
+        let course = self.courses.get(course_id).ok_or(Error::NonexistentCourse)?;
+        let ctx = self.ctx();
+        if !ctx.owner && self.course_role(&ctx, &course) != CourseRole::Teacher {
+            return Err(Error::InsufficientPermissions);
+        }
+        Ok(course)
+    }
+}
+
+impl Ctx {
+    /// returns true if the caller holds the role
+    pub(crate) fn has_role(&self, role: Role) -> bool {
+        match role {
+            Role::Owner => self.owner,
+            Role::Teacher => self.teacher,
+            Role::Member => self.member,
+        }
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Caller roles and guards test
+    #[ink::test]
+    fn caller_roles() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let teacher = AccountId::from([0x1; 32]);
+        let student = AccountId::from([0x2; 32]);
+        let outsider = AccountId::from([0x3; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_teacher(teacher, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert!(course_reg.require_role(Role::Owner).is_ok());
+        assert!(course_reg.require_role(Role::Teacher).is_ok());
+        set_next_caller(teacher);
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.require_role(Role::Owner).err(), Some(Error::InsufficientPermissions));
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+
+        let course = course_reg.courses.get(course_id).unwrap();
+        for (caller, role) in [(teacher, CourseRole::Teacher), (student, CourseRole::Student), (outsider, CourseRole::Outsider)] {
+            set_next_caller(caller);
+            assert_eq!(course_reg.course_role(&course_reg.ctx(), &course), role);
+        }
+        set_next_caller(student);
+        assert!(course_reg.require_role(Role::Member).is_ok());
+        assert_eq!(course_reg.require_role(Role::Teacher).err(), Some(Error::InsufficientPermissions));
+        assert_eq!(course_reg.require_course_admin(course_id).err(), Some(Error::InsufficientPermissions));
+        assert_eq!(course_reg.require_course_teacher(course_id, teacher), Ok(course));
+        assert!(course_reg.require_self_or_role(student, Role::Owner).is_ok());
+        assert_eq!(course_reg.require_self_or_role(teacher, Role::Owner).err(), Some(Error::InsufficientPermissions));
+        set_next_caller(owner);
+        assert!(course_reg.require_self_or_role(student, Role::Owner).is_ok());
+        assert!(course_reg.require_course_admin(course_id).is_ok());
+        assert_eq!(course_reg.require_course_admin(CourseId([0x1; 32])).err(), Some(Error::NonexistentCourse));
+    }
+}
@@ -0,0 +1,133 @@
+//! Draft schedules the students prepare before registration opens
+
+use ink_lang::codegen::StaticEnv;
+use ink_prelude::vec::Vec;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn add_to_plan_inner(&mut self, course_id: CourseId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_PLANS)?;
+        let caller = Self::env().caller();
+        self.require_role(Role::Member)?;
+        if !self.courses.contains(course_id) {
+            return Err(Error::NonexistentCourse);
+        }
+        let mut plan = self.plans.get(caller).unwrap_or_default();
+        if plan.contains(&course_id) {
+            return Err(Error::AlreadyPlanned);
+        }
+        if plan.len() >= MAX_PLANNED_COURSES as usize {
+            return Err(Error::TooManyPlannedCourses);
+        }
+        plan.push(course_id);
+        self.plans.insert(caller, &plan);
+        self.record_plan_addition(course_id);
+        Ok(())
+    }
+
+    pub(crate) fn remove_from_plan_inner(&mut self, course_id: CourseId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_PLANS)?;
+        let caller = Self::env().caller();
+        let mut plan = self.plans.get(caller).unwrap_or_default();
+        let position = plan.iter().position(|planned| *planned == course_id).ok_or(Error::NotPlanned)?;
+        plan.remove(position);
+        if plan.is_empty() {
+            self.plans.remove(caller);
+        } else {
+            self.plans.insert(caller, &plan);
+        }
+        Ok(())
+    }
+
+    /// returns why each planned course would fail to register right now,
+    /// the courses before it in the plan count as registered
+    pub(crate) fn validate_plan_inner(&self) -> Result<Vec<PlanIssue>, Error> {
+        self.ensure_feature(FEATURE_PLANS)?;
+        let caller = Self::env().caller();
+        self.require_role(Role::Member)?;
+        let plan = self.plans.get(caller).unwrap_or_default();
+        Ok((0..plan.len())
+           .filter_map(|position| self.check_batch_registration(caller, &plan, position)
+                       .err()
+                       .map(|error| PlanIssue { course_id: plan[position], error }))
+           .collect())
+    }
+
+    /// registers the caller to every planned course or to none of them
+    pub(crate) fn commit_plan_inner(&mut self) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_PLANS)?;
+        let caller = Self::env().caller();
+        let plan = self.plans.get(caller).ok_or(Error::NoPlannedCourses)?;
+        self.register_to_courses_inner(plan)?;
+        self.plans.remove(caller);
+        Ok(())
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Draft plan validation and commit test
+    #[ink::test]
+    fn enrollment_plan() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let course_id1 = CourseId(hash_keccak_256("test_course1".as_bytes()));
+        let course_id2 = CourseId(hash_keccak_256("test_course2".as_bytes()));
+        let start_time = get_start_time();
+        let term = Term {
+            start: start_time - MILLIS_PER_DAY,
+            end: start_time + 100 * MILLIS_PER_DAY,
+            add_deadline: start_time,
+            drop_deadline: start_time,
+            swap_deadline: start_time,
+        };
+        let term_id = course_reg.create_term(term, None).unwrap();
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id1, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id2, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.set_corequisites(course_id2, ink_prelude::vec![course_id1], None), Ok(()));
+
+        // the plan is drafted before registration opens
+        set_next_caller(student);
+        assert_eq!(course_reg.commit_plan(None), Err(Error::NoPlannedCourses));
+        assert_eq!(course_reg.add_to_plan(CourseId([0x1; 32]), None), Err(Error::NonexistentCourse));
+        assert_eq!(course_reg.add_to_plan(course_id2, None), Ok(()));
+        assert_eq!(course_reg.add_to_plan(course_id2, None), Err(Error::AlreadyPlanned));
+        let opening = Error::RegistrationNotOpen { opens_at: term.start };
+        assert_eq!(course_reg.validate_plan(),
+                   Ok(ink_prelude::vec![PlanIssue { course_id: course_id2, error: opening }]));
+        assert_eq!(course_reg.remove_from_plan(course_id1, None), Err(Error::NotPlanned));
+        assert_eq!(course_reg.add_to_plan(course_id1, None), Ok(()));
+        assert_eq!(course_reg.get_plan(student), ink_prelude::vec![course_id2, course_id1]);
+
+        // the batch counts as registered for the corequisites
+        TestClock::set(term.start);
+        assert_eq!(course_reg.validate_plan(), Ok(ink_prelude::vec![]));
+        assert_eq!(course_reg.remove_from_plan(course_id1, None), Ok(()));
+        let missing = Error::MissingCorequisite { corequisite: course_id1 };
+        assert_eq!(course_reg.validate_plan(),
+                   Ok(ink_prelude::vec![PlanIssue { course_id: course_id2, error: missing }]));
+        assert_eq!(course_reg.add_to_plan(course_id1, None), Ok(()));
+        assert_eq!(course_reg.commit_plan(None), Ok(()));
+        assert!(course_reg.get_plan(student).is_empty());
+        assert!(course_reg.is_registered(course_id1, student));
+        assert!(course_reg.is_registered(course_id2, student));
+    }
+}
@@ -0,0 +1,68 @@
+//! Hashes of the members' off-chain profiles, the personal data stays off-chain
+
+use ink_lang::codegen::StaticEnv;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn set_profile_inner(&mut self, profile_hash: [u8; 32], contact_hash: [u8; 32]) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_PROFILES)?;
+        let caller = Self::env().caller();
+        self.require_role(Role::Member)?;
+        let profile = Profile {
+            profile_hash,
+            contact_hash,
+            updated_at: Self::now(),
+        };
+        self.profiles.insert(caller, &profile);
+        Ok(())
+    }
+
+    /// only the owner and the account itself can read a profile
+    pub(crate) fn get_profile_inner(&self, account: AccountId) -> Result<Option<Profile>, Error> {
+        self.require_self_or_role(account, Role::Owner)?;
+        Ok(self.profiles.get(account))
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Profile hash anchoring and reading permissions test
+    #[ink::test]
+    fn profile_hashes() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let other = AccountId::from([0x2; 32]);
+        let profile_hash = hash_keccak_256("Jane Doe".as_bytes());
+        let contact_hash = hash_keccak_256("jane@example.com".as_bytes());
+        let now = get_current_time();
+        TestClock::set(now);
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+
+        set_next_caller(other);
+        assert_eq!(course_reg.set_profile(profile_hash, contact_hash, None), Err(Error::InsufficientPermissions));
+        set_next_caller(student);
+        assert_eq!(course_reg.get_profile(student), Ok(None));
+        assert_eq!(course_reg.set_profile(profile_hash, contact_hash, None), Ok(()));
+        let profile = Profile { profile_hash, contact_hash, updated_at: now };
+        assert_eq!(course_reg.get_profile(student), Ok(Some(profile)));
+        set_next_caller(other);
+        assert_eq!(course_reg.get_profile(student), Err(Error::InsufficientPermissions));
+        set_next_caller(owner);
+        assert_eq!(course_reg.get_profile(student), Ok(Some(profile)));
+    }
+}
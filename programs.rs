@@ -0,0 +1,128 @@
+//! Degree programs and the students' progress through their requirements
+
+use ink_prelude::vec::Vec;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn create_program_inner(&mut self, program: Program) -> Result<ProgramId, Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_PROGRAMS)?;
+        self.require_role(Role::Owner)?;
+        let course_count = program.elective_buckets.iter()
+            .fold(program.required_courses.len(), |count, bucket| count.saturating_add(bucket.courses.len()));
+        if course_count > MAX_PROGRAM_COURSES as usize
+            || program.elective_buckets.len() > MAX_ELECTIVE_BUCKETS as usize {
+            return Err(Error::InvalidProgram);
+        }
+        let program_id = self.next_program_id.get_or_default();
+        self.next_program_id.set(&program_id.checked_add(1).ok_or(Error::ArithmeticOverflow)?);
+        self.programs.insert(program_id, &program);
+        Ok(program_id)
+    }
+
+    pub(crate) fn assign_program_inner(&mut self, student: AccountId, program_id: ProgramId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_PROGRAMS)?;
+        self.require_role(Role::Owner)?;
+        if !self.programs.contains(program_id) {
+            return Err(Error::NonexistentProgram);
+        }
+        if !self.is_school_member_inner(student) {
+            return Err(Error::NotSchoolMember);
+        }
+        self.student_programs.insert(student, &program_id);
+        Ok(())
+    }
+
+    pub(crate) fn get_program_progress_inner(&self, student: AccountId) -> Result<ProgramProgress, Error> {
+        let program_id = self.student_programs.get(student).ok_or(Error::NoProgramAssigned)?;
+        let program = self.programs.get(program_id).ok_or(Error::NonexistentProgram)?;
+        let (completed_required, missing_required): (Vec<CourseId>, Vec<CourseId>) = program.required_courses
+            .into_iter()
+            .partition(|course_id| self.has_completed(student, *course_id));
+        let mut buckets = Vec::new();
+        for bucket in program.elective_buckets {
+            let mut earned_credits: u32 = 0;
+            for course_id in bucket.courses {
+                if self.has_completed(student, course_id) {
+                    let credits = self.course_credits.get(course_id).unwrap_or(0);
+                    earned_credits = earned_credits.checked_add(credits).ok_or(Error::ArithmeticOverflow)?;
+                }
+            }
+            buckets.push(BucketProgress { earned_credits, min_credits: bucket.min_credits });
+        }
+        let complete = missing_required.is_empty()
+            && buckets.iter().all(|bucket| bucket.earned_credits >= bucket.min_credits);
+        Ok(ProgramProgress {
+            program_id,
+            completed_required,
+            missing_required,
+            buckets,
+            complete,
+        })
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+    use ink_prelude::vec;
+
+    /// Program requirement progress test
+    #[ink::test]
+    fn program_progress() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        let salt = [0x7; 32];
+        let required = CourseId(hash_keccak_256("required".as_bytes()));
+        let missing = CourseId(hash_keccak_256("missing".as_bytes()));
+        let elective = CourseId(hash_keccak_256("elective".as_bytes()));
+        let program = Program {
+            required_courses: vec![required, missing],
+            elective_buckets: vec![ElectiveBucket { courses: vec![elective], min_credits: 4 }],
+        };
+        let program_id = course_reg.create_program(program, None).unwrap();
+        assert_eq!(course_reg.get_program_progress(student), Err(Error::NoProgramAssigned));
+        assert_eq!(course_reg.assign_program(student, program_id, None), Err(Error::NotSchoolMember));
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert_eq!(course_reg.assign_program(student, program_id, None), Ok(()));
+        for course_id in [required, elective] {
+            assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+            assert_eq!(course_reg.set_course_credits(course_id, 5, None), Ok(()));
+            set_next_caller(student);
+            assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+            set_next_caller(owner);
+        }
+
+        TestClock::set(start_time);
+        for course_id in [required, elective] {
+            set_next_caller(owner);
+            assert_eq!(course_reg.end_course(course_id, None), Ok(()));
+            let commitment = CourseReg::grade_commitment(4, salt);
+            assert_eq!(course_reg.record_grade(course_id, student, commitment, None), Ok(()));
+            set_next_caller(student);
+            assert_eq!(course_reg.reveal_grade(course_id, salt, 4, None), Ok(()));
+        }
+        assert_eq!(course_reg.get_program_progress(student), Ok(ProgramProgress {
+            program_id,
+            completed_required: vec![required],
+            missing_required: vec![missing],
+            buckets: vec![BucketProgress { earned_credits: 5, min_credits: 4 }],
+            complete: false,
+        }));
+    }
+}
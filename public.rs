@@ -0,0 +1,127 @@
+//! Open enrollment of non-members to public courses, against a fee
+
+use ink_lang::codegen::StaticEnv;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    /// opens the course to the public or, with None, closes it,
+    /// the public registrants keep their seats
+    pub(crate) fn set_public_enrollment_inner(&mut self,
+                                              course_id: CourseId,
+                                              enrollment: Option<PublicEnrollment>) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_PUBLIC_ENROLLMENT)?;
+        self.require_course_teacher(course_id, Self::env().caller())?;
+        match enrollment {
+            Some(enrollment) if enrollment.capacity == 0
+                || enrollment.capacity > MAX_PUBLIC_CAPACITY
+                || enrollment.fee == 0 => return Err(Error::InvalidPublicEnrollment),
+            Some(enrollment) => self.public_enrollments.insert(course_id, &enrollment),
+            None => self.public_enrollments.remove(course_id),
+        }
+        Ok(())
+    }
+
+    /// takes a seat of the public pool of the course for the fee,
+    /// an account can take one public seat per cooldown
+    pub(crate) fn register_as_public_inner(&mut self, course_id: CourseId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_PUBLIC_ENROLLMENT)?;
+        let caller = Self::env().caller();
+        let enrollment = self.public_enrollments.get(course_id).ok_or(Error::NotOpenToPublic)?;
+        let course = self.courses.get(course_id).ok_or(Error::NonexistentCourse)?;
+        let now = Self::now();
+        let opens_at = self.registration_opening(&course);
+        if now < opens_at {
+            return Err(Error::RegistrationNotOpen { opens_at });
+        }
+        let closed_at = self.registration_deadline(&course);
+        if closed_at <= now {
+            return Err(Error::RegistrationClosed { closed_at });
+        }
+        if self.public_registrations.contains((course_id, caller)) || self.enrollments.contains((course_id, caller)) {
+            return Err(Error::AlreadyRegistered);
+        }
+        if let Some(last) = self.last_public_registrations.get(caller) {
+            let retry_at = last.saturating_add(PUBLIC_REGISTRATION_COOLDOWN);
+            if now < retry_at {
+                return Err(Error::RateLimited { retry_at });
+            }
+        }
+        let count = self.public_counts.get(course_id).unwrap_or(0);
+        if count >= enrollment.capacity {
+            return Err(Error::PublicCapacityFull { capacity: enrollment.capacity });
+        }
+        let count = count.checked_add(1).ok_or(Error::ArithmeticOverflow)?;
+        if Self::env().transferred_value() != enrollment.fee {
+            return Err(Error::IncorrectFee { fee: enrollment.fee });
+        }
+        self.public_registrations.insert((course_id, caller), &());
+        self.public_counts.insert(course_id, &count);
+        self.last_public_registrations.insert(caller, &now);
+        Ok(())
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    fn set_value(value: Balance) {
+        ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(value);
+    }
+
+    /// Public enrollment of non-members test
+    #[ink::test]
+    fn public_enrollment() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let public1 = AccountId::from([0x1; 32]);
+        let public2 = AccountId::from([0x2; 32]);
+        let course_id1 = CourseId(hash_keccak_256("test_course1".as_bytes()));
+        let course_id2 = CourseId(hash_keccak_256("test_course2".as_bytes()));
+        let start_time = get_start_time();
+        let now = start_time - 7 * MILLIS_PER_DAY;
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.create_course(course_id1, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id2, 10, start_time, term_id, None, None), Ok(()));
+        let invalid = PublicEnrollment { capacity: 1, fee: 0 };
+        assert_eq!(course_reg.set_public_enrollment(course_id1, Some(invalid), None), Err(Error::InvalidPublicEnrollment));
+        let enrollment = PublicEnrollment { capacity: 1, fee: 100 };
+        assert_eq!(course_reg.set_public_enrollment(course_id1, Some(enrollment), None), Ok(()));
+        assert_eq!(course_reg.set_public_enrollment(course_id2, Some(enrollment), None), Ok(()));
+
+        // regular registration stays member-only
+        set_next_caller(public1);
+        assert_eq!(course_reg.register_to_course(course_id1, None), Err(Error::InsufficientPermissions));
+        assert_eq!(course_reg.set_public_enrollment(course_id1, None, None), Err(Error::InsufficientPermissions));
+        assert_eq!(course_reg.register_as_public(CourseId([0x1; 32]), None), Err(Error::NotOpenToPublic));
+        assert_eq!(course_reg.register_as_public(course_id1, None), Err(Error::IncorrectFee { fee: 100 }));
+        set_value(100);
+        assert_eq!(course_reg.register_as_public(course_id1, None), Ok(()));
+        assert!(course_reg.is_public_registrant(course_id1, public1));
+        assert_eq!(course_reg.get_public_count(course_id1), 1);
+        let retry_at = now + PUBLIC_REGISTRATION_COOLDOWN;
+        assert_eq!(course_reg.register_as_public(course_id2, None), Err(Error::RateLimited { retry_at }));
+        TestClock::set(retry_at);
+        assert_eq!(course_reg.register_as_public(course_id1, None), Err(Error::AlreadyRegistered));
+        assert_eq!(course_reg.register_as_public(course_id2, None), Ok(()));
+
+        // the public pool is separate from the member seats
+        set_next_caller(public2);
+        assert_eq!(course_reg.register_as_public(course_id1, None), Err(Error::PublicCapacityFull { capacity: 1 }));
+        assert_eq!(course_reg.get_course_info(course_id1).unwrap().enrolled_count, 0);
+        set_value(0);
+    }
+}
@@ -0,0 +1,104 @@
+//! Registration questions of the courses, answered with hashes of off-chain answers
+
+use ink_lang::codegen::StaticEnv;
+use ink_prelude::vec::Vec;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    /// attaches the question to the course or, with None, removes it
+    pub(crate) fn set_registration_question_inner(&mut self,
+                                                  course_id: CourseId,
+                                                  question_hash: Option<[u8; 32]>) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_REGISTRATION_QUESTIONS)?;
+        self.require_course_teacher(course_id, Self::env().caller())?;
+        match question_hash {
+            Some(question_hash) => self.registration_questions.insert(course_id, &question_hash),
+            None => self.registration_questions.remove(course_id),
+        }
+        Ok(())
+    }
+
+    /// registers the caller to the course that has a question, with the hash of the answer
+    pub(crate) fn register_with_answer_inner(&mut self, course_id: CourseId, answer_hash: [u8; 32]) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_REGISTRATION_QUESTIONS)?;
+        let caller = Self::env().caller();
+        self.require_role(Role::Member)?;
+        if !self.registration_questions.contains(course_id) {
+            return Err(Error::NoRegistrationQuestion);
+        }
+        if self.supervision_topics.contains(course_id) {
+            return Err(Error::ApplicationRequired);
+        }
+        self.mark_registration_phase(course_id);
+        self.check_registration(caller, course_id, &[])?;
+        self.ensure_corequisites(caller, course_id, &[course_id])?;
+        self.move_registration(course_id, None, Some(caller))?;
+        self.record_activity(caller, course_id, ActivityKind::Added);
+        self.registration_answers.insert((course_id, caller), &answer_hash);
+        Ok(())
+    }
+
+    /// returns the answer hashes of the registered students, only the teacher can read them
+    pub(crate) fn get_registration_answers_inner(&self,
+                                                 course_id: CourseId,
+                                                 cursor: Option<Cursor>,
+                                                 count: u32) -> Result<Page<RegistrationAnswer>, Error> {
+        self.require_course_teacher(course_id, Self::env().caller())?;
+        let answers: Vec<RegistrationAnswer> = self.rosters.get(course_id)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|student| self.registration_answers.get((course_id, student))
+                        .map(|answer_hash| RegistrationAnswer { student, answer_hash }))
+            .collect();
+        Self::page(answers, cursor, count, |answer| *answer.student.as_ref())
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Registration question and answer hashes test
+    #[ink::test]
+    fn registration_questions() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let course_id = CourseId(hash_keccak_256("test_seminar".as_bytes()));
+        let question_hash = hash_keccak_256("Why do you want this seminar?".as_bytes());
+        let answer_hash = hash_keccak_256("To write my thesis on it".as_bytes());
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        set_next_caller(student);
+        assert_eq!(course_reg.register_with_answer(course_id, answer_hash, None), Err(Error::NoRegistrationQuestion));
+        assert_eq!(course_reg.set_registration_question(course_id, Some(question_hash), None),
+                   Err(Error::InsufficientPermissions));
+        set_next_caller(owner);
+        assert_eq!(course_reg.set_registration_question(course_id, Some(question_hash), None), Ok(()));
+        assert_eq!(course_reg.get_registration_question(course_id), Some(question_hash));
+
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(course_id, None), Err(Error::AnswerRequired));
+        assert_eq!(course_reg.register_with_answer(course_id, answer_hash, None), Ok(()));
+        assert!(course_reg.is_registered(course_id, student));
+        assert_eq!(course_reg.register_with_answer(course_id, answer_hash, None), Err(Error::AlreadyRegistered));
+        assert_eq!(course_reg.get_registration_answers(course_id, None, 10), Err(Error::InsufficientPermissions));
+        set_next_caller(owner);
+        assert_eq!(course_reg.get_registration_answers(course_id, None, 10).map(|page| page.items),
+                   Ok(ink_prelude::vec![RegistrationAnswer { student, answer_hash }]));
+    }
+}
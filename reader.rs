@@ -0,0 +1,101 @@
+//! The read-only interface other contracts query the registry through
+
+use crate::course_reg::CourseReg;
+use crate::types::*;
+
+/// The registry reads other contracts and runtime pallets call
+/// without encoding the selectors by hand
+#[ink_lang::trait_definition]
+pub trait CourseRegistryReader {
+    /// Returns true if the account is an admitted member of the school
+    #[ink(message)]
+    fn is_member(&self, account: AccountId) -> bool;
+
+    /// Returns true if the account is registered to the course
+    #[ink(message)]
+    fn is_enrolled(&self, course_id: CourseId, account: AccountId) -> bool;
+
+    /// Returns the free seats of the course, `None` if it doesn't exist
+    #[ink(message)]
+    fn remaining_capacity(&self, course_id: CourseId) -> Option<u32>;
+
+    /// Returns the state of the course, `None` if it doesn't exist
+    #[ink(message)]
+    fn course_state(&self, course_id: CourseId) -> Option<CourseState>;
+
+    /// Returns true if the student held a seat in the course at `as_of`
+    ///
+    /// answers from the recorded enrollment periods, only the latest
+    /// periods of a student in a course are kept and none reach past
+    /// the end of the course
+    #[ink(message)]
+    fn verify_enrollment(&self, student: AccountId, course_id: CourseId, as_of: Timestamp) -> bool;
+}
+
+impl CourseReg {
+    pub(crate) fn remaining_capacity_inner(&self, course_id: CourseId) -> Option<u32> {
+        self.courses.get(course_id)
+            .map(|course| course.capacity.saturating_sub(course.enrolled_count))
+    }
+
+    pub(crate) fn course_state_inner(&self, course_id: CourseId) -> Option<CourseState> {
+        self.courses.get(course_id).map(|course| self.course_state_of(&course))
+    }
+
+    pub(crate) fn course_state_of(&self, course: &Course) -> CourseState {
+        let course_id = course.course_id;
+        let now = Self::now();
+        if self.is_course_archived_inner(course_id) {
+            CourseState::Archived
+        } else if self.course_ends.contains(course_id) {
+            CourseState::Ended
+        } else if now < self.registration_opening(course) {
+            CourseState::Announced
+        } else if now < self.registration_deadline(course) {
+            CourseState::RegistrationOpen
+        } else {
+            CourseState::RegistrationClosed
+        }
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::reader::CourseRegistryReader;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Registry reader queries test
+    #[ink::test]
+    fn registry_reader() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert!(CourseRegistryReader::is_member(&course_reg, student));
+        assert_eq!(course_reg.course_state(course_id), Some(CourseState::RegistrationOpen));
+        assert_eq!(course_reg.course_state(CourseId([0x1; 32])), None);
+
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        assert!(course_reg.is_enrolled(course_id, student));
+        assert_eq!(course_reg.remaining_capacity(course_id), Some(9));
+        TestClock::set(start_time);
+        assert_eq!(course_reg.course_state(course_id), Some(CourseState::RegistrationClosed));
+        set_next_caller(owner);
+        assert_eq!(course_reg.end_course(course_id, None), Ok(()));
+        assert_eq!(course_reg.course_state(course_id), Some(CourseState::Ended));
+        TestClock::set(start_time + 100 * MILLIS_PER_DAY);
+        assert_eq!(course_reg.course_state(course_id), Some(CourseState::Archived));
+    }
+}
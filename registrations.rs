@@ -0,0 +1,394 @@
+//! Registration tokens and the enrollment of students to courses
+
+use ink_lang::codegen::{EmitEvent, StaticEnv};
+use ink_prelude::vec::Vec;
+
+use crate::course_reg::{CourseReg, Dropped, Registered, RegistrationClosed, RegistrationOpened};
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn register_to_course_inner(&mut self, course_id: CourseId) -> Result<(), Error> {
+        self.register_to_courses_inner(ink_prelude::vec![course_id])
+    }
+
+    /// registers the caller to every course or to none of them,
+    /// the courses of the batch count as registered for the corequisites
+    pub(crate) fn register_to_courses_inner(&mut self, course_ids: Vec<CourseId>) -> Result<(), Error> {
+        let caller = Self::env().caller();
+        for course_id in &course_ids {
+            self.mark_registration_phase(*course_id);
+            self.ensure_not_collecting(*course_id)?;
+            self.settle_requests(*course_id);
+        }
+        self.check_register_to_courses(caller, &course_ids)?;
+        for course_id in course_ids {
+            self.move_registration(course_id, None, Some(caller))?;
+            self.record_activity(caller, course_id, ActivityKind::Added);
+        }
+        Ok(())
+    }
+
+    /// fails with the error registering the student to the courses would fail with
+    pub(crate) fn check_register_to_courses(&self, student: AccountId, course_ids: &[CourseId]) -> Result<(), Error> {
+        self.ensure_version()?;
+        if !self.is_school_member_inner(student) {
+            return Err(Error::InsufficientPermissions);
+        }
+        for position in 0..course_ids.len() {
+            self.check_batch_registration(student, course_ids, position)?;
+        }
+        Ok(())
+    }
+
+    /// fails if the student can't register to the course at `position`
+    /// of the batch, the other courses of the batch count as registered
+    pub(crate) fn check_batch_registration(&self,
+                                           student: AccountId,
+                                           course_ids: &[CourseId],
+                                           position: usize) -> Result<(), Error> {
+        let course_id = course_ids[position];
+        let earlier = &course_ids[..position];
+        if earlier.contains(&course_id) {
+            return Err(Error::AlreadyRegistered);
+        }
+        if self.supervision_topics.contains(course_id) {
+            return Err(Error::ApplicationRequired);
+        }
+        if self.feature_enabled(FEATURE_REGISTRATION_QUESTIONS) && self.registration_questions.contains(course_id) {
+            return Err(Error::AnswerRequired);
+        }
+        self.check_registration(student, course_id, earlier)?;
+        self.ensure_corequisites(student, course_id, course_ids)?;
+        self.ensure_hook_allows(course_id, student)
+    }
+
+    /// fails if the student can't register to the course
+    /// after registering to the `earlier` courses of the batch
+    pub(crate) fn check_registration(&self,
+                                     student: AccountId,
+                                     course_id: CourseId,
+                                     earlier: &[CourseId]) -> Result<(), Error> {
+        let course = self.courses.get(course_id).ok_or(Error::NonexistentCourse)?;
+        if course.enrolled_count >= course.capacity {
+            return Err(Error::CourseCapacityFull { capacity: course.capacity });
+        }
+        self.check_seat_taker(student, &course, earlier)
+    }
+
+    /// fails if the student can't take a seat in the course, whether a free
+    /// one or one handed over, after registering to the `earlier` courses
+    pub(crate) fn check_seat_taker(&self,
+                                   student: AccountId,
+                                   course: &Course,
+                                   earlier: &[CourseId]) -> Result<(), Error> {
+        let course_id = course.course_id;
+        self.ensure_membership_active(student)?;
+        let opens_at = self.registration_opening(course);
+        if Self::now() < opens_at {
+            return Err(Error::RegistrationNotOpen { opens_at });
+        }
+        if self.enrollments.contains((course_id, student)) {
+            return Err(Error::AlreadyRegistered);
+        }
+        let closed_at = self.registration_deadline(course);
+        if closed_at <= Self::now() {
+            return Err(Error::RegistrationClosed { closed_at });
+        }
+        self.ensure_standing_allows(student, course_id, earlier)?;
+        let held = self.registration_index.get(student).unwrap_or_default().len();
+        if held.saturating_add(earlier.len()) >= MAX_REGISTRATIONS_PER_ACCOUNT as usize {
+            return Err(Error::TooManyRegistrations);
+        }
+        Ok(())
+    }
+
+    /// emits the markers of the registration boundaries of the course
+    /// that passed since the last call, once per boundary
+    ///
+    /// the markers persist only if the calling message succeeds, a failed
+    /// call is reverted with them; the next successful call or `poke`
+    /// on the course writes them then
+    pub(crate) fn mark_registration_phase(&mut self, course_id: CourseId) {
+        let course = match self.courses.get(course_id) {
+            Some(course) => course,
+            None => return,
+        };
+        let now = Self::now();
+        let marked = self.registration_phases.get(course_id).unwrap_or_default();
+        let phase = if now >= self.registration_deadline(&course) {
+            RegistrationPhase::Closed
+        } else if now >= self.registration_opening(&course) {
+            RegistrationPhase::Opened
+        } else {
+            RegistrationPhase::Announced
+        };
+        if marked == phase {
+            return;
+        }
+        if marked == RegistrationPhase::Announced {
+            Self::env().emit_event(RegistrationOpened { course_id });
+        }
+        if phase == RegistrationPhase::Closed {
+            Self::env().emit_event(RegistrationClosed { course_id });
+        }
+        self.registration_phases.insert(course_id, &phase);
+    }
+
+    /// moves the seat in the course from one student to another
+    ///
+    /// the only place enrollment changes hands, besides a key rotation
+    /// re-pointing a student's own seats: `from: None` fills a new seat,
+    /// `to: None` frees it. The registration token is canonical, the
+    /// enrollment set, the roster and the enrolled count are derived
+    /// from it and updated together here, the token itself moves
+    /// through the primitives of the tokens module
+    ///
+    /// a seat filled or freed is announced with the seats taken around it
+    pub(crate) fn move_registration(&mut self,
+                                    course_id: CourseId,
+                                    from: Option<AccountId>,
+                                    to: Option<AccountId>) -> Result<(), Error> {
+        let mut roster = self.rosters.get(course_id).unwrap_or_default();
+        let seats_taken_before = u32::try_from(roster.len()).map_err(|_| Error::ArithmeticOverflow)?;
+        let seat = from.and_then(|from| roster.iter().position(|student| student == &from));
+        match (seat, to) {
+            (Some(seat), Some(to)) => roster[seat] = to,
+            (Some(seat), None) => {
+                roster.remove(seat);
+            }
+            (None, Some(to)) => roster.push(to),
+            (None, None) => (),
+        }
+        let enrolled_count = u32::try_from(roster.len()).map_err(|_| Error::ArithmeticOverflow)?;
+        match (seat.and(from), to) {
+            (Some(student), None) => Self::env().emit_event(Dropped {
+                student,
+                course_id,
+                seats_taken_before,
+                seats_taken_after: enrolled_count,
+            }),
+            (None, Some(student)) => Self::env().emit_event(Registered {
+                student,
+                course_id,
+                seats_taken_before,
+                seats_taken_after: enrolled_count,
+            }),
+            _ => (),
+        }
+
+        self.move_token(course_id, from, to);
+        if let Some(from) = from {
+            self.enrollments.remove((course_id, from));
+            self.record_enrollment_end(course_id, from);
+            self.warn_dropped_corequisite(from, course_id);
+        }
+        if let Some(to) = to {
+            self.enrollments.insert((course_id, to), &());
+            self.record_enrollment_start(course_id, to);
+        }
+        if let Some(mut course) = self.courses.get(course_id) {
+            course.enrolled_count = enrolled_count;
+            self.courses.insert(course_id, &course);
+        }
+        self.rosters.insert(course_id, &roster);
+        if let (None, Some(to)) = (seat, to) {
+            self.notify_hook(course_id, to);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn get_own_registrations_inner(&self) -> Result<Vec<RegistrationView>,Error> {
+        let caller = Self::env().caller();
+        let registrations: Vec<RegistrationView> = self.registration_index.get(caller)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|course_id| self.registrations.get(TokenId { owner: caller, course_id }))
+            .map(Self::registration_view)
+            .collect();
+        if registrations.is_empty() {
+            return Err(Error::NoRegistrations);
+        }
+        Ok(registrations)
+    }
+
+    pub(crate) fn is_registered_inner(&self, course_id: CourseId, account: AccountId) -> bool {
+        self.enrollments.contains((course_id, account))
+    }
+
+    pub(crate) fn get_course_registrations_inner(&self,
+                                                 course_id: CourseId,
+                                                 cursor: Option<Cursor>,
+                                                 count: u32) -> Result<Page<AccountId>, Error> {
+        if !self.courses.contains(course_id) {
+            return Err(Error::NonexistentCourse);
+        }
+        let roster = self.rosters.get(course_id).unwrap_or_default();
+        Self::page(roster, cursor, count, |student| *student.as_ref())
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+    use ink_prelude::vec::Vec;
+
+    /// Course registration test
+    #[ink::test]
+    fn course_registration() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let teacher = AccountId::from([0x1; 32]);
+        let student = AccountId::from([0x2; 32]);
+        let course_name = "test_course".as_bytes();
+        let course_id = CourseId(hash_keccak_256(course_name));
+        let course_cap:u32 = 10;
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+
+        assert_eq!(course_reg.admit_as_teacher(teacher, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert!(course_reg.is_school_member(teacher));
+        assert!(course_reg.is_teacher(teacher));
+        assert!(course_reg.is_school_member(student));
+        set_next_caller(teacher);
+        assert_eq!(course_reg.create_course(course_id, course_cap, start_time, term_id, None, None), Ok(()));
+        assert_ne!(course_reg.get_course_info(course_id), Err(Error::NonexistentCourse));
+        set_next_caller(student);
+
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()),Ok(()));
+        assert_ne!(course_reg.get_own_registrations(), Err(Error::NoRegistrations));
+    }
+
+    /// Enrollment count and duplicate registration test
+    #[ink::test]
+    fn course_enrollment_count() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let teacher = AccountId::from([0x1; 32]);
+        let student1 = AccountId::from([0x2; 32]);
+        let student2 = AccountId::from([0x3; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_teacher(teacher, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student1, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student2, None), Ok(()));
+        set_next_caller(teacher);
+        assert_eq!(course_reg.create_course(course_id, 1, start_time, term_id, None, None), Ok(()));
+
+        set_next_caller(student1);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.register_to_course(course_id, None), Err(Error::CourseCapacityFull { capacity: 1 }));
+        set_next_caller(student2);
+        assert_eq!(course_reg.register_to_course(course_id, None), Err(Error::CourseCapacityFull { capacity: 1 }));
+        assert_eq!(course_reg.get_course_info(course_id).unwrap().enrolled_count, 1);
+        assert!(course_reg.is_registered(course_id, student1));
+        assert!(!course_reg.is_registered(course_id, student2));
+        assert_eq!(course_reg.get_course_registrations(course_id, None, 10).map(|page| page.items), Ok(ink_prelude::vec![student1]));
+        assert_eq!(course_reg.get_course_registrations(course_id, Some([0x2; 32]), 10),
+                   Ok(Page { items: Vec::new(), next_cursor: None, total: 1 }));
+    }
+
+    /// Registration period markers test
+    #[ink::test]
+    fn registration_period_events() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term = Term {
+            start: start_time - MILLIS_PER_DAY,
+            end: start_time + 100 * MILLIS_PER_DAY,
+            add_deadline: start_time,
+            drop_deadline: start_time,
+            swap_deadline: start_time,
+        };
+        let term_id = course_reg.create_term(term, None).unwrap();
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert_eq!(ink_env::test::recorded_events().count(), 1);
+
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(course_id, None),
+                   Err(Error::RegistrationNotOpen { opens_at: term.start }));
+        assert_eq!(ink_env::test::recorded_events().count(), 1);
+        TestClock::set(term.start);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.register_to_course(course_id, None), Err(Error::AlreadyRegistered));
+        // the opening marker and the registration itself
+        assert_eq!(ink_env::test::recorded_events().count(), 3);
+        TestClock::set(start_time);
+        assert_eq!(course_reg.register_to_course(course_id, None), Err(Error::AlreadyRegistered));
+        assert_eq!(ink_env::test::recorded_events().count(), 4);
+    }
+
+    /// Roster pages stay stable across changes test
+    #[ink::test]
+    fn roster_pages() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let students: Vec<AccountId> = (1..=5u8).map(|i| AccountId::from([i; 32])).collect();
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        for student in &students {
+            assert_eq!(course_reg.admit_as_student(*student, None), Ok(()));
+            set_next_caller(*student);
+            assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+            set_next_caller(owner);
+        }
+        let page = course_reg.get_course_registrations(course_id, None, 2).unwrap();
+        assert_eq!((page.items.as_slice(), page.total), (&students[..2], 5));
+
+        // a student of the first page leaves, the next page still follows the last one seen
+        assert_eq!(course_reg.move_registration(course_id, Some(students[0]), None), Ok(()));
+        let page = course_reg.get_course_registrations(course_id, page.next_cursor, 2).unwrap();
+        assert_eq!((page.items.as_slice(), page.total), (&students[2..4], 4));
+
+        // the last one seen leaves too, the next page resumes at the student after them
+        assert_eq!(course_reg.move_registration(course_id, Some(students[3]), None), Ok(()));
+        let page = course_reg.get_course_registrations(course_id, page.next_cursor, 2).unwrap();
+        assert_eq!(page, Page { items: students[4..].to_vec(), next_cursor: None, total: 3 });
+    }
+
+    /// Seat counts of the registration events test
+    #[ink::test]
+    fn seat_count_events() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.move_registration(course_id, Some(student), None), Ok(()));
+
+        // the registration and the drop are the last events, after the variant index
+        // both carry the student, the course and the seats taken before and after
+        let events: Vec<_> = ink_env::test::recorded_events().collect();
+        let seat_counts: Vec<(u8, AccountId, CourseId, u32, u32)> = events[events.len() - 2..].iter()
+            .map(|event| scale::Decode::decode(&mut &event.data[..]).unwrap())
+            .collect();
+        assert_eq!((seat_counts[0].1, seat_counts[0].2, seat_counts[0].3, seat_counts[0].4), (student, course_id, 0, 1));
+        assert_eq!((seat_counts[1].1, seat_counts[1].2, seat_counts[1].3, seat_counts[1].4), (student, course_id, 1, 0));
+        assert_ne!(seat_counts[0].0, seat_counts[1].0);
+    }
+}
@@ -0,0 +1,142 @@
+//! Seats handed over from one student to another without passing through the free pool
+
+use ink_lang::codegen::StaticEnv;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn offer_seat_release_inner(&mut self, course_id: CourseId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_SEAT_RELEASES)?;
+        self.ensure_no_blackout()?;
+        let caller = Self::env().caller();
+        self.ensure_membership_active(caller)?;
+        if !self.enrollments.contains((course_id, caller)) {
+            return Err(Error::NotRegistered);
+        }
+        self.ensure_not_frozen(caller, course_id)?;
+        self.ensure_drops_open(course_id)?;
+        if self.seat_releases.contains((course_id, caller)) {
+            return Err(Error::SeatReleaseAlreadyOffered);
+        }
+        self.seat_releases.insert((course_id, caller), &());
+        Ok(())
+    }
+
+    pub(crate) fn withdraw_seat_release_inner(&mut self, course_id: CourseId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_SEAT_RELEASES)?;
+        let caller = Self::env().caller();
+        if !self.seat_releases.contains((course_id, caller)) {
+            return Err(Error::SeatReleaseNotFound);
+        }
+        self.seat_releases.remove((course_id, caller));
+        Ok(())
+    }
+
+    /// moves the releaser's seat to the caller, the seat is never free in between
+    pub(crate) fn claim_released_seat_inner(&mut self, course_id: CourseId, releaser: AccountId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_SEAT_RELEASES)?;
+        self.ensure_no_blackout()?;
+        let caller = Self::env().caller();
+        self.require_role(Role::Member)?;
+        // an offer outlives the seat if the releaser left the course some other way
+        if !self.seat_releases.contains((course_id, releaser))
+            || !self.enrollments.contains((course_id, releaser)) {
+            return Err(Error::SeatReleaseNotFound);
+        }
+        self.ensure_not_frozen(releaser, course_id)?;
+        self.ensure_drops_open(course_id)?;
+        let course = self.courses.get(course_id).ok_or(Error::NonexistentCourse)?;
+        self.mark_registration_phase(course_id);
+        self.check_seat_taker(caller, &course, &[])?;
+        self.ensure_corequisites(caller, course_id, &[])?;
+        self.seat_releases.remove((course_id, releaser));
+        self.move_registration(course_id, Some(releaser), Some(caller))?;
+        self.record_activity(releaser, course_id, ActivityKind::Dropped);
+        self.record_activity(caller, course_id, ActivityKind::Added);
+        Ok(())
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+    use ink_prelude::vec;
+
+    /// Seat release to a claiming student test
+    #[ink::test]
+    fn seat_release() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let releaser = AccountId::from([0x1; 32]);
+        let claimer = AccountId::from([0x2; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.create_course(course_id, 1, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(releaser, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(claimer, None), Ok(()));
+
+        set_next_caller(releaser);
+        assert_eq!(course_reg.offer_seat_release(course_id, None), Err(Error::NotRegistered));
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.offer_seat_release(course_id, None), Ok(()));
+        assert_eq!(course_reg.offer_seat_release(course_id, None), Err(Error::SeatReleaseAlreadyOffered));
+        assert!(course_reg.is_seat_released(course_id, releaser));
+
+        // the full course's seat goes straight to the claimer
+        set_next_caller(claimer);
+        assert_eq!(course_reg.register_to_course(course_id, None), Err(Error::CourseCapacityFull { capacity: 1 }));
+        assert_eq!(course_reg.claim_released_seat(course_id, owner, None), Err(Error::SeatReleaseNotFound));
+        assert_eq!(course_reg.claim_released_seat(course_id, releaser, None), Ok(()));
+        assert!(!course_reg.is_registered(course_id, releaser));
+        assert_eq!(course_reg.get_course_registrations(course_id, None, 10).map(|page| page.items), Ok(vec![claimer]));
+        assert_eq!(course_reg.claim_released_seat(course_id, releaser, None), Err(Error::SeatReleaseNotFound));
+    }
+
+    /// Seat release closing at the drop deadline test
+    #[ink::test]
+    fn seat_release_after_drop_deadline() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let releaser = AccountId::from([0x1; 32]);
+        let late_releaser = AccountId::from([0x2; 32]);
+        let claimer = AccountId::from([0x3; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        let drop_deadline = course_reg.get_term(term_id).unwrap().drop_deadline;
+        assert_eq!(course_reg.create_course(course_id, 2, start_time, term_id, None, None), Ok(()));
+        for student in [releaser, late_releaser, claimer] {
+            assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        }
+        for student in [releaser, late_releaser] {
+            set_next_caller(student);
+            assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        }
+        set_next_caller(releaser);
+        assert_eq!(course_reg.offer_seat_release(course_id, None), Ok(()));
+
+        // once dropping closed no seat is offered or handed over
+        TestClock::set(drop_deadline);
+        set_next_caller(late_releaser);
+        assert_eq!(course_reg.offer_seat_release(course_id, None), Err(Error::DropsClosed { closed_at: drop_deadline }));
+        set_next_caller(claimer);
+        assert_eq!(course_reg.claim_released_seat(course_id, releaser, None),
+                   Err(Error::DropsClosed { closed_at: drop_deadline }));
+        assert!(course_reg.is_registered(course_id, releaser));
+    }
+}
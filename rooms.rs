@@ -0,0 +1,135 @@
+//! The rooms of the school, a course can't seat more students than its room
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    /// adds the room or changes its capacity, the courses held in it have to fit
+    pub(crate) fn set_room_inner(&mut self, room_id: RoomId, capacity: u32) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.require_role(Role::Owner)?;
+        if capacity == 0 {
+            return Err(Error::InvalidCapacity);
+        }
+        let overflows = self.room_courses.get(room_id).unwrap_or_default()
+            .into_iter()
+            .filter_map(|course_id| self.courses.get(course_id))
+            .any(|course| course.capacity > capacity);
+        if overflows {
+            return Err(Error::RoomTooSmall { capacity });
+        }
+        self.rooms.insert(room_id, &capacity);
+        Ok(())
+    }
+
+    /// moves the course to the room, or out of any room with `None`,
+    /// the course's teacher or the owner can move it
+    pub(crate) fn set_course_room_inner(&mut self, course_id: CourseId, room_id: Option<RoomId>) -> Result<(), Error> {
+        self.ensure_version()?;
+        let course = self.require_course_admin(course_id)?;
+        if let Some(room_id) = room_id {
+            self.ensure_room_fits(room_id, course.capacity)?;
+            self.ensure_room_has_space(room_id)?;
+        }
+        if let Some(previous) = self.course_rooms.get(course_id) {
+            let mut courses = self.room_courses.get(previous).unwrap_or_default();
+            courses.retain(|held| *held != course_id);
+            self.room_courses.insert(previous, &courses);
+            self.course_rooms.remove(course_id);
+        }
+        if let Some(room_id) = room_id {
+            self.place_in_room(course_id, room_id);
+        }
+        Ok(())
+    }
+
+    /// lists the course, held in no room, among the courses of the room
+    pub(crate) fn place_in_room(&mut self, course_id: CourseId, room_id: RoomId) {
+        let mut courses = self.room_courses.get(room_id).unwrap_or_default();
+        courses.push(course_id);
+        self.room_courses.insert(room_id, &courses);
+        self.course_rooms.insert(course_id, &room_id);
+    }
+
+    /// fails if the room already holds `MAX_COURSES_PER_ROOM` courses
+    pub(crate) fn ensure_room_has_space(&self, room_id: RoomId) -> Result<(), Error> {
+        if self.room_courses.get(room_id).unwrap_or_default().len() >= MAX_COURSES_PER_ROOM as usize {
+            return Err(Error::TooManyCoursesInRoom);
+        }
+        Ok(())
+    }
+
+    /// fails if the room doesn't exist or can't seat the capacity
+    pub(crate) fn ensure_room_fits(&self, room_id: RoomId, capacity: u32) -> Result<(), Error> {
+        let room_capacity = self.rooms.get(room_id).ok_or(Error::NonexistentRoom)?;
+        if capacity > room_capacity {
+            return Err(Error::RoomTooSmall { capacity: room_capacity });
+        }
+        Ok(())
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Room capacity linkage test
+    #[ink::test]
+    fn room_capacity() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let teacher = AccountId::from([0x1; 32]);
+        let course_id1 = CourseId(hash_keccak_256("test_course1".as_bytes()));
+        let course_id2 = CourseId(hash_keccak_256("test_course2".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_teacher(teacher, None), Ok(()));
+        assert_eq!(course_reg.set_room(1, 0, None), Err(Error::InvalidCapacity));
+        assert_eq!(course_reg.set_room(1, 20, None), Ok(()));
+        assert_eq!(course_reg.set_room(2, 50, None), Ok(()));
+
+        set_next_caller(teacher);
+        assert_eq!(course_reg.set_room(3, 50, None), Err(Error::InsufficientPermissions));
+        assert_eq!(course_reg.create_course(course_id1, 30, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id2, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.set_course_room(course_id1, Some(3), None), Err(Error::NonexistentRoom));
+        assert_eq!(course_reg.set_course_room(course_id1, Some(1), None), Err(Error::RoomTooSmall { capacity: 20 }));
+        assert_eq!(course_reg.set_course_room(course_id1, Some(2), None), Ok(()));
+        assert_eq!(course_reg.set_course_room(course_id2, Some(2), None), Ok(()));
+
+        // the room can't shrink below a course held in it
+        set_next_caller(owner);
+        assert_eq!(course_reg.set_room(2, 20, None), Err(Error::RoomTooSmall { capacity: 20 }));
+        assert_eq!(course_reg.set_course_room(course_id1, None, None), Ok(()));
+        assert_eq!(course_reg.set_room(2, 20, None), Ok(()));
+        assert_eq!(course_reg.get_course_room(course_id1), None);
+        assert_eq!(course_reg.get_course_room(course_id2), Some(2));
+        assert_eq!(course_reg.get_room_capacity(2), Some(20));
+    }
+
+    /// Course creation in a room test
+    #[ink::test]
+    fn course_created_in_room() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.set_room(1, 20, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, Some(2), None), Err(Error::NonexistentRoom));
+        assert_eq!(course_reg.create_course(course_id, 30, start_time, term_id, Some(1), None),
+                   Err(Error::RoomTooSmall { capacity: 20 }));
+        assert_eq!(course_reg.create_course(course_id, 20, start_time, term_id, Some(1), None), Ok(()));
+        assert_eq!(course_reg.get_course_room(course_id), Some(1));
+        assert_eq!(course_reg.set_room(1, 10, None), Err(Error::RoomTooSmall { capacity: 10 }));
+    }
+}
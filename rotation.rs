@@ -0,0 +1,228 @@
+//! Key rotation, re-pointing a student's records from a lost key to a new one
+
+use ink_lang::codegen::{EmitEvent, StaticEnv};
+use ink_prelude::vec::Vec;
+use ink_storage::traits::PackedLayout;
+use ink_storage::Mapping;
+
+use crate::course_reg::{CourseReg, KeyRotated};
+use crate::errors::Error;
+use crate::types::*;
+
+/// moves the entry of the mapping from one key to another
+fn rekey<K, V>(map: &mut Mapping<K, V>, from: K, to: K)
+where
+    K: PackedLayout + scale::EncodeLike + Copy,
+    V: PackedLayout + scale::EncodeLike,
+{
+    if let Some(value) = map.get(from) {
+        map.remove(from);
+        map.insert(to, &value);
+    }
+}
+
+/// replaces one account with another in the per-course list of accounts
+fn replace_in_index(map: &mut Mapping<CourseId, Vec<AccountId>>, course_id: CourseId, from: AccountId, to: AccountId) {
+    let mut accounts = map.get(course_id).unwrap_or_default();
+    if accounts.contains(&from) {
+        for account in accounts.iter_mut().filter(|account| **account == from) {
+            *account = to;
+        }
+        map.insert(course_id, &accounts);
+    }
+}
+
+impl CourseReg {
+    /// re-points the student's membership, registrations, escrowed tokens
+    /// and course records from `old` to `new`, approved by the registrar
+    /// or by the old key itself
+    ///
+    /// the records keyed by session, assignment, exam or term ids stay
+    /// with the old key, they can't be enumerated per student; the cohort
+    /// memberships are looked up in every cohort
+    pub(crate) fn rotate_key_inner(&mut self, old: AccountId, new: AccountId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.require_self_or_role(old, Role::Owner)?;
+        if !self.is_school_member_inner(old) {
+            return Err(Error::NotSchoolMember);
+        }
+        if self.is_teacher_inner(old) {
+            return Err(Error::InvalidRoleTransition);
+        }
+        if self.is_school_member_inner(new) || self.alumni.contains(new)
+            || !self.registration_index.get(new).unwrap_or_default().is_empty() {
+            return Err(Error::AccountInUse);
+        }
+
+        // the seats, held or escrowed, and the courses with records
+        let held = self.registration_index.get(old).unwrap_or_default();
+        let mut proposal_ids = self.escrow_index.get(old).unwrap_or_default();
+        proposal_ids.sort_unstable();
+        proposal_ids.dedup();
+        let mut seats = held.clone();
+        for proposal_id in &proposal_ids {
+            if let Some(mut proposal) = self.proposals.get(proposal_id) {
+                let tokens = core::iter::once(&mut proposal.offer).chain(proposal.counter_offers.iter_mut());
+                for token in tokens.filter(|token| token.owner == old) {
+                    token.owner = new;
+                    seats.push(token.course_id);
+                }
+                self.proposals.insert(proposal_id, &proposal);
+            }
+        }
+        for course_id in &seats {
+            self.rotate_seat(*course_id, old, new);
+        }
+        for course_id in held {
+            self.move_token(course_id, Some(old), Some(new));
+        }
+        rekey(&mut self.escrow_index, old, new);
+        let transcript = self.transcripts.get(old).unwrap_or_default();
+        let courses: Vec<CourseId> = seats.into_iter().chain(transcript).collect();
+        for course_id in courses {
+            rekey(&mut self.grades, (course_id, old), (course_id, new));
+            rekey(&mut self.attendance, (course_id, old), (course_id, new));
+            rekey(&mut self.evaluations, (course_id, old), (course_id, new));
+            rekey(&mut self.failed_exams, (course_id, old), (course_id, new));
+            rekey(&mut self.registration_answers, (course_id, old), (course_id, new));
+            rekey(&mut self.frozen_registrations, (course_id, old), (course_id, new));
+            rekey(&mut self.enrollment_periods, (course_id, old), (course_id, new));
+            self.rotate_booking(course_id, old, new);
+        }
+        rekey(&mut self.transcripts, old, new);
+
+        // the offers and requests for courses the student has no seat in
+        for course_id in self.standing_counter_courses.get(old).unwrap_or_default() {
+            rekey(&mut self.standing_counters, (course_id, old), (course_id, new));
+            replace_in_index(&mut self.standing_counter_index, course_id, old, new);
+        }
+        rekey(&mut self.standing_counter_courses, old, new);
+        for course_id in self.seat_request_courses.get(old).unwrap_or_default() {
+            replace_in_index(&mut self.seat_requests, course_id, old, new);
+        }
+        rekey(&mut self.seat_request_courses, old, new);
+
+        // the membership and the student's own records
+        rekey(&mut self.school_members, old, new);
+        rekey(&mut self.activation_dates, old, new);
+        rekey(&mut self.membership_expiries, old, new);
+        rekey(&mut self.student_programs, old, new);
+        rekey(&mut self.standings, old, new);
+        rekey(&mut self.accommodations, old, new);
+        rekey(&mut self.profiles, old, new);
+        rekey(&mut self.notification_keys, old, new);
+        rekey(&mut self.plans, old, new);
+        rekey(&mut self.swap_reputations, old, new);
+        for cohort_id in 0..self.next_cohort_id.get_or_default() {
+            rekey(&mut self.cohort_members, (cohort_id, old), (cohort_id, new));
+        }
+        let mut pending = self.pending_activations.get_or_default();
+        for account in pending.iter_mut().filter(|account| **account == old) {
+            *account = new;
+        }
+        self.pending_activations.set(&pending);
+        Self::env().emit_event(KeyRotated { old, new });
+        Ok(())
+    }
+
+    /// hands the student's seat in the course from the old key to the new
+    /// one, the seat keeps its place and the token moves separately
+    fn rotate_seat(&mut self, course_id: CourseId, old: AccountId, new: AccountId) {
+        replace_in_index(&mut self.rosters, course_id, old, new);
+        if self.enrollments.contains((course_id, old)) {
+            self.enrollments.remove((course_id, old));
+            self.enrollments.insert((course_id, new), &());
+        }
+    }
+
+    /// hands the student's office-hour booking in the course, and the
+    /// booked slot, from the old key to the new one
+    fn rotate_booking(&mut self, course_id: CourseId, old: AccountId, new: AccountId) {
+        if let Some(slot_id) = self.office_hour_bookings.get((course_id, old)) {
+            rekey(&mut self.office_hour_bookings, (course_id, old), (course_id, new));
+            if let Some(mut slot) = self.office_hours.get((course_id, slot_id)) {
+                slot.booked_by = Some(new);
+                self.office_hours.insert((course_id, slot_id), &slot);
+            }
+        }
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+    use ink_prelude::vec;
+
+    /// Key rotation of a student test
+    #[ink::test]
+    fn key_rotation() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let old = AccountId::from([0x1; 32]);
+        let new = AccountId::from([0x2; 32]);
+        let other = AccountId::from([0x3; 32]);
+        let course_id1 = CourseId(hash_keccak_256("test_course1".as_bytes()));
+        let course_id2 = CourseId(hash_keccak_256("test_course2".as_bytes()));
+        let wanted_id = CourseId(hash_keccak_256("wanted_course".as_bytes()));
+        let requested_id = CourseId(hash_keccak_256("requested_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        let next_term_id = create_term(&mut course_reg, start_time + 30 * MILLIS_PER_DAY);
+        assert_eq!(course_reg.admit_as_student(old, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(other, None), Ok(()));
+        for course_id in [course_id1, course_id2, wanted_id] {
+            assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        }
+        assert_eq!(course_reg.create_course(requested_id, 10, start_time + 30 * MILLIS_PER_DAY, next_term_id, None, None), Ok(()));
+        assert_eq!(course_reg.set_tie_break_policy(requested_id, TieBreakPolicy::Seniority, None), Ok(()));
+        let cohort_id = course_reg.create_cohort("class of 2027".as_bytes().to_vec(), None).unwrap();
+        assert_eq!(course_reg.add_to_cohort(cohort_id, old, None), Ok(()));
+        let window = OfficeHourWindow { starts: start_time, ends: start_time + 1000 };
+        assert_eq!(course_reg.publish_office_hours(course_id1, vec![window], None), Ok(vec![0]));
+        set_next_caller(old);
+        assert_eq!(course_reg.register_to_courses(vec![course_id1, course_id2], None).map(|_| ()), Ok(()));
+        let proposal_id = course_reg.propose_swap(course_id2, None).unwrap();
+        assert_eq!(course_reg.set_standing_counter(wanted_id, course_id1, None), Ok(()));
+        assert_eq!(course_reg.book_office_hour(course_id1, 0, None), Ok(()));
+        TestClock::set(course_reg.get_term(next_term_id).unwrap().start);
+        assert_eq!(course_reg.request_seat(requested_id, None), Ok(()));
+
+        set_next_caller(other);
+        assert_eq!(course_reg.rotate_key(old, new, None), Err(Error::InsufficientPermissions));
+        set_next_caller(owner);
+        assert_eq!(course_reg.rotate_key(old, other, None), Err(Error::AccountInUse));
+        assert_eq!(course_reg.rotate_key(owner, new, None), Err(Error::InvalidRoleTransition));
+        assert_eq!(course_reg.rotate_key(old, new, None), Ok(()));
+
+        assert!(!course_reg.is_school_member(old));
+        assert!(course_reg.is_school_member(new));
+        assert!(course_reg.is_registered(course_id1, new));
+        assert!(course_reg.is_registered(course_id2, new));
+        assert!(!course_reg.is_registered(course_id1, old));
+        assert_eq!(course_reg.get_escrowed(new), vec![EscrowedToken { proposal_id, course_id: course_id2 }]);
+        assert_eq!(course_reg.get_swap_proposal(proposal_id).unwrap().proposer, new);
+        assert_eq!(course_reg.check_invariants(vec![course_id1, course_id2], vec![old, new]), Ok(vec![]));
+        assert!(course_reg.in_cohort(cohort_id, new) && !course_reg.in_cohort(cohort_id, old));
+        assert_eq!(course_reg.get_standing_counter(new, wanted_id), Some(course_id1));
+        assert_eq!(course_reg.get_standing_counter(old, wanted_id), None);
+        assert_eq!(course_reg.standing_counter_index.get(wanted_id), Some(vec![new]));
+        assert_eq!(course_reg.office_hour_bookings.get((course_id1, new)), Some(0));
+        assert_eq!(course_reg.get_office_hours(course_id1, None, 10).unwrap().items[0].booked_by, Some(new));
+        assert_eq!(course_reg.get_seat_requests(requested_id), vec![new]);
+        assert_eq!(course_reg.seat_request_courses.get(new), Some(vec![requested_id]));
+        assert!(!course_reg.seat_request_courses.contains(old));
+
+        // the new key takes the escrowed token back
+        set_next_caller(new);
+        assert_eq!(course_reg.cancel_swap_proposal(proposal_id, None), Ok(()));
+        assert_eq!(course_reg.get_own_registrations().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,83 @@
+//! Commitments to the students' schedules, for campus systems to verify
+//! a schedule the student hands them without reading the rosters
+
+use ink_env::hash;
+use ink_prelude::vec::Vec;
+use scale::Encode;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    /// returns the Keccak256 hash of the encoded `(SCHEDULE_DOMAIN, student, term_id, entries)`,
+    /// the entries are the student's courses of the term sorted by course id
+    pub(crate) fn get_schedule_commitment_inner(&self, student: AccountId, term_id: TermId) -> Result<[u8; 32], Error> {
+        if !self.terms.contains(term_id) {
+            return Err(Error::NonexistentTerm);
+        }
+        // the seats offered in a swap are still the student's
+        let held = self.registration_index.get(student).unwrap_or_default();
+        let escrowed = self.get_escrowed_inner(student).into_iter().map(|token| token.course_id);
+        let mut entries: Vec<ScheduleEntry> = held.into_iter()
+            .chain(escrowed)
+            .filter(|course_id| self.course_terms.get(course_id) == Some(term_id))
+            .filter_map(|course_id| self.courses.get(course_id))
+            .map(|course| ScheduleEntry { course_id: course.course_id, start_date: course.start_date })
+            .collect();
+        entries.sort_unstable_by_key(|entry| entry.course_id);
+        entries.dedup();
+        let mut output = <hash::Keccak256 as hash::HashOutput>::Type::default();
+        ink_env::hash_bytes::<hash::Keccak256>(&(SCHEDULE_DOMAIN, student, term_id, entries).encode(), &mut output);
+        Ok(output)
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_env::hash;
+    use ink_lang as ink;
+    use ink_prelude::vec;
+    use scale::Encode;
+
+    fn commitment(student: AccountId, term_id: TermId, entries: ink_prelude::vec::Vec<ScheduleEntry>) -> [u8; 32] {
+        let mut output = <hash::Keccak256 as hash::HashOutput>::Type::default();
+        ink_env::hash_bytes::<hash::Keccak256>(&(SCHEDULE_DOMAIN, student, term_id, entries).encode(), &mut output);
+        output
+    }
+
+    /// Schedule commitment test
+    #[ink::test]
+    fn schedule_commitment() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let course_id1 = CourseId([0x1; 32]);
+        let course_id2 = CourseId([0x2; 32]);
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id1, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id2, 10, start_time + 1, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.get_schedule_commitment(student, term_id), Ok(commitment(student, term_id, vec![])));
+        assert_eq!(course_reg.get_schedule_commitment(student, term_id + 1), Err(Error::NonexistentTerm));
+
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_courses(vec![course_id2, course_id1], None).map(|_| ()), Ok(()));
+        let expected = commitment(student, term_id, vec![
+            ScheduleEntry { course_id: course_id1, start_date: start_time },
+            ScheduleEntry { course_id: course_id2, start_date: start_time + 1 },
+        ]);
+        assert_eq!(course_reg.get_schedule_commitment(student, term_id), Ok(expected));
+        // a seat offered in a swap stays on the schedule
+        assert!(course_reg.propose_swap(course_id1, None).is_ok());
+        assert_eq!(course_reg.get_schedule_commitment(student, term_id), Ok(expected));
+    }
+}
@@ -0,0 +1,116 @@
+//! Attendance sessions the students check in to with a code
+
+use ink_env::hash;
+use ink_lang::codegen::StaticEnv;
+use ink_prelude::vec::Vec;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn open_session_inner(&mut self,
+                                     course_id: CourseId,
+                                     starts: Timestamp,
+                                     ends: Timestamp,
+                                     code_hash: [u8; 32]) -> Result<SessionId, Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_ATTENDANCE)?;
+        self.require_course_teacher(course_id, Self::env().caller())?;
+        if self.course_ends.contains(course_id) {
+            return Err(Error::CourseAlreadyEnded);
+        }
+        if starts < MIN_TIMESTAMP || starts >= ends || ends <= Self::now() {
+            return Err(Error::InvalidSessionWindow);
+        }
+        let session_id = self.session_counts.get(course_id).unwrap_or(0);
+        if session_id >= MAX_SESSIONS_PER_COURSE {
+            return Err(Error::TooManySessions);
+        }
+        let session_count = session_id.checked_add(1).ok_or(Error::ArithmeticOverflow)?;
+        let session = Session { starts, ends, code_hash };
+        self.sessions.insert((course_id, session_id), &session);
+        self.session_counts.insert(course_id, &session_count);
+        Ok(session_id)
+    }
+
+    pub(crate) fn check_in_inner(&mut self,
+                                 course_id: CourseId,
+                                 session_id: SessionId,
+                                 code: Vec<u8>) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_ATTENDANCE)?;
+        let caller = Self::env().caller();
+        self.ensure_membership_active(caller)?;
+        let session = self.sessions.get((course_id, session_id)).ok_or(Error::SessionNotFound)?;
+        let now = Self::now();
+        if now < session.starts || now > session.ends {
+            return Err(Error::SessionNotOpen);
+        }
+        if !self.enrollments.contains((course_id, caller)) {
+            return Err(Error::NotRegistered);
+        }
+        if self.check_ins.contains((course_id, session_id, caller)) {
+            return Err(Error::AlreadyCheckedIn);
+        }
+        let mut code_hash = <hash::Keccak256 as hash::HashOutput>::Type::default();
+        ink_env::hash_bytes::<hash::Keccak256>(&code, &mut code_hash);
+        if code_hash != session.code_hash {
+            return Err(Error::InvalidCheckInCode);
+        }
+        let attended = self.attendance.get((course_id, caller)).unwrap_or(0)
+            .checked_add(1).ok_or(Error::ArithmeticOverflow)?;
+        self.check_ins.insert((course_id, session_id, caller), &());
+        self.attendance.insert((course_id, caller), &attended);
+        Ok(())
+    }
+
+    pub(crate) fn get_attendance_inner(&self, course_id: CourseId, student: AccountId) -> Attendance {
+        Attendance {
+            attended: self.attendance.get((course_id, student)).unwrap_or(0),
+            sessions: self.session_counts.get(course_id).unwrap_or(0),
+        }
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Session check-in test
+    #[ink::test]
+    fn session_check_in() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        let code = "open sesame".as_bytes().to_vec();
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.open_session(course_id, start_time, start_time, hash_keccak_256(&code), None),
+                   Err(Error::InvalidSessionWindow));
+        let session_end = start_time + 60 * 60 * 1000;
+        assert_eq!(course_reg.open_session(course_id, start_time, session_end, hash_keccak_256(&code), None), Ok(0));
+        assert_eq!(course_reg.open_session(course_id, session_end, session_end + 1, [0x0; 32], None), Ok(1));
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+
+        assert_eq!(course_reg.check_in(course_id, 0, code.clone(), None), Err(Error::SessionNotOpen));
+        TestClock::set(start_time);
+        assert_eq!(course_reg.check_in(course_id, 2, code.clone(), None), Err(Error::SessionNotFound));
+        assert_eq!(course_reg.check_in(course_id, 0, ink_prelude::vec![0x0], None), Err(Error::InvalidCheckInCode));
+        assert_eq!(course_reg.check_in(course_id, 0, code.clone(), None), Ok(()));
+        assert_eq!(course_reg.check_in(course_id, 0, code, None), Err(Error::AlreadyCheckedIn));
+        assert_eq!(course_reg.get_attendance(course_id, student), Attendance { attended: 1, sessions: 2 });
+    }
+}
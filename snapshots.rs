@@ -0,0 +1,100 @@
+//! Roster snapshots, the hashes of course rosters frozen at a point in time
+
+use ink_env::hash;
+use ink_lang::codegen::StaticEnv;
+use ink_prelude::vec::Vec;
+use scale::Encode;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn snapshot_roster_inner(&mut self, course_id: CourseId) -> Result<SnapshotId, Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_ROSTER_SNAPSHOTS)?;
+        self.require_course_teacher(course_id, Self::env().caller())?;
+        let snapshot_id = self.snapshot_counts.get(course_id).unwrap_or(0);
+        if snapshot_id >= MAX_SNAPSHOTS_PER_COURSE {
+            return Err(Error::TooManySnapshots);
+        }
+        let snapshot_count = snapshot_id.checked_add(1).ok_or(Error::ArithmeticOverflow)?;
+        let snapshot = RosterSnapshot {
+            roster_hash: Self::roster_hash(self.rosters.get(course_id).unwrap_or_default()),
+            taken_at: Self::now(),
+        };
+        self.roster_snapshots.insert((course_id, snapshot_id), &snapshot);
+        self.snapshot_counts.insert(course_id, &snapshot_count);
+        Ok(snapshot_id)
+    }
+
+    /// returns true if the claimed roster, in any order, is the snapshotted one
+    pub(crate) fn verify_roster_snapshot_inner(&self,
+                                               course_id: CourseId,
+                                               snapshot_id: SnapshotId,
+                                               roster: Vec<AccountId>) -> Result<bool, Error> {
+        let snapshot = self.roster_snapshots.get((course_id, snapshot_id)).ok_or(Error::SnapshotNotFound)?;
+        Ok(Self::roster_hash(roster) == snapshot.roster_hash)
+    }
+
+    /// returns the Keccak256 hash of the encoded roster sorted by account
+    fn roster_hash(mut roster: Vec<AccountId>) -> [u8; 32] {
+        roster.sort_unstable();
+        let mut output = <hash::Keccak256 as hash::HashOutput>::Type::default();
+        ink_env::hash_bytes::<hash::Keccak256>(&roster.encode(), &mut output);
+        output
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Roster snapshot and verification test
+    #[ink::test]
+    fn roster_snapshots() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student1 = AccountId::from([0x1; 32]);
+        let student2 = AccountId::from([0x2; 32]);
+        let student3 = AccountId::from([0x3; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        for student in [student1, student2, student3] {
+            assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        }
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        set_next_caller(student2);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        set_next_caller(student1);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.snapshot_roster(course_id, None), Err(Error::InsufficientPermissions));
+
+        set_next_caller(owner);
+        let now = get_current_time();
+        TestClock::set(now);
+        assert_eq!(course_reg.snapshot_roster(CourseId([0x1; 32]), None), Err(Error::NonexistentCourse));
+        assert_eq!(course_reg.snapshot_roster(course_id, None), Ok(0));
+        assert_eq!(course_reg.get_roster_snapshot(course_id, 0).unwrap().taken_at, now);
+        set_next_caller(student3);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+
+        // the snapshot stays frozen, the claimed order doesn't matter
+        assert_eq!(course_reg.verify_roster_snapshot(course_id, 0, vec![student1, student2]), Ok(true));
+        assert_eq!(course_reg.verify_roster_snapshot(course_id, 0, vec![student2, student1]), Ok(true));
+        assert_eq!(course_reg.verify_roster_snapshot(course_id, 0, vec![student1, student2, student3]), Ok(false));
+        assert_eq!(course_reg.verify_roster_snapshot(course_id, 1, vec![]), Err(Error::SnapshotNotFound));
+        set_next_caller(owner);
+        assert_eq!(course_reg.snapshot_roster(course_id, None), Ok(1));
+        assert_eq!(course_reg.verify_roster_snapshot(course_id, 1, vec![student3, student1, student2]), Ok(true));
+    }
+}
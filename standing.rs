@@ -0,0 +1,90 @@
+//! The academic standing of the students and the registration limits it imposes
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn set_standing_inner(&mut self, student: AccountId, standing: AcademicStanding) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_STANDING)?;
+        self.require_role(Role::Owner)?;
+        if !self.is_school_member_inner(student) {
+            return Err(Error::NotSchoolMember);
+        }
+        self.standings.insert(student, &standing);
+        Ok(())
+    }
+
+    /// fails if the student's standing doesn't allow registering to the course
+    /// next to the `pending` courses registered to in the same call
+    pub(crate) fn ensure_standing_allows(&self,
+                                         student: AccountId,
+                                         course_id: CourseId,
+                                         pending: &[CourseId]) -> Result<(), Error> {
+        if !self.feature_enabled(FEATURE_STANDING) {
+            return Ok(());
+        }
+        match self.standings.get(student).unwrap_or_default() {
+            AcademicStanding::Good => Ok(()),
+            AcademicStanding::Suspended => Err(Error::StudentSuspended),
+            AcademicStanding::Probation => {
+                let mut total = self.credits_in_progress(student)?;
+                for course_id in pending.iter().chain(core::iter::once(&course_id)) {
+                    let credits = self.course_credits.get(course_id).unwrap_or(0);
+                    total = total.checked_add(credits).ok_or(Error::ArithmeticOverflow)?;
+                }
+                if total > PROBATION_CREDIT_CAP {
+                    return Err(Error::CreditCapExceeded { cap: PROBATION_CREDIT_CAP });
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Standing gated registration test
+    #[ink::test]
+    fn standing_registration_limits() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        let first = CourseId(hash_keccak_256("first".as_bytes()));
+        let second = CourseId(hash_keccak_256("second".as_bytes()));
+        for course_id in [first, second] {
+            assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+            assert_eq!(course_reg.set_course_credits(course_id, 8, None), Ok(()));
+        }
+        assert_eq!(course_reg.set_standing(student, AcademicStanding::Suspended, None),
+                   Err(Error::NotSchoolMember));
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert_eq!(course_reg.get_standing(student), AcademicStanding::Good);
+        assert_eq!(course_reg.set_standing(student, AcademicStanding::Suspended, None), Ok(()));
+
+        set_next_caller(student);
+        assert_eq!(course_reg.set_standing(student, AcademicStanding::Good, None),
+                   Err(Error::InsufficientPermissions));
+        assert_eq!(course_reg.register_to_course(first, None), Err(Error::StudentSuspended));
+
+        // on probation the second course would exceed the credit cap
+        set_next_caller(owner);
+        assert_eq!(course_reg.set_standing(student, AcademicStanding::Probation, None), Ok(()));
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(first, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.register_to_course(second, None),
+                   Err(Error::CreditCapExceeded { cap: PROBATION_CREDIT_CAP }));
+    }
+}
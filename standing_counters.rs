@@ -0,0 +1,145 @@
+//! Standing counter offers, a student's instruction to counter every
+//! proposal of a wanted course with one of the student's own seats
+
+use ink_lang::codegen::StaticEnv;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    /// sets or replaces the caller's standing counter offer for the wanted course
+    pub(crate) fn set_standing_counter_inner(&mut self,
+                                             wanted_course_id: CourseId,
+                                             offered_course_id: CourseId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_SWAPS)?;
+        let caller = Self::env().caller();
+        self.ensure_membership_active(caller)?;
+        if !self.courses.contains(wanted_course_id) {
+            return Err(Error::NonexistentCourse);
+        }
+        if self.enrollments.contains((wanted_course_id, caller)) {
+            return Err(Error::AlreadyRegistered);
+        }
+        if !self.registrations.contains(TokenId { owner: caller, course_id: offered_course_id }) {
+            return Err(Error::RegistrationNotOwned);
+        }
+        let mut index = self.standing_counter_index.get(wanted_course_id).unwrap_or_default();
+        if !index.contains(&caller) {
+            let mut wanted = self.standing_counter_courses.get(caller).unwrap_or_default();
+            if index.len() >= MAX_STANDING_COUNTERS as usize
+                || wanted.len() >= MAX_REGISTRATIONS_PER_ACCOUNT as usize {
+                return Err(Error::TooManyStandingCounters);
+            }
+            index.push(caller);
+            self.standing_counter_index.insert(wanted_course_id, &index);
+            wanted.push(wanted_course_id);
+            self.standing_counter_courses.insert(caller, &wanted);
+        }
+        self.standing_counters.insert((wanted_course_id, caller), &offered_course_id);
+        Ok(())
+    }
+
+    pub(crate) fn cancel_standing_counter_inner(&mut self, wanted_course_id: CourseId) -> Result<(), Error> {
+        self.ensure_version()?;
+        let caller = Self::env().caller();
+        if !self.standing_counters.contains((wanted_course_id, caller)) {
+            return Err(Error::StandingCounterNotFound);
+        }
+        let mut index = self.standing_counter_index.get(wanted_course_id).unwrap_or_default();
+        index.retain(|student| *student != caller);
+        self.standing_counter_index.insert(wanted_course_id, &index);
+        self.remove_standing_counter(wanted_course_id, caller);
+        Ok(())
+    }
+
+    /// removes the student's standing counter offer for the wanted course
+    /// and the course from the student's wanted courses
+    pub(crate) fn remove_standing_counter(&mut self, wanted_course_id: CourseId, student: AccountId) {
+        self.standing_counters.remove((wanted_course_id, student));
+        let mut wanted = self.standing_counter_courses.get(student).unwrap_or_default();
+        wanted.retain(|course_id| *course_id != wanted_course_id);
+        if wanted.is_empty() {
+            self.standing_counter_courses.remove(student);
+        } else {
+            self.standing_counter_courses.insert(student, &wanted);
+        }
+    }
+
+    /// counters the new proposal with the standing offers for its course,
+    /// in the order they were set, the offers that fail the usual checks are skipped
+    pub(crate) fn run_standing_counters(&mut self, proposal_id: ProposalId, wanted_course_id: CourseId) {
+        for student in self.standing_counter_index.get(wanted_course_id).unwrap_or_default() {
+            if let Some(offered_course_id) = self.standing_counters.get((wanted_course_id, student)) {
+                let _ = self.counter_swap_proposal_as(student, proposal_id, offered_course_id);
+            }
+        }
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Standing counter offers test
+    #[ink::test]
+    fn standing_counters() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student1 = AccountId::from([0x1; 32]);
+        let student2 = AccountId::from([0x2; 32]);
+        let student3 = AccountId::from([0x3; 32]);
+        let course_ids: [CourseId; 3] = [0u8, 1, 2].map(|i| CourseId(hash_keccak_256(&[i])));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        for student in [student1, student2, student3] {
+            assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        }
+        for course_id in course_ids {
+            assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        }
+        for (student, course_id) in [(student1, course_ids[0]), (student2, course_ids[1]), (student3, course_ids[2])] {
+            set_next_caller(student);
+            assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        }
+
+        set_next_caller(student2);
+        assert_eq!(course_reg.set_standing_counter(course_ids[0], course_ids[0], None), Err(Error::RegistrationNotOwned));
+        assert_eq!(course_reg.set_standing_counter(course_ids[0], course_ids[1], None), Ok(()));
+        assert_eq!(course_reg.get_standing_counter(student2, course_ids[0]), Some(course_ids[1]));
+        set_next_caller(student3);
+        assert_eq!(course_reg.set_standing_counter(course_ids[0], course_ids[2], None), Ok(()));
+        assert_eq!(course_reg.cancel_standing_counter(course_ids[0], None), Ok(()));
+        assert_eq!(course_reg.cancel_standing_counter(course_ids[0], None), Err(Error::StandingCounterNotFound));
+
+        // proposing the wanted course counters it right away
+        set_next_caller(student1);
+        let proposal_id = course_reg.propose_swap(course_ids[0], None).unwrap();
+        let proposal = course_reg.get_swap_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.counter_offers.len(), 1);
+        assert_eq!(proposal.counter_offers[0].owner, student2);
+        assert_eq!(proposal.counter_offers[0].course_id, course_ids[1]);
+        assert_eq!(course_reg.accept_counter_offer(proposal_id, course_ids[1], student2, None).map(|_| ()), Ok(()));
+        assert!(course_reg.is_registered(course_ids[0], student2));
+
+        // the offered seat is gone, so the standing offer no longer counters
+        set_next_caller(student2);
+        assert_eq!(course_reg.set_standing_counter(course_ids[2], course_ids[0], None), Ok(()));
+        set_next_caller(student3);
+        assert_eq!(course_reg.register_to_course(course_ids[1], None).map(|_| ()), Ok(()));
+        set_next_caller(student2);
+        let proposal_id = course_reg.propose_swap(course_ids[0], None).unwrap();
+        set_next_caller(student3);
+        let proposal_id2 = course_reg.propose_swap(course_ids[2], None).unwrap();
+        assert!(course_reg.get_swap_proposal(proposal_id2).unwrap().counter_offers.is_empty());
+        assert!(course_reg.get_swap_proposal(proposal_id).unwrap().counter_offers.is_empty());
+    }
+}
@@ -0,0 +1,134 @@
+//! One-on-one supervision slots that students apply for
+
+use ink_lang::codegen::StaticEnv;
+use ink_prelude::vec::Vec;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    /// creates a course with a single seat for the topic
+    pub(crate) fn publish_supervision_slot_inner(&mut self,
+                                                 course_id: CourseId,
+                                                 course_start: Timestamp,
+                                                 term_id: TermId,
+                                                 topic_hash: [u8; 32]) -> Result<(), Error> {
+        self.ensure_feature(FEATURE_SUPERVISION)?;
+        self.create_course_inner(course_id, 1, course_start, term_id, None)?;
+        self.supervision_topics.insert(course_id, &topic_hash);
+        Ok(())
+    }
+
+    pub(crate) fn apply_for_supervision_inner(&mut self,
+                                              course_id: CourseId,
+                                              proposal_hash: [u8; 32]) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_SUPERVISION)?;
+        let caller = Self::env().caller();
+        self.ensure_membership_active(caller)?;
+        self.require_role(Role::Member)?;
+        if !self.supervision_topics.contains(course_id) {
+            return Err(Error::NotSupervisionSlot);
+        }
+        self.mark_registration_phase(course_id);
+        self.check_registration(caller, course_id, &[])?;
+        let mut applications = self.supervision_applications.get(course_id).unwrap_or_default();
+        if applications.iter().any(|application| application.student == caller) {
+            return Err(Error::AlreadyApplied);
+        }
+        if applications.len() >= MAX_SUPERVISION_APPLICATIONS as usize {
+            return Err(Error::TooManyApplications);
+        }
+        applications.push(SupervisionApplication { student: caller, proposal_hash });
+        self.supervision_applications.insert(course_id, &applications);
+        Ok(())
+    }
+
+    /// fills the slot with the applicant, the slot's seat is registered like any other
+    pub(crate) fn accept_supervision_applicant_inner(&mut self,
+                                                     course_id: CourseId,
+                                                     student: AccountId) -> Result<(), Error> {
+        self.accept_supervision_applicant_by(course_id, student, Self::env().caller())
+    }
+
+    /// fills the slot with the applicant on behalf of `actor`, the slot's teacher
+    pub(crate) fn accept_supervision_applicant_by(&mut self,
+                                                  course_id: CourseId,
+                                                  student: AccountId,
+                                                  actor: AccountId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_SUPERVISION)?;
+        self.require_course_teacher(course_id, actor)?;
+        if !self.supervision_topics.contains(course_id) {
+            return Err(Error::NotSupervisionSlot);
+        }
+        let applications = self.supervision_applications.get(course_id).unwrap_or_default();
+        if !applications.iter().any(|application| application.student == student) {
+            return Err(Error::ApplicationNotFound);
+        }
+        self.check_registration(student, course_id, &[])?;
+        self.move_registration(course_id, None, Some(student))?;
+        self.record_activity(student, course_id, ActivityKind::Added);
+        Ok(())
+    }
+
+    pub(crate) fn get_supervision_applications_inner(&self,
+                                                     course_id: CourseId) -> Result<Vec<SupervisionApplication>, Error> {
+        if !self.supervision_topics.contains(course_id) {
+            return Err(Error::NotSupervisionSlot);
+        }
+        Ok(self.supervision_applications.get(course_id).unwrap_or_default())
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+    use ink_prelude::vec;
+
+    /// Supervision application and acceptance test
+    #[ink::test]
+    fn supervision_slot() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let teacher = AccountId::from([0x1; 32]);
+        let student = AccountId::from([0x2; 32]);
+        let other = AccountId::from([0x3; 32]);
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        let slot = CourseId(hash_keccak_256("thesis".as_bytes()));
+        let topic_hash = hash_keccak_256("topic".as_bytes());
+        let proposal_hash = hash_keccak_256("proposal".as_bytes());
+        assert_eq!(course_reg.admit_as_teacher(teacher, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(other, None), Ok(()));
+        set_next_caller(teacher);
+        assert_eq!(course_reg.publish_supervision_slot(slot, start_time, term_id, topic_hash, None), Ok(()));
+        assert_eq!(course_reg.get_supervision_topic(slot), Some(topic_hash));
+
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(slot, None), Err(Error::ApplicationRequired));
+        assert_eq!(course_reg.apply_for_supervision(slot, proposal_hash, None), Ok(()));
+        assert_eq!(course_reg.apply_for_supervision(slot, proposal_hash, None), Err(Error::AlreadyApplied));
+        set_next_caller(other);
+        assert_eq!(course_reg.apply_for_supervision(slot, proposal_hash, None), Ok(()));
+        assert_eq!(course_reg.accept_supervision_applicant(slot, other, None), Err(Error::InsufficientPermissions));
+        assert_eq!(course_reg.get_supervision_applications(slot).unwrap().len(), 2);
+
+        // a single applicant gets the seat
+        set_next_caller(teacher);
+        assert_eq!(course_reg.accept_supervision_applicant(slot, teacher, None), Err(Error::ApplicationNotFound));
+        assert_eq!(course_reg.accept_supervision_applicant(slot, student, None), Ok(()));
+        assert_eq!(course_reg.accept_supervision_applicant(slot, other, None),
+                   Err(Error::CourseCapacityFull { capacity: 1 }));
+        assert_eq!(course_reg.get_course_registrations(slot, None, 10).map(|page| page.items), Ok(vec![student]));
+    }
+}
@@ -0,0 +1,241 @@
+//! Randomized state machine tests of the registration and swap engine
+//!
+//! every case replays a seeded sequence of register, propose, counter,
+//! accept, batch accept, cancel, withdraw, admin swap and poke calls from
+//! many accounts and checks after each step that swaps conserve the seats
+//! of every course and of every student, and that exactly the open
+//! proposals hold the escrowed tokens. The swaps close for the last
+//! quarter of the steps, where poke expires the proposals
+
+use crate::clock::TestClock;
+use crate::course_reg::CourseReg;
+use crate::test_utils::*;
+use crate::types::*;
+
+use ink_prelude::vec::Vec;
+
+const CASES: u64 = 16;
+const STEPS: usize = 100;
+const STUDENTS: u32 = 8;
+const COURSES: u32 = 6;
+const CAPACITY: u32 = 3;
+
+/// xorshift64*, seeded per case so a failing case can be replayed
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn below(&mut self, bound: u32) -> u32 {
+        (self.next() % u64::from(bound)) as u32
+    }
+}
+
+fn account(i: u32) -> AccountId {
+    let mut bytes = [0xaa; 32];
+    bytes[..4].copy_from_slice(&i.to_le_bytes());
+    AccountId::from(bytes)
+}
+
+fn course(i: u32) -> CourseId {
+    let mut bytes = [0xbb; 32];
+    bytes[..4].copy_from_slice(&i.to_le_bytes());
+    CourseId(bytes)
+}
+
+/// the seats and open proposals the model expects, the contract has to agree after every step
+struct Model {
+    course_seats: Vec<u32>,
+    student_seats: Vec<u32>,
+    open: Vec<ProposalId>,
+}
+
+impl Model {
+    fn close(&mut self, proposal_id: ProposalId) {
+        self.open.retain(|open| *open != proposal_id);
+    }
+}
+
+fn check(course_reg: &CourseReg, model: &Model, seed: u64, step: usize) {
+    let courses: Vec<CourseId> = (0..COURSES).map(course).collect();
+    let students: Vec<AccountId> = (0..STUDENTS).map(account).collect();
+    for (i, course_id) in courses.iter().enumerate() {
+        let enrolled = course_reg.get_course_info(*course_id).unwrap().enrolled_count;
+        assert_eq!(enrolled, model.course_seats[i], "course seats, seed {} step {}", seed, step);
+    }
+    for (i, student) in students.iter().enumerate() {
+        let seats = courses.iter().filter(|course_id| course_reg.is_registered(**course_id, *student)).count();
+        assert_eq!(seats as u32, model.student_seats[i], "student seats, seed {} step {}", seed, step);
+    }
+    let mut locked = 0;
+    for proposal_id in &model.open {
+        let proposal = course_reg.get_swap_proposal(*proposal_id);
+        assert!(proposal.is_ok(), "open proposal {:?}, seed {} step {}", proposal_id, seed, step);
+        locked += 1 + proposal.unwrap().counter_offers.len();
+    }
+    assert_eq!(course_reg.open_proposal_count.get_or_default() as usize, model.open.len(),
+               "open proposals, seed {} step {}", seed, step);
+    let escrowed: usize = students.iter().map(|student| course_reg.get_escrowed(*student).len()).sum();
+    assert_eq!(escrowed, locked, "escrowed tokens, seed {} step {}", seed, step);
+    assert_eq!(course_reg.check_invariants(courses, students), Ok(Vec::new()),
+               "invariants, seed {} step {}", seed, step);
+}
+
+/// picks one of the courses the student holds the token of
+fn held_course(course_reg: &CourseReg, student: AccountId, rng: &mut Rng) -> Option<CourseId> {
+    let held: Vec<CourseId> = (0..COURSES).map(course)
+        .filter(|course_id| course_reg.registrations.contains(TokenId { owner: student, course_id: *course_id }))
+        .collect();
+    if held.is_empty() {
+        return None;
+    }
+    Some(held[rng.below(held.len() as u32) as usize])
+}
+
+/// picks an open proposal with counter offers and one of its counter offers
+fn pick_counter_offer(course_reg: &CourseReg, model: &Model, rng: &mut Rng) -> Option<(SwapView, RegistrationView)> {
+    if model.open.is_empty() {
+        return None;
+    }
+    let proposal_id = model.open[rng.below(model.open.len() as u32) as usize];
+    let proposal = course_reg.get_swap_proposal(proposal_id).ok()?;
+    if proposal.counter_offers.is_empty() {
+        return None;
+    }
+    let counter = proposal.counter_offers[rng.below(proposal.counter_offers.len() as u32) as usize];
+    Some((proposal, counter))
+}
+
+fn run_case(seed: u64) {
+    let owner = AccountId::from([0x0; 32]);
+    set_next_caller(owner);
+    let mut course_reg = CourseReg::new(owner);
+    let start_time = get_start_time();
+    let term_id = create_term(&mut course_reg, start_time);
+    for i in 0..COURSES {
+        course_reg.create_course(course(i), CAPACITY, start_time, term_id, None, None).unwrap();
+    }
+    for i in 0..STUDENTS {
+        course_reg.admit_as_student(account(i), None).unwrap();
+    }
+
+    let mut rng = Rng(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1);
+    let mut model = Model {
+        course_seats: ink_prelude::vec![0; COURSES as usize],
+        student_seats: ink_prelude::vec![0; STUDENTS as usize],
+        open: Vec::new(),
+    };
+    for step in 0..STEPS {
+        if step == STEPS * 3 / 4 {
+            TestClock::set(start_time);
+        }
+        let student = rng.below(STUDENTS);
+        let course_index = rng.below(COURSES);
+        set_next_caller(account(student));
+        match rng.below(12) {
+            0 | 9 if course_reg.register_to_course(course(course_index), None).is_ok() => {
+                model.course_seats[course_index as usize] += 1;
+                model.student_seats[student as usize] += 1;
+            }
+            1 => {
+                let offered = held_course(&course_reg, account(student), &mut rng).unwrap_or(course(course_index));
+                if let Ok(proposal_id) = course_reg.propose_swap(offered, None) {
+                    model.open.push(proposal_id);
+                }
+            }
+            2 | 10 | 11 if !model.open.is_empty() => {
+                let proposal_id = model.open[rng.below(model.open.len() as u32) as usize];
+                let offered = held_course(&course_reg, account(student), &mut rng).unwrap_or(course(course_index));
+                let _ = course_reg.counter_swap_proposal(proposal_id, offered, None);
+            }
+            3 => {
+                if let Some((proposal, counter)) = pick_counter_offer(&course_reg, &model, &mut rng) {
+                    set_next_caller(proposal.proposer);
+                    if course_reg.accept_counter_offer(proposal.proposal_id, counter.course_id, counter.owner, None).is_ok() {
+                        model.close(proposal.proposal_id);
+                    }
+                }
+            }
+            4 if !model.open.is_empty() => {
+                let proposal_id = model.open[rng.below(model.open.len() as u32) as usize];
+                let proposer = course_reg.get_swap_proposal(proposal_id).unwrap().proposer;
+                set_next_caller(proposer);
+                if course_reg.cancel_swap_proposal(proposal_id, None).is_ok() {
+                    model.close(proposal_id);
+                }
+            }
+            5 => {
+                if let Some((proposal, counter)) = pick_counter_offer(&course_reg, &model, &mut rng) {
+                    set_next_caller(counter.owner);
+                    let _ = course_reg.withdraw_counter_offer(proposal.proposal_id, counter.course_id, None);
+                }
+            }
+            6 => {
+                // the seats change hands, neither side gains or loses one
+                let other = account(rng.below(STUDENTS));
+                let course_a = held_course(&course_reg, account(student), &mut rng).unwrap_or(course(course_index));
+                let course_b = held_course(&course_reg, other, &mut rng).unwrap_or(course(course_index));
+                set_next_caller(AccountId::from([0x0; 32]));
+                let _ = course_reg.admin_swap(account(student), course_a, other, course_b, None);
+            }
+            7 => {
+                // the first pick names the proposer, the others join the batch if they're also theirs
+                let first = match pick_counter_offer(&course_reg, &model, &mut rng) {
+                    Some(first) => first,
+                    None => continue,
+                };
+                let proposer = first.0.proposer;
+                let others: Vec<(SwapView, RegistrationView)> = (0..2)
+                    .filter_map(|_| pick_counter_offer(&course_reg, &model, &mut rng))
+                    .filter(|(proposal, _)| proposal.proposer == proposer)
+                    .collect();
+                let batch: Vec<CounterOfferRef> = core::iter::once(first).chain(others)
+                    .map(|(proposal, counter)| CounterOfferRef {
+                        proposal_id: proposal.proposal_id,
+                        course_id: counter.course_id,
+                        owner: counter.owner,
+                    })
+                    .collect();
+                set_next_caller(proposer);
+                if let Ok(results) = course_reg.accept_counter_offers(batch.clone(), None) {
+                    for (offer, result) in batch.iter().zip(results) {
+                        if result.is_ok() {
+                            model.close(offer.proposal_id);
+                        }
+                    }
+                }
+            }
+            8 => {
+                let offered: Vec<ProposalId> = model.open.iter().copied()
+                    .filter(|proposal_id| course_reg.get_swap_proposal(*proposal_id).unwrap().course_id == course(course_index))
+                    .collect();
+                if let Ok(expired) = course_reg.poke(course(course_index), None) {
+                    if expired > 0 {
+                        assert_eq!(expired as usize, offered.len(), "expired proposals, seed {} step {}", seed, step);
+                        for proposal_id in offered {
+                            model.close(proposal_id);
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+        check(&course_reg, &model, seed, step);
+    }
+}
+
+/// Seat conservation over random call sequences test
+#[test]
+fn swap_state_machine() {
+    for seed in 0..CASES {
+        ink_env::test::run_test::<ink_env::DefaultEnvironment, _>(|_| {
+            run_case(seed);
+            Ok(())
+        }).unwrap();
+    }
+}
@@ -0,0 +1,97 @@
+//! The swaps settled between the same two accounts in a term, capped so
+//! seats can't be passed around between colluding accounts
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    /// the storage key of the unordered account pair in the term
+    fn swap_pair_key(term_id: TermId, a: AccountId, b: AccountId) -> (TermId, AccountId, AccountId) {
+        if a <= b { (term_id, a, b) } else { (term_id, b, a) }
+    }
+
+    pub(crate) fn get_swap_pair_count_inner(&self, a: AccountId, b: AccountId, term_id: TermId) -> u32 {
+        self.swap_pair_counts.get(Self::swap_pair_key(term_id, a, b)).unwrap_or(0)
+    }
+
+    /// fails if the two accounts already settled as many swaps in the
+    /// course's term as the school allows
+    pub(crate) fn ensure_swap_pair_cap(&self, a: AccountId, b: AccountId, course_id: CourseId) -> Result<(), Error> {
+        let (cap, term_id) = match (self.school_config.get_or_default().swap_pair_cap, self.course_terms.get(course_id)) {
+            (Some(cap), Some(term_id)) => (cap, term_id),
+            _ => return Ok(()),
+        };
+        if self.get_swap_pair_count_inner(a, b, term_id) >= cap {
+            return Err(Error::SwapPairLimitExceeded { cap });
+        }
+        Ok(())
+    }
+
+    /// counts a settled swap between the two accounts in the course's term
+    pub(crate) fn record_swap_pair(&mut self, a: AccountId, b: AccountId, course_id: CourseId) {
+        if let Some(term_id) = self.course_terms.get(course_id) {
+            let count = self.get_swap_pair_count_inner(a, b, term_id).saturating_add(1);
+            self.swap_pair_counts.insert(Self::swap_pair_key(term_id, a, b), &count);
+        }
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Swap cap between the same two accounts test
+    #[ink::test]
+    fn swap_pair_cap() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student1 = AccountId::from([0x1; 32]);
+        let student2 = AccountId::from([0x2; 32]);
+        let course_id1 = CourseId(hash_keccak_256("test_course1".as_bytes()));
+        let course_id2 = CourseId(hash_keccak_256("test_course2".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_student(student1, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student2, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id1, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id2, 10, start_time, term_id, None, None), Ok(()));
+        let config = SchoolConfig { swap_pair_cap: Some(1), ..SchoolConfig::default() };
+        assert_eq!(course_reg.set_school_config(config, None), Ok(()));
+        set_next_caller(student1);
+        assert_eq!(course_reg.register_to_course(course_id1, None).map(|_| ()), Ok(()));
+        set_next_caller(student2);
+        assert_eq!(course_reg.register_to_course(course_id2, None).map(|_| ()), Ok(()));
+
+        // the first swap between the pair settles
+        set_next_caller(student1);
+        let proposal_id = course_reg.propose_swap(course_id1, None).unwrap();
+        set_next_caller(student2);
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id2, None), Ok(()));
+        set_next_caller(student1);
+        assert_eq!(course_reg.accept_counter_offer(proposal_id, course_id2, student2, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.get_swap_pair_count(student2, student1, term_id), 1);
+
+        // swapping the seats back is one swap too many, whoever proposes it
+        set_next_caller(student2);
+        let proposal_id = course_reg.propose_swap(course_id1, None).unwrap();
+        set_next_caller(student1);
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id2, None), Ok(()));
+        set_next_caller(student2);
+        assert_eq!(course_reg.accept_counter_offer(proposal_id, course_id2, student1, None),
+                   Err(Error::SwapPairLimitExceeded { cap: 1 }));
+
+        set_next_caller(owner);
+        assert_eq!(course_reg.set_school_config(SchoolConfig::default(), None), Ok(()));
+        set_next_caller(student2);
+        assert_eq!(course_reg.accept_counter_offer(proposal_id, course_id2, student1, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.get_swap_pair_count(student1, student2, term_id), 2);
+    }
+}
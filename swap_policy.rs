@@ -0,0 +1,130 @@
+//! Per-course restrictions on the seats a course can be swapped against
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn set_swap_policy_inner(&mut self, course_id: CourseId, policy: SwapPolicy) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_SWAP_POLICIES)?;
+        self.require_role(Role::Owner)?;
+        if !self.courses.contains(course_id) {
+            return Err(Error::NonexistentCourse);
+        }
+        if policy == SwapPolicy::Open {
+            self.swap_policies.remove(course_id);
+        } else {
+            self.swap_policies.insert(course_id, &policy);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn set_course_department_inner(&mut self,
+                                              course_id: CourseId,
+                                              department: Option<DepartmentId>) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_SWAP_POLICIES)?;
+        self.require_role(Role::Owner)?;
+        if !self.courses.contains(course_id) {
+            return Err(Error::NonexistentCourse);
+        }
+        match department {
+            Some(department) => self.course_departments.insert(course_id, &department),
+            None => self.course_departments.remove(course_id),
+        }
+        Ok(())
+    }
+
+    /// fails if the policy of either course forbids exchanging their seats,
+    /// a department requirement fails if either course has no department
+    pub(crate) fn ensure_swap_policy(&self, course_id: CourseId, other_course_id: CourseId) -> Result<(), Error> {
+        if !self.feature_enabled(FEATURE_SWAP_POLICIES) {
+            return Ok(());
+        }
+        let (course, other_course) = match (self.courses.get(course_id), self.courses.get(other_course_id)) {
+            (Some(course), Some(other_course)) => (course, other_course),
+            _ => return Err(Error::NonexistentCourse),
+        };
+        for policy in [self.swap_policies.get(course_id), self.swap_policies.get(other_course_id)].into_iter().flatten() {
+            let allowed = match policy {
+                SwapPolicy::Open => true,
+                SwapPolicy::SameTeacher => course.teacher == other_course.teacher,
+                SwapPolicy::SameDepartment => {
+                    let department = self.course_departments.get(course_id);
+                    department.is_some() && department == self.course_departments.get(other_course_id)
+                }
+            };
+            if !allowed {
+                return Err(Error::SwapPolicyViolation { policy });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Swap policy enforcement test
+    #[ink::test]
+    fn swap_policy() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let teacher = AccountId::from([0x1; 32]);
+        let student1 = AccountId::from([0x2; 32]);
+        let student2 = AccountId::from([0x3; 32]);
+        let course_id1 = CourseId(hash_keccak_256("test_course1".as_bytes()));
+        let course_id2 = CourseId(hash_keccak_256("test_course2".as_bytes()));
+        let course_id3 = CourseId(hash_keccak_256("test_course3".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_teacher(teacher, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student1, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student2, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id1, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id2, 10, start_time, term_id, None, None), Ok(()));
+        set_next_caller(teacher);
+        assert_eq!(course_reg.create_course(course_id3, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.set_swap_policy(course_id1, SwapPolicy::SameTeacher, None),
+                   Err(Error::InsufficientPermissions));
+        set_next_caller(owner);
+        assert_eq!(course_reg.set_swap_policy(course_id1, SwapPolicy::SameDepartment, None), Ok(()));
+        assert_eq!(course_reg.get_swap_policy(course_id1), SwapPolicy::SameDepartment);
+        assert_eq!(course_reg.get_swap_policy(course_id2), SwapPolicy::Open);
+        assert_eq!(course_reg.set_course_department(course_id1, Some(1), None), Ok(()));
+        assert_eq!(course_reg.set_course_department(course_id3, Some(2), None), Ok(()));
+
+        set_next_caller(student1);
+        assert_eq!(course_reg.register_to_course(course_id1, None).map(|_| ()), Ok(()));
+        let proposal_id = course_reg.propose_swap(course_id1, None).unwrap();
+        set_next_caller(student2);
+        assert_eq!(course_reg.register_to_courses(ink_prelude::vec![course_id2, course_id3], None).map(|_| ()), Ok(()));
+        let violation = Err(Error::SwapPolicyViolation { policy: SwapPolicy::SameDepartment });
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id2, None), violation);
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id3, None), violation);
+
+        // the policy is checked again at settlement
+        set_next_caller(owner);
+        assert_eq!(course_reg.set_course_department(course_id2, Some(1), None), Ok(()));
+        set_next_caller(student2);
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id2, None), Ok(()));
+        set_next_caller(owner);
+        assert_eq!(course_reg.set_course_department(course_id2, None, None), Ok(()));
+        assert_eq!(course_reg.get_course_department(course_id2), None);
+        set_next_caller(student1);
+        assert_eq!(course_reg.accept_counter_offer(proposal_id, course_id2, student2, None).map(|_| ()), violation);
+        set_next_caller(owner);
+        assert_eq!(course_reg.set_swap_policy(course_id1, SwapPolicy::Open, None), Ok(()));
+        set_next_caller(student1);
+        assert_eq!(course_reg.accept_counter_offer(proposal_id, course_id2, student2, None).map(|_| ()), Ok(()));
+    }
+}
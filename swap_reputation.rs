@@ -0,0 +1,76 @@
+//! The swap record of the accounts, how their proposals ended
+
+use crate::course_reg::CourseReg;
+use crate::types::*;
+
+impl CourseReg {
+    /// counts the outcome in the swap record of the proposer
+    pub(crate) fn record_swap_outcome(&mut self, proposer: AccountId, outcome: SwapOutcome) {
+        let mut reputation = self.swap_reputations.get(proposer).unwrap_or_default();
+        let count = match outcome {
+            SwapOutcome::Honored => &mut reputation.honored,
+            SwapOutcome::WithdrawnAfterCounters => &mut reputation.withdrawn_after_counters,
+            SwapOutcome::Expired => &mut reputation.expired,
+        };
+        *count = count.saturating_add(1);
+        self.swap_reputations.insert(proposer, &reputation);
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Swap record of a proposer test
+    #[ink::test]
+    fn swap_reputation() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student1 = AccountId::from([0x1; 32]);
+        let student2 = AccountId::from([0x2; 32]);
+        let course_ids: [CourseId; 4] = [0u8, 1, 2, 3].map(|i| CourseId(hash_keccak_256(&[i])));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_student(student1, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student2, None), Ok(()));
+        for course_id in course_ids {
+            assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        }
+        set_next_caller(student1);
+        assert_eq!(course_reg.register_to_courses(ink_prelude::vec![course_ids[0], course_ids[1]], None).map(|_| ()), Ok(()));
+        set_next_caller(student2);
+        assert_eq!(course_reg.register_to_courses(ink_prelude::vec![course_ids[2], course_ids[3]], None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.get_swap_reputation(student1), SwapReputation::default());
+
+        // honored, then withdrawn after a counter offer arrived
+        set_next_caller(student1);
+        let proposal_id = course_reg.propose_swap(course_ids[0], None).unwrap();
+        set_next_caller(student2);
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_ids[2], None), Ok(()));
+        set_next_caller(student1);
+        assert_eq!(course_reg.accept_counter_offer(proposal_id, course_ids[2], student2, None).map(|_| ()), Ok(()));
+        let proposal_id = course_reg.propose_swap(course_ids[1], None).unwrap();
+        assert_eq!(course_reg.cancel_swap_proposal(proposal_id, None), Ok(()));
+        let proposal_id = course_reg.propose_swap(course_ids[1], None).unwrap();
+        set_next_caller(student2);
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_ids[3], None), Ok(()));
+        set_next_caller(student1);
+        assert_eq!(course_reg.cancel_swap_proposal(proposal_id, None), Ok(()));
+
+        // expired once swaps closed
+        let proposal_id = course_reg.propose_swap(course_ids[1], None).unwrap();
+        TestClock::set(start_time);
+        assert_eq!(course_reg.poke(course_ids[1], None), Ok(1));
+        assert!(course_reg.get_swap_proposal(proposal_id).is_err());
+        assert_eq!(course_reg.get_swap_reputation(student1),
+                   SwapReputation { honored: 1, withdrawn_after_counters: 1, expired: 1 });
+        assert_eq!(course_reg.get_swap_reputation(student2), SwapReputation::default());
+    }
+}
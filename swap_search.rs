@@ -0,0 +1,154 @@
+//! Search of the open swap proposals, for students hunting for one exchange
+
+use ink_prelude::vec::Vec;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    /// returns a page of the open proposals matching the filters, in proposal order
+    ///
+    /// with an `offering` course only its proposals are searched, otherwise
+    /// the proposals are walked by id, at most `MAX_SWAP_SCAN` ids a page, so
+    /// a page can come back short while a `next_cursor` is still returned
+    pub(crate) fn find_swaps_inner(&self,
+                                   offering: Option<CourseId>,
+                                   wanting: Option<CourseId>,
+                                   newer_than: Option<Timestamp>,
+                                   cursor: Option<Cursor>,
+                                   count: u32) -> Result<Page<SwapView>, Error> {
+        let start = cursor.map_or(0, |cursor| Self::cursor_proposal_id(cursor).0.saturating_add(1));
+        let (candidates, total, exhausted) = match offering {
+            Some(course_id) => {
+                if !self.courses.contains(course_id) {
+                    return Err(Error::NonexistentCourse);
+                }
+                let mut ids = self.course_proposals.get(course_id).unwrap_or_default();
+                let total = ids.len() as u32;
+                ids.retain(|proposal_id| proposal_id.0 >= start);
+                ids.sort_unstable();
+                (ids, total, true)
+            }
+            None => {
+                let next_proposal_id = self.next_proposal_id.get_or_default().0;
+                let end = next_proposal_id.min(start.saturating_add(MAX_SWAP_SCAN));
+                ((start..end).map(ProposalId).collect::<Vec<ProposalId>>(), self.open_proposal_count.get_or_default(), end == next_proposal_id)
+            }
+        };
+        let mut items = Vec::new();
+        let mut last = None;
+        let mut more = !exhausted;
+        for proposal_id in candidates {
+            if items.len() >= count as usize {
+                more = true;
+                break;
+            }
+            last = Some(proposal_id);
+            if let Some(proposal) = self.proposals.get(proposal_id) {
+                if self.swap_matches(&proposal, wanting, newer_than) {
+                    items.push(Self::swap_view(proposal));
+                }
+            }
+        }
+        let next_cursor = match last {
+            Some(last) if more => Some(Self::proposal_cursor(last)),
+            _ => None,
+        };
+        Ok(Page { items, next_cursor, total })
+    }
+
+    /// returns true if the proposer could take the `wanting` course in exchange
+    /// and the proposal was made after `newer_than`
+    fn swap_matches(&self,
+                    proposal: &CourseRegistrationSwapProposal,
+                    wanting: Option<CourseId>,
+                    newer_than: Option<Timestamp>) -> bool {
+        let offered_course_id = proposal.offer.course_id;
+        let wanted = wanting.is_none_or(|course_id| {
+            course_id != offered_course_id
+                && !self.enrollments.contains((course_id, proposal.offer.owner))
+                && self.ensure_swap_policy(offered_course_id, course_id).is_ok()
+        });
+        // proposals made before their times were recorded count as old
+        let fresh = newer_than.is_none_or(|newer_than| {
+            self.proposal_times.get(proposal.proposal_id).is_some_and(|proposed_at| proposed_at > newer_than)
+        });
+        wanted && fresh
+    }
+
+    fn proposal_cursor(proposal_id: ProposalId) -> Cursor {
+        let mut cursor = Cursor::default();
+        cursor[..8].copy_from_slice(&proposal_id.0.to_le_bytes());
+        cursor
+    }
+
+    fn cursor_proposal_id(cursor: Cursor) -> ProposalId {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&cursor[..8]);
+        ProposalId(u64::from_le_bytes(bytes))
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+    use ink_prelude::vec::Vec;
+
+    fn ids(page: &Page<SwapView>) -> Vec<ProposalId> {
+        page.items.iter().map(|swap| swap.proposal_id).collect()
+    }
+
+    /// Filtered swap search test
+    #[ink::test]
+    fn swap_search() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let students: [AccountId; 3] = [1u8, 2, 3].map(|i| AccountId::from([i; 32]));
+        let course_ids: [CourseId; 3] = [0u8, 1, 2].map(|i| CourseId(hash_keccak_256(&[i])));
+        let start_time = get_start_time();
+        let now = start_time - 7 * MILLIS_PER_DAY;
+        let term_id = create_term(&mut course_reg, start_time);
+        for student in students {
+            assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        }
+        for course_id in course_ids {
+            assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        }
+        set_next_caller(students[0]);
+        assert_eq!(course_reg.register_to_courses(ink_prelude::vec![course_ids[0], course_ids[1]], None).map(|_| ()), Ok(()));
+        let first = course_reg.propose_swap(course_ids[0], None).unwrap();
+        set_next_caller(students[1]);
+        assert_eq!(course_reg.register_to_course(course_ids[0], None).map(|_| ()), Ok(()));
+        TestClock::set(now + 1);
+        let second = course_reg.propose_swap(course_ids[0], None).unwrap();
+        set_next_caller(students[2]);
+        assert_eq!(course_reg.register_to_course(course_ids[2], None).map(|_| ()), Ok(()));
+        let third = course_reg.propose_swap(course_ids[2], None).unwrap();
+
+        let all = course_reg.find_swaps(None, None, None, None, 10).unwrap();
+        assert_eq!((ids(&all), all.total, all.next_cursor), (ink_prelude::vec![first, second, third], 3, None));
+        let offering = course_reg.find_swaps(Some(course_ids[0]), None, None, None, 10).unwrap();
+        assert_eq!(ids(&offering), ink_prelude::vec![first, second]);
+        // the first proposer already holds the wanted course
+        let wanting = course_reg.find_swaps(Some(course_ids[0]), Some(course_ids[1]), None, None, 10).unwrap();
+        assert_eq!(ids(&wanting), ink_prelude::vec![second]);
+        let fresh = course_reg.find_swaps(None, None, Some(now), None, 10).unwrap();
+        assert_eq!(ids(&fresh), ink_prelude::vec![second, third]);
+        assert_eq!(course_reg.find_swaps(Some(CourseId([0x1; 32])), None, None, None, 10), Err(Error::NonexistentCourse));
+
+        // paging resumes after the last proposal of the page
+        let page = course_reg.find_swaps(None, None, None, None, 2).unwrap();
+        assert_eq!(ids(&page), ink_prelude::vec![first, second]);
+        let page = course_reg.find_swaps(None, None, None, page.next_cursor, 2).unwrap();
+        assert_eq!((ids(&page), page.next_cursor), (ink_prelude::vec![third], None));
+    }
+}
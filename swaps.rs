@@ -0,0 +1,840 @@
+//! Swap proposals, counter offers and their settlement
+
+use ink_lang::codegen::{EmitEvent, StaticEnv};
+use ink_prelude::vec::Vec;
+
+use crate::course_reg::{AdminSwapped, CourseReg, SwapSettled};
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn propose_swap_inner(&mut self, course_id: CourseId) -> Result<ProposalId,Error> {
+        let caller = Self::env().caller();
+        self.check_propose_swap(caller, course_id)?;
+        let proposal_id = self.next_proposal_id.get_or_default();
+        let next_proposal_id = proposal_id.next().ok_or(Error::ArithmeticOverflow)?;
+        let course = self.burn(course_id, caller)
+            .ok_or(Error::NoSwappableRegistrations)?;
+
+        self.next_proposal_id.set(&next_proposal_id);
+        let proposal = CourseRegistrationSwapProposal {
+            proposal_id,
+            offer: course,
+            counter_offers: Vec::default(),
+        };
+        self.add_proposal(course_id, proposal);
+        self.proposal_times.insert(proposal_id, &Self::now());
+        self.run_standing_counters(proposal_id, course_id);
+        Ok(proposal_id)
+    }
+
+    /// fails with the error proposing the student's registration would fail with
+    pub(crate) fn check_propose_swap(&self, student: AccountId, course_id: CourseId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_SWAPS)?;
+        self.ensure_no_blackout()?;
+        self.ensure_membership_active(student)?;
+        self.ensure_swaps_open(course_id)?;
+        let open = self.course_proposals.get(course_id).unwrap_or_default().len();
+        if open >= MAX_PROPOSALS_PER_COURSE as usize {
+            return Err(Error::TooManyProposals);
+        }
+        if self.next_proposal_id.get_or_default().next().is_none() {
+            return Err(Error::ArithmeticOverflow);
+        }
+        if !self.registrations.contains(TokenId { owner: student, course_id }) {
+            return Err(Error::NoSwappableRegistrations);
+        }
+        self.ensure_not_frozen(student, course_id)
+    }
+
+    /// stores a Swap proposal token and places its id
+    /// in the list of proposals for the given course,
+    /// its tokens are listed in their owners' escrow index
+    pub(crate) fn add_proposal(&mut self, course_id: CourseId, proposal: CourseRegistrationSwapProposal) {
+        self.lock_in_escrow(proposal.proposal_id, proposal.offer.owner);
+        for counter_offer in &proposal.counter_offers {
+            self.lock_in_escrow(proposal.proposal_id, counter_offer.owner);
+        }
+        let mut index = self.course_proposals.get(course_id).unwrap_or_default();
+        index.push(proposal.proposal_id);
+        self.course_proposals.insert(course_id, &index);
+        self.proposals.insert(proposal.proposal_id, &proposal);
+        self.open_proposal_count.set(&self.open_proposal_count.get_or_default().saturating_add(1));
+    }
+
+    /// removes a Swap proposal token and its id from the
+    /// list of proposals for its course, its tokens leave the escrow
+    pub(crate) fn remove_proposal(&mut self, proposal: &CourseRegistrationSwapProposal) {
+        self.release_from_escrow(proposal.proposal_id, proposal.offer.owner);
+        for counter_offer in &proposal.counter_offers {
+            self.release_from_escrow(proposal.proposal_id, counter_offer.owner);
+        }
+        let course_id = proposal.offer.course_id;
+        let mut index = self.course_proposals.get(course_id).unwrap_or_default();
+        index.retain(|id| id != &proposal.proposal_id);
+        self.course_proposals.insert(course_id, &index);
+        self.proposals.remove(proposal.proposal_id);
+        self.proposal_times.remove(proposal.proposal_id);
+        self.open_proposal_count.set(&self.open_proposal_count.get_or_default().saturating_sub(1));
+    }
+
+    pub(crate) fn get_proposed_swaps_inner(&self,
+                                           course_id: CourseId,
+                                           cursor: Option<Cursor>,
+                                           count: u32) -> Result<Page<SwapView>, Error> {
+        let proposal_ids = self.course_proposals.get(course_id).unwrap_or_default();
+        if proposal_ids.is_empty() {
+            return Err(Error::NoProposedSwap);
+        }
+        let page = Self::page(proposal_ids, cursor, count, |proposal_id| Self::id_key(proposal_id.0))?;
+        let items = page.items.into_iter()
+            .filter_map(|proposal_id| self.proposals.get(proposal_id))
+            .map(Self::swap_view)
+            .collect();
+        Ok(Page { items, next_cursor: page.next_cursor, total: page.total })
+    }
+
+    pub(crate) fn get_swap_proposal_inner(&self, proposal_id: ProposalId) -> Result<SwapView, Error> {
+        self.proposals.get(proposal_id).map(Self::swap_view).ok_or(Error::SwapProposalNotFound)
+    }
+
+    pub(crate) fn counter_swap_proposal_inner(&mut self,
+                                              proposal_id: ProposalId,
+                                              counter_course_id: CourseId) -> Result<(), Error> {
+        self.counter_swap_proposal_as(Self::env().caller(), proposal_id, counter_course_id)
+    }
+
+    /// counters the proposal with the student's registration
+    pub(crate) fn counter_swap_proposal_as(&mut self,
+                                           student: AccountId,
+                                           proposal_id: ProposalId,
+                                           counter_course_id: CourseId) -> Result<(), Error> {
+        let mut proposal = self.check_counter_swap_proposal(student, proposal_id, counter_course_id)?;
+
+        // the student needs the registration offered in exchange,
+        // which is removed from the student's tokens
+        let exchange_course = self.burn(counter_course_id, student)
+            .ok_or(Error::RegistrationNotOwned)?;
+        proposal.counter_offers.push(exchange_course);
+        self.lock_in_escrow(proposal_id, student);
+
+        // result is saved
+        self.proposals.insert(proposal_id, &proposal);
+
+        Ok(())
+    }
+
+    /// fails with the error countering the proposal with the student's
+    /// registration would fail with, returns the countered proposal
+    pub(crate) fn check_counter_swap_proposal(&self,
+                                              student: AccountId,
+                                              proposal_id: ProposalId,
+                                              counter_course_id: CourseId) -> Result<CourseRegistrationSwapProposal, Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_SWAPS)?;
+        self.ensure_no_blackout()?;
+        self.ensure_membership_active(student)?;
+        // find the proposal the counter offer belongs to
+        let proposal = self.proposals.get(proposal_id).ok_or(Error::SwapProposalNotFound)?;
+        if proposal.counter_offers.len() >= MAX_COUNTER_OFFERS as usize {
+            return Err(Error::TooManyCounterOffers);
+        }
+        self.ensure_swaps_open(proposal.offer.course_id)?;
+        self.ensure_swaps_open(counter_course_id)?;
+        self.ensure_swap_policy(proposal.offer.course_id, counter_course_id)?;
+        // a second seat in the offered course would swallow the countered one
+        if self.enrollments.contains((proposal.offer.course_id, student)) {
+            return Err(Error::AlreadyRegistered);
+        }
+        if !self.registrations.contains(TokenId { owner: student, course_id: counter_course_id }) {
+            return Err(Error::RegistrationNotOwned);
+        }
+        self.ensure_not_frozen(student, counter_course_id)?;
+        Ok(proposal)
+    }
+
+    /// returns the course the proposer gave up
+    pub(crate) fn accept_counter_offer_inner(&mut self,
+                                             proposal_id: ProposalId,
+                                             accepted_course_id: CourseId,
+                                             accepted_owner: AccountId) -> Result<CourseId, Error> {
+        let caller = Self::env().caller();
+        let (mut found_prop, found_counter) =
+            self.check_accept_counter_offer(caller, proposal_id, accepted_course_id, accepted_owner)?;
+        // remove the proposal from the active proposals
+        self.remove_proposal(&found_prop);
+        let found_counter = found_prop.counter_offers.remove(found_counter);
+        let offered_course_id = found_prop.offer.course_id;
+        let offered_seats_taken_before = self.seats_taken(offered_course_id);
+        let accepted_seats_taken_before = self.seats_taken(accepted_course_id);
+
+        // perform the seat swap
+        self.move_registration(accepted_course_id, Some(found_counter.owner), Some(caller))?;
+        self.move_registration(offered_course_id, Some(caller), Some(found_counter.owner))?;
+        Self::env().emit_event(SwapSettled {
+            proposal_id,
+            offered_course_id,
+            offered_seats_taken_before,
+            offered_seats_taken_after: self.seats_taken(offered_course_id),
+            accepted_course_id,
+            accepted_seats_taken_before,
+            accepted_seats_taken_after: self.seats_taken(accepted_course_id),
+        });
+        self.record_activity(caller, accepted_course_id, ActivityKind::Swapped);
+        self.record_activity(found_counter.owner, offered_course_id, ActivityKind::Swapped);
+        self.record_swap_outcome(caller, SwapOutcome::Honored);
+        self.record_swap_pair(caller, found_counter.owner, offered_course_id);
+
+        // the counter offers that weren't accepted go back to their owners
+        for counter_off in found_prop.counter_offers {
+            self.mint(counter_off.course_id, counter_off.owner);
+        }
+        Ok(offered_course_id)
+    }
+
+    /// returns the seats taken in the course
+    fn seats_taken(&self, course_id: CourseId) -> u32 {
+        self.courses.get(course_id).map_or(0, |course| course.enrolled_count)
+    }
+
+    /// fails with the error the student accepting the counter offer would
+    /// fail with, returns the proposal and the position of the counter offer
+    pub(crate) fn check_accept_counter_offer(&self,
+                                             student: AccountId,
+                                             proposal_id: ProposalId,
+                                             accepted_course_id: CourseId,
+                                             accepted_owner: AccountId)
+                                             -> Result<(CourseRegistrationSwapProposal, usize), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_SWAPS)?;
+        self.ensure_no_blackout()?;
+        self.ensure_membership_active(student)?;
+
+        // find the proposal of the student
+        let proposal = self.proposals.get(proposal_id).ok_or(Error::SwapProposalNotFound)?;
+        if proposal.offer.owner != student {
+            return Err(Error::NotProposalOwner)
+        }
+
+        // find the accepted counter offer
+        let position = Self::find_counter_offer(&proposal, accepted_course_id, accepted_owner)?;
+        if let Some(violation) = self.settlement_violations(&proposal, &proposal.counter_offers[position])
+            .into_iter()
+            .next() {
+            return Err(violation);
+        }
+        Ok((proposal, position))
+    }
+
+    /// settles every acceptance of the batch on its own
+    ///
+    /// returns the result of each acceptance, in the order of the batch
+    pub(crate) fn accept_counter_offers_inner(&mut self,
+                                              batch: Vec<CounterOfferRef>) -> Result<Vec<Result<(), Error>>, Error> {
+        self.ensure_version()?;
+        if batch.len() > MAX_BATCH_SIZE as usize {
+            return Err(Error::BatchTooLarge);
+        }
+        Ok(batch.into_iter()
+           .map(|counter| self.accept_counter_offer_inner(counter.proposal_id, counter.course_id, counter.owner).map(|_| ()))
+           .collect())
+    }
+
+    /// exchanges the seats of the students directly, only the registrar can
+    ///
+    /// the students agreed with the registrar, so the swap deadlines and
+    /// the course constraints aren't checked, the tokens have to be held
+    /// by the students, not locked in a proposal
+    pub(crate) fn admin_swap_inner(&mut self,
+                                   student_a: AccountId,
+                                   course_a: CourseId,
+                                   student_b: AccountId,
+                                   course_b: CourseId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.require_role(Role::Owner)?;
+        if !self.courses.contains(course_a) || !self.courses.contains(course_b) {
+            return Err(Error::NonexistentCourse);
+        }
+        if !self.registrations.contains(TokenId { owner: student_a, course_id: course_a })
+            || !self.registrations.contains(TokenId { owner: student_b, course_id: course_b }) {
+            return Err(Error::RegistrationNotOwned);
+        }
+        if self.enrollments.contains((course_b, student_a)) || self.enrollments.contains((course_a, student_b)) {
+            return Err(Error::AlreadyRegistered);
+        }
+        self.ensure_not_frozen(student_a, course_a)?;
+        self.ensure_not_frozen(student_b, course_b)?;
+        self.move_registration(course_a, Some(student_a), Some(student_b))?;
+        self.move_registration(course_b, Some(student_b), Some(student_a))?;
+        self.record_activity(student_a, course_b, ActivityKind::Swapped);
+        self.record_activity(student_b, course_a, ActivityKind::Swapped);
+        Self::env().emit_event(AdminSwapped {
+            registrar: Self::env().caller(),
+            student_a,
+            course_a,
+            student_b,
+            course_b,
+        });
+        Ok(())
+    }
+
+    /// takes the proposal back, every escrowed token returns to its owner
+    pub(crate) fn cancel_swap_proposal_inner(&mut self, proposal_id: ProposalId) -> Result<(), Error> {
+        self.ensure_version()?;
+        let proposal = self.proposals.get(proposal_id).ok_or(Error::SwapProposalNotFound)?;
+        if proposal.offer.owner != Self::env().caller() {
+            return Err(Error::NotProposalOwner);
+        }
+        if !proposal.counter_offers.is_empty() {
+            self.record_swap_outcome(proposal.offer.owner, SwapOutcome::WithdrawnAfterCounters);
+        }
+        self.remove_proposal(&proposal);
+        self.mint(proposal.offer.course_id, proposal.offer.owner);
+        for counter_offer in proposal.counter_offers {
+            self.mint(counter_offer.course_id, counter_offer.owner);
+        }
+        Ok(())
+    }
+
+    /// takes the caller's counter offer back from the proposal
+    pub(crate) fn withdraw_counter_offer_inner(&mut self,
+                                               proposal_id: ProposalId,
+                                               course_id: CourseId) -> Result<(), Error> {
+        self.ensure_version()?;
+        let caller = Self::env().caller();
+        let mut proposal = self.proposals.get(proposal_id).ok_or(Error::SwapProposalNotFound)?;
+        let position = Self::find_counter_offer(&proposal, course_id, caller)?;
+        proposal.counter_offers.remove(position);
+        self.proposals.insert(proposal_id, &proposal);
+        self.release_from_escrow(proposal_id, caller);
+        self.mint(course_id, caller);
+        Ok(())
+    }
+
+    /// reports every check accepting the counter offer would fail,
+    /// without touching the proposal
+    pub(crate) fn preview_swap_inner(&self,
+                                     proposal_id: ProposalId,
+                                     accepted_course_id: CourseId,
+                                     accepted_owner: AccountId) -> Result<SwapPreview, Error> {
+        self.ensure_feature(FEATURE_SWAPS)?;
+        let proposal = self.proposals.get(proposal_id).ok_or(Error::SwapProposalNotFound)?;
+        let counter = Self::find_counter_offer(&proposal, accepted_course_id, accepted_owner)?;
+        Ok(SwapPreview {
+            violations: self.settlement_violations(&proposal, &proposal.counter_offers[counter]),
+        })
+    }
+
+    /// returns the position of the counter offer in the proposal
+    fn find_counter_offer(proposal: &CourseRegistrationSwapProposal,
+                          course_id: CourseId,
+                          owner: AccountId) -> Result<usize, Error> {
+        proposal.counter_offers.iter()
+            .position(|counter_off| counter_off.owner == owner && counter_off.course_id == course_id)
+            .ok_or(Error::CounterOfferNotFound)
+    }
+
+    /// returns the checks the settlement of the proposal with the counter
+    /// offer fails, in the order the settlement runs them
+    pub(crate) fn settlement_violations(&self,
+                                        proposal: &CourseRegistrationSwapProposal,
+                                        counter: &CourseRegistration) -> Vec<Error> {
+        let proposer = proposal.offer.owner;
+        let offered_course_id = proposal.offer.course_id;
+        let checks = [
+            self.ensure_no_blackout(),
+            self.ensure_swaps_open(offered_course_id),
+            self.ensure_swaps_open(counter.course_id),
+            self.ensure_swap_policy(offered_course_id, counter.course_id),
+            self.ensure_not_frozen(proposer, offered_course_id),
+            self.ensure_not_frozen(counter.owner, counter.course_id),
+            self.ensure_swap_pair_cap(proposer, counter.owner, offered_course_id),
+        ];
+        let mut violations: Vec<Error> = checks.into_iter().filter_map(Result::err).collect();
+        // either side may have registered to the other's course since the counter offer
+        if self.enrollments.contains((counter.course_id, proposer))
+            || self.enrollments.contains((offered_course_id, counter.owner)) {
+            violations.push(Error::AlreadyRegistered);
+        }
+        let legs = [
+            self.check_swap_leg(proposer, counter.course_id, offered_course_id),
+            self.check_swap_leg(counter.owner, offered_course_id, counter.course_id),
+        ];
+        violations.extend(legs.into_iter().filter_map(Result::err));
+        violations
+    }
+
+    /// fails if the student couldn't register to the incoming course while
+    /// leaving the outgoing one, the seats are exchanged so the capacity
+    /// isn't checked and the swap deadline replaces the registration one
+    fn check_swap_leg(&self, student: AccountId, incoming: CourseId, outgoing: CourseId) -> Result<(), Error> {
+        let course = self.courses.get(incoming).ok_or(Error::NonexistentCourse)?;
+        self.ensure_membership_active(student)?;
+        let opens_at = self.registration_opening(&course);
+        if Self::now() < opens_at {
+            return Err(Error::RegistrationNotOpen { opens_at });
+        }
+        if self.supervision_topics.contains(incoming) {
+            return Err(Error::ApplicationRequired);
+        }
+        if self.feature_enabled(FEATURE_REGISTRATION_QUESTIONS) && self.registration_questions.contains(incoming) {
+            return Err(Error::AnswerRequired);
+        }
+        // the escrowed outgoing token no longer counts towards the credits in progress
+        self.ensure_standing_allows(student, incoming, &[])?;
+        self.ensure_corequisites(student, incoming, &[])?;
+        if self.feature_enabled(FEATURE_COREQUISITES)
+            && self.corequisites.get(incoming).unwrap_or_default().contains(&outgoing) {
+            return Err(Error::MissingCorequisite { corequisite: outgoing });
+        }
+        Ok(())
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Swap proposal creation test
+    #[ink::test]
+    fn swap_proposal_creation() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let teacher = AccountId::from([0x1; 32]);
+        let student = AccountId::from([0x2; 32]);
+        let course_name = "test_course".as_bytes();
+        let course_id = CourseId(hash_keccak_256(course_name));
+        let course_cap:u32 = 10;
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+
+        assert_eq!(course_reg.admit_as_teacher(teacher, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert!(course_reg.is_school_member(teacher));
+        assert!(course_reg.is_teacher(teacher));
+        assert!(course_reg.is_school_member(student));
+        set_next_caller(teacher);
+        assert_eq!(course_reg.create_course(course_id, course_cap, start_time, term_id, None, None), Ok(()));
+        assert_ne!(course_reg.get_course_info(course_id), Err(Error::NonexistentCourse));
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        assert_ne!(course_reg.get_own_registrations(), Err(Error::NoRegistrations));
+
+        assert_eq!(course_reg.propose_swap(course_id, None), Ok(ProposalId(0)));
+        assert_eq!(course_reg.get_proposed_swaps(course_id, None, 10).unwrap().items.len(),1);
+        assert_eq!(course_reg.get_own_registrations(), Err(Error::NoRegistrations));
+    }
+
+    /// Unaccepted counter offers are returned on settlement test
+    #[ink::test]
+    fn unaccepted_counter_offers_returned() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let teacher = AccountId::from([0x1; 32]);
+        let student1 = AccountId::from([0x2; 32]);
+        let student2 = AccountId::from([0x3; 32]);
+        let student3 = AccountId::from([0x4; 32]);
+        let course_id1 = CourseId(hash_keccak_256("test_course1".as_bytes()));
+        let course_id2 = CourseId(hash_keccak_256("test_course2".as_bytes()));
+        let course_id3 = CourseId(hash_keccak_256("test_course3".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_teacher(teacher, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student1, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student2, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student3, None), Ok(()));
+        set_next_caller(teacher);
+        assert_eq!(course_reg.create_course(course_id1, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id2, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id3, 10, start_time, term_id, None, None), Ok(()));
+        set_next_caller(student1);
+        assert_eq!(course_reg.register_to_course(course_id1, None).map(|_| ()), Ok(()));
+        let proposal_id = course_reg.propose_swap(course_id1, None).unwrap();
+        set_next_caller(student2);
+        assert_eq!(course_reg.register_to_course(course_id2, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id2, None), Ok(()));
+        set_next_caller(student3);
+        assert_eq!(course_reg.register_to_course(course_id3, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id3, None), Ok(()));
+
+        set_next_caller(student1);
+        assert_eq!(course_reg.accept_counter_offer(proposal_id, course_id2, student2, None).map(|_| ()), Ok(()));
+        set_next_caller(student3);
+        assert_eq!(course_reg.get_own_registrations(),
+                   Ok(ink_prelude::vec![RegistrationView { version: VIEW_VERSION, course_id: course_id3, owner: student3 }]));
+        assert!(course_reg.is_registered(course_id3, student3));
+        assert_eq!(course_reg.get_course_registrations(course_id1, None, 10).map(|page| page.items), Ok(ink_prelude::vec![student2]));
+        assert_eq!(course_reg.get_course_registrations(course_id2, None, 10).map(|page| page.items), Ok(ink_prelude::vec![student1]));
+    }
+
+    /// Full happy path test
+    #[ink::test]
+    fn accept_counter_offer() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let teacher = AccountId::from([0x1; 32]);
+        let student1 = AccountId::from([0x2; 32]);
+        let student2 = AccountId::from([0x3; 32]);
+        let course_name1 = "test_course1".as_bytes();
+        let course_id1 = CourseId(hash_keccak_256(course_name1));
+        let course_name2 = "test_course2".as_bytes();
+        let course_id2 = CourseId(hash_keccak_256(course_name2));
+        let course_cap:u32 = 10;
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+
+        assert_eq!(course_reg.admit_as_teacher(teacher, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student1, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student2, None), Ok(()));
+        assert!(course_reg.is_school_member(teacher));
+        assert!(course_reg.is_teacher(teacher));
+        assert!(course_reg.is_school_member(student1));
+        assert!(course_reg.is_school_member(student2));
+        set_next_caller(teacher);
+        assert_eq!(course_reg.create_course(course_id1, course_cap, start_time, term_id, None, None), Ok(()));
+        assert_ne!(course_reg.get_course_info(course_id1), Err(Error::NonexistentCourse));
+        assert_eq!(course_reg.create_course(course_id2, course_cap, start_time, term_id, None, None), Ok(()));
+        assert_ne!(course_reg.get_course_info(course_id2), Err(Error::NonexistentCourse));
+        set_next_caller(student1);
+        assert_eq!(course_reg.register_to_course(course_id1, None).map(|_| ()), Ok(()));
+        assert_ne!(course_reg.get_own_registrations(), Err(Error::NoRegistrations));
+        let proposal_id = course_reg.propose_swap(course_id1, None).unwrap();
+        assert_eq!(course_reg.get_proposed_swaps(course_id1, None, 10).unwrap().items.len(),1);
+        set_next_caller(student2);
+        assert_eq!(course_reg.register_to_course(course_id2, None).map(|_| ()), Ok(()));
+        assert_ne!(course_reg.get_own_registrations(), Err(Error::NoRegistrations));
+
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id2, None), Ok(()));
+        set_next_caller(student2);
+        assert_eq!(course_reg.get_own_registrations(), Err(Error::NoRegistrations));
+
+        set_next_caller(student1);
+        assert_eq!(course_reg.accept_counter_offer(proposal_id, course_id2, student2, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.get_swap_proposal(proposal_id), Err(Error::SwapProposalNotFound));
+        assert_eq!(course_reg.get_proposed_swaps(course_id1, None, 10), Err(Error::NoProposedSwap));
+        let pos = course_reg.get_own_registrations().unwrap().iter().position(|course| course.course_id == course_id2);
+        assert!(pos.is_some());
+    }
+
+    /// Rosters agree with the tokens after a settlement test
+    #[ink::test]
+    fn settlement_persists_rosters() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student1 = AccountId::from([0x1; 32]);
+        let student2 = AccountId::from([0x2; 32]);
+        let student3 = AccountId::from([0x3; 32]);
+        let course_id1 = CourseId(hash_keccak_256("test_course1".as_bytes()));
+        let course_id2 = CourseId(hash_keccak_256("test_course2".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        for student in [student1, student2, student3] {
+            assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        }
+        assert_eq!(course_reg.create_course(course_id1, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id2, 10, start_time, term_id, None, None), Ok(()));
+        set_next_caller(student1);
+        assert_eq!(course_reg.register_to_course(course_id1, None).map(|_| ()), Ok(()));
+        set_next_caller(student3);
+        assert_eq!(course_reg.register_to_course(course_id1, None).map(|_| ()), Ok(()));
+        set_next_caller(student2);
+        assert_eq!(course_reg.register_to_course(course_id2, None).map(|_| ()), Ok(()));
+        set_next_caller(student1);
+        let proposal_id = course_reg.propose_swap(course_id1, None).unwrap();
+        set_next_caller(student2);
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id2, None), Ok(()));
+        set_next_caller(student1);
+        assert_eq!(course_reg.accept_counter_offer(proposal_id, course_id2, student2, None).map(|_| ()), Ok(()));
+
+        // the seat keeps its place in the roster, only its holder changes
+        assert_eq!(course_reg.get_course_registrations(course_id1, None, 10).map(|page| page.items), Ok(ink_prelude::vec![student2, student3]));
+        assert_eq!(course_reg.get_course_registrations(course_id2, None, 10).map(|page| page.items), Ok(ink_prelude::vec![student1]));
+        for (course_id, student) in [(course_id1, student2), (course_id1, student3), (course_id2, student1)] {
+            assert!(course_reg.registrations.contains(TokenId { owner: student, course_id }));
+        }
+        assert!(!course_reg.registrations.contains(TokenId { owner: student1, course_id: course_id1 }));
+        assert!(!course_reg.registrations.contains(TokenId { owner: student2, course_id: course_id2 }));
+        assert_eq!(course_reg.check_invariants(ink_prelude::vec![course_id1, course_id2],
+                                               ink_prelude::vec![student1, student2, student3]),
+                   Ok(ink_prelude::vec![]));
+    }
+
+    /// Distinct swap failure errors test
+    #[ink::test]
+    fn swap_errors() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student1 = AccountId::from([0x1; 32]);
+        let student2 = AccountId::from([0x2; 32]);
+        let course_id1 = CourseId(hash_keccak_256("test_course1".as_bytes()));
+        let course_id2 = CourseId(hash_keccak_256("test_course2".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_student(student1, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student2, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id1, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id2, 10, start_time, term_id, None, None), Ok(()));
+        set_next_caller(student1);
+        assert_eq!(course_reg.register_to_course(course_id1, None).map(|_| ()), Ok(()));
+        let proposal_id = course_reg.propose_swap(course_id1, None).unwrap();
+
+        set_next_caller(student2);
+        assert_eq!(course_reg.get_swap_proposal(ProposalId(proposal_id.0 + 1)), Err(Error::SwapProposalNotFound));
+        assert_eq!(course_reg.counter_swap_proposal(ProposalId(proposal_id.0 + 1), course_id2, None), Err(Error::SwapProposalNotFound));
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id2, None), Err(Error::RegistrationNotOwned));
+        assert_eq!(course_reg.register_to_course(course_id2, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id2, None), Ok(()));
+        assert_eq!(course_reg.accept_counter_offer(proposal_id, course_id2, student2, None), Err(Error::NotProposalOwner));
+
+        set_next_caller(student1);
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id2, None), Err(Error::AlreadyRegistered));
+        assert_eq!(course_reg.accept_counter_offer(proposal_id, course_id1, student2, None), Err(Error::CounterOfferNotFound));
+        assert_eq!(course_reg.accept_counter_offer(proposal_id, course_id2, student2, None).map(|_| ()), Ok(()));
+    }
+
+    /// Course constraints of both settlement legs test
+    #[ink::test]
+    fn swap_leg_constraints() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student1 = AccountId::from([0x1; 32]);
+        let student2 = AccountId::from([0x2; 32]);
+        let course_id1 = CourseId(hash_keccak_256("test_course1".as_bytes()));
+        let course_id2 = CourseId(hash_keccak_256("test_course2".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_student(student1, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student2, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id1, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id2, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.set_course_credits(course_id1, 6, None), Ok(()));
+        assert_eq!(course_reg.set_course_credits(course_id2, 13, None), Ok(()));
+        set_next_caller(student1);
+        assert_eq!(course_reg.register_to_course(course_id1, None).map(|_| ()), Ok(()));
+        let proposal_id = course_reg.propose_swap(course_id1, None).unwrap();
+        set_next_caller(student2);
+        assert_eq!(course_reg.register_to_course(course_id2, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id2, None), Ok(()));
+
+        // the proposer on probation couldn't register to the heavier course
+        set_next_caller(owner);
+        assert_eq!(course_reg.set_standing(student1, AcademicStanding::Probation, None), Ok(()));
+        set_next_caller(student1);
+        let cap_exceeded = Error::CreditCapExceeded { cap: PROBATION_CREDIT_CAP };
+        assert_eq!(course_reg.accept_counter_offer(proposal_id, course_id2, student2, None), Err(cap_exceeded));
+
+        // neither could the suspended counter offerer
+        set_next_caller(owner);
+        assert_eq!(course_reg.set_standing(student1, AcademicStanding::Good, None), Ok(()));
+        assert_eq!(course_reg.set_standing(student2, AcademicStanding::Suspended, None), Ok(()));
+        set_next_caller(student1);
+        assert_eq!(course_reg.accept_counter_offer(proposal_id, course_id2, student2, None), Err(Error::StudentSuspended));
+
+        // a corequisite of the incoming course can't be the one left
+        set_next_caller(owner);
+        assert_eq!(course_reg.set_standing(student2, AcademicStanding::Good, None), Ok(()));
+        assert_eq!(course_reg.set_corequisites(course_id2, ink_prelude::vec![course_id1], None), Ok(()));
+        let missing = Error::MissingCorequisite { corequisite: course_id1 };
+        assert_eq!(course_reg.preview_swap(proposal_id, course_id2, student2),
+                   Ok(SwapPreview { violations: ink_prelude::vec![missing] }));
+        assert_eq!(course_reg.set_corequisites(course_id2, ink_prelude::vec![], None), Ok(()));
+        set_next_caller(student1);
+        assert_eq!(course_reg.accept_counter_offer(proposal_id, course_id2, student2, None).map(|_| ()), Ok(()));
+        assert!(course_reg.is_registered(course_id2, student1));
+        assert!(course_reg.is_registered(course_id1, student2));
+    }
+
+    /// Swap settlement preview test
+    #[ink::test]
+    fn swap_preview() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student1 = AccountId::from([0x1; 32]);
+        let student2 = AccountId::from([0x2; 32]);
+        let course_id1 = CourseId(hash_keccak_256("test_course1".as_bytes()));
+        let course_id2 = CourseId(hash_keccak_256("test_course2".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_student(student1, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student2, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id1, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id2, 10, start_time, term_id, None, None), Ok(()));
+        set_next_caller(student1);
+        assert_eq!(course_reg.register_to_course(course_id1, None).map(|_| ()), Ok(()));
+        let proposal_id = course_reg.propose_swap(course_id1, None).unwrap();
+        set_next_caller(student2);
+        assert_eq!(course_reg.register_to_course(course_id2, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id2, None), Ok(()));
+
+        assert_eq!(course_reg.preview_swap(ProposalId(proposal_id.0 + 1), course_id2, student2), Err(Error::SwapProposalNotFound));
+        assert_eq!(course_reg.preview_swap(proposal_id, course_id1, student2), Err(Error::CounterOfferNotFound));
+        assert_eq!(course_reg.preview_swap(proposal_id, course_id2, student2), Ok(SwapPreview { violations: ink_prelude::vec![] }));
+
+        // every failing check is reported, nothing changes
+        TestClock::set(start_time);
+        let closed = Error::SwapsClosed { closed_at: start_time };
+        let closed_twice = ink_prelude::vec![closed, Error::SwapsClosed { closed_at: start_time }];
+        assert_eq!(course_reg.preview_swap(proposal_id, course_id2, student2), Ok(SwapPreview { violations: closed_twice }));
+        assert!(course_reg.get_swap_proposal(proposal_id).is_ok());
+        set_next_caller(student1);
+        assert_eq!(course_reg.accept_counter_offer(proposal_id, course_id2, student2, None),
+                   Err(Error::SwapsClosed { closed_at: start_time }));
+    }
+
+    /// Swaps frozen during a blackout test
+    #[ink::test]
+    fn swap_blackout() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        let now = start_time - 7 * MILLIS_PER_DAY;
+        let blackout = Blackout { start: now, end: now + MILLIS_PER_DAY };
+        let config = SchoolConfig { blackouts: ink_prelude::vec![Blackout { start: now, end: now }], ..SchoolConfig::default() };
+        assert_eq!(course_reg.set_school_config(config, None), Err(Error::InvalidBlackout));
+        let config = SchoolConfig { blackouts: ink_prelude::vec![blackout], ..SchoolConfig::default() };
+        assert_eq!(course_reg.set_school_config(config, None), Ok(()));
+
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.propose_swap(course_id, None), Err(Error::BlackoutActive { ends_at: blackout.end }));
+        assert!(course_reg.is_registered(course_id, student));
+        TestClock::set(blackout.end);
+        assert!(course_reg.propose_swap(course_id, None).is_ok());
+    }
+
+    /// Swaps disabled by the feature flags test
+    #[ink::test]
+    fn swaps_disabled() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        let flags = FeatureFlags { disabled: FEATURE_SWAPS };
+        assert_eq!(course_reg.set_feature_flags(flags, None), Ok(()));
+        assert_eq!(course_reg.supported_features() & FEATURE_SWAPS, 0);
+
+        set_next_caller(student);
+        assert_eq!(course_reg.set_feature_flags(FeatureFlags::default(), None), Err(Error::InsufficientPermissions));
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.propose_swap(course_id, None), Err(Error::FeatureDisabled { feature: FEATURE_SWAPS }));
+        assert_eq!(course_reg.get_proposed_swaps(course_id, None, 10), Err(Error::NoProposedSwap));
+    }
+
+    /// Batch acceptance of counter offers test
+    #[ink::test]
+    fn accept_counter_offers_batch() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student1 = AccountId::from([0x1; 32]);
+        let student2 = AccountId::from([0x2; 32]);
+        let student3 = AccountId::from([0x3; 32]);
+        let course_ids: Vec<CourseId> = (0..4u8).map(|i| CourseId(hash_keccak_256(&[i]))).collect();
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        for student in [student1, student2, student3] {
+            assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        }
+        for course_id in &course_ids {
+            assert_eq!(course_reg.create_course(*course_id, 10, start_time, term_id, None, None), Ok(()));
+        }
+        set_next_caller(student1);
+        assert_eq!(course_reg.register_to_courses(ink_prelude::vec![course_ids[0], course_ids[1]], None).map(|_| ()), Ok(()));
+        let proposal_id1 = course_reg.propose_swap(course_ids[0], None).unwrap();
+        let proposal_id2 = course_reg.propose_swap(course_ids[1], None).unwrap();
+        set_next_caller(student2);
+        assert_eq!(course_reg.register_to_course(course_ids[2], None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id1, course_ids[2], None), Ok(()));
+        set_next_caller(student3);
+        assert_eq!(course_reg.register_to_course(course_ids[3], None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id2, course_ids[3], None), Ok(()));
+
+        let accepted = |proposal_id, course_id, owner| CounterOfferRef { proposal_id, course_id, owner };
+        let batch = ink_prelude::vec![
+            accepted(proposal_id1, course_ids[2], student2),
+            accepted(proposal_id1, course_ids[2], student2),
+            accepted(proposal_id2, course_ids[2], student3),
+            accepted(proposal_id2, course_ids[3], student3),
+        ];
+        set_next_caller(student1);
+        assert_eq!(course_reg.accept_counter_offers(batch, None), Ok(ink_prelude::vec![
+            Ok(()),
+            Err(Error::SwapProposalNotFound),
+            Err(Error::CounterOfferNotFound),
+            Ok(()),
+        ]));
+        assert!(course_reg.is_registered(course_ids[2], student1));
+        assert!(course_reg.is_registered(course_ids[3], student1));
+        assert!(course_reg.is_registered(course_ids[0], student2));
+        assert!(course_reg.is_registered(course_ids[1], student3));
+        let too_large = ink_prelude::vec![accepted(proposal_id1, course_ids[2], student2); MAX_BATCH_SIZE as usize + 1];
+        assert_eq!(course_reg.accept_counter_offers(too_large, None), Err(Error::BatchTooLarge));
+    }
+
+    /// Registrar seat exchange test
+    #[ink::test]
+    fn admin_swap() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student1 = AccountId::from([0x1; 32]);
+        let student2 = AccountId::from([0x2; 32]);
+        let course_id1 = CourseId(hash_keccak_256("test_course1".as_bytes()));
+        let course_id2 = CourseId(hash_keccak_256("test_course2".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_student(student1, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student2, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id1, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id2, 10, start_time, term_id, None, None), Ok(()));
+        set_next_caller(student1);
+        assert_eq!(course_reg.register_to_course(course_id1, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.admin_swap(student1, course_id1, student2, course_id2, None),
+                   Err(Error::InsufficientPermissions));
+        set_next_caller(owner);
+        assert_eq!(course_reg.admin_swap(student1, course_id1, student2, course_id2, None),
+                   Err(Error::RegistrationNotOwned));
+        set_next_caller(student2);
+        assert_eq!(course_reg.register_to_course(course_id2, None).map(|_| ()), Ok(()));
+
+        // the swap deadline has passed, the registrar can still exchange the seats
+        TestClock::set(start_time);
+        set_next_caller(owner);
+        let events = ink_env::test::recorded_events().count();
+        assert_eq!(course_reg.admin_swap(student1, course_id1, student2, course_id2, None), Ok(()));
+        assert_eq!(ink_env::test::recorded_events().count(), events + 1);
+        assert!(course_reg.is_registered(course_id2, student1));
+        assert!(course_reg.is_registered(course_id1, student2));
+        assert_eq!(course_reg.admin_swap(student1, course_id2, student2, course_id2, None),
+                   Err(Error::RegistrationNotOwned));
+        assert_eq!(course_reg.check_invariants(ink_prelude::vec![course_id1, course_id2],
+                                               ink_prelude::vec![student1, student2]),
+                   Ok(ink_prelude::vec![]));
+    }
+}
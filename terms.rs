@@ -0,0 +1,274 @@
+//! Terms, the semesters whose deadlines govern the courses
+
+use ink_prelude::vec::Vec;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn create_term_inner(&mut self, term: Term) -> Result<TermId, Error> {
+        self.ensure_version()?;
+        self.require_role(Role::Owner)?;
+        let now = Self::now();
+        if term.start < MIN_TIMESTAMP
+            || term.start >= term.end
+            || term.end <= now
+            || term.start > now.saturating_add(MAX_SCHEDULE_AHEAD)
+            || term.add_deadline < term.start
+            || term.add_deadline > term.end
+            || term.drop_deadline < term.start
+            || term.drop_deadline > term.end
+            || term.swap_deadline < term.start
+            || term.swap_deadline > term.end {
+            return Err(Error::InvalidTerm);
+        }
+        let term_id = self.next_term_id.get_or_default();
+        self.next_term_id.set(&term_id.checked_add(1).ok_or(Error::ArithmeticOverflow)?);
+        self.terms.insert(term_id, &term);
+        Ok(term_id)
+    }
+
+    /// returns the term of the course, courses migrated from
+    /// before the terms were introduced have none
+    pub(crate) fn course_term(&self, course_id: CourseId) -> Option<Term> {
+        self.course_terms.get(course_id).and_then(|term_id| self.terms.get(term_id))
+    }
+
+    /// returns the time registration to the course opens, the start of
+    /// its term or right away for a course without a term, unless it
+    /// was opened earlier by hand
+    pub(crate) fn registration_opening(&self, course: &Course) -> Timestamp {
+        let scheduled = self.course_term(course.course_id)
+            .map(|term| term.start)
+            .unwrap_or(0);
+        let transitions = self.registration_transitions.get(course.course_id).unwrap_or_default();
+        transitions.opened_at.map_or(scheduled, |opened_at| opened_at.min(scheduled))
+    }
+
+    /// returns the time registration to the course closes, the add
+    /// deadline of its term or the start of a course without a term
+    pub(crate) fn registration_deadline(&self, course: &Course) -> Timestamp {
+        let scheduled = self.course_term(course.course_id)
+            .map(|term| term.add_deadline)
+            .unwrap_or(course.start_date);
+        let transitions = self.registration_transitions.get(course.course_id).unwrap_or_default();
+        transitions.closed_at.map_or(scheduled, |closed_at| closed_at.min(scheduled))
+    }
+
+    /// fails once the swap deadline of the course's term has passed,
+    /// courses without a term can be swapped until they start
+    pub(crate) fn ensure_swaps_open(&self, course_id: CourseId) -> Result<(), Error> {
+        let closed_at = match self.course_term(course_id) {
+            Some(term) => term.swap_deadline,
+            None => self.courses.get(course_id).ok_or(Error::NonexistentCourse)?.start_date,
+        };
+        if Self::now() >= closed_at {
+            return Err(Error::SwapsClosed { closed_at });
+        }
+        Ok(())
+    }
+
+    /// fails once the drop deadline of the course's term has passed,
+    /// courses without a term have no drop deadline
+    pub(crate) fn ensure_drops_open(&self, course_id: CourseId) -> Result<(), Error> {
+        if let Some(term) = self.course_term(course_id) {
+            if Self::now() >= term.drop_deadline {
+                return Err(Error::DropsClosed { closed_at: term.drop_deadline });
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn is_course_archived_inner(&self, course_id: CourseId) -> bool {
+        self.course_term(course_id)
+            .map(|term| term.end <= Self::now())
+            .unwrap_or(false)
+    }
+
+    /// cleans up the courses of the term after it's over, a batch at a time
+    ///
+    /// returns the number of proposals expired in each course, in the order of the batch
+    pub(crate) fn close_term_inner(&mut self,
+                                   term_id: TermId,
+                                   course_ids: Vec<CourseId>) -> Result<Vec<Result<u32, Error>>, Error> {
+        self.ensure_version()?;
+        self.require_role(Role::Owner)?;
+        let term = self.terms.get(term_id).ok_or(Error::NonexistentTerm)?;
+        if Self::now() < term.end {
+            return Err(Error::TermNotOver { ends_at: term.end });
+        }
+        if course_ids.len() > MAX_BATCH_SIZE as usize {
+            return Err(Error::BatchTooLarge);
+        }
+        Ok(course_ids.into_iter().map(|course_id| self.close_term_course(term_id, course_id)).collect())
+    }
+
+    /// ends the course if its teacher didn't, expires its proposals and
+    /// drops the indices nothing reads after the term
+    ///
+    /// the batch doesn't revert a failing course, so every check runs
+    /// before the first write and a failing course is left as it was
+    fn close_term_course(&mut self, term_id: TermId, course_id: CourseId) -> Result<u32, Error> {
+        let course = self.courses.get(course_id).ok_or(Error::NonexistentCourse)?;
+        if self.course_terms.get(course_id) != Some(term_id) {
+            return Err(Error::CourseOutsideTerm);
+        }
+        let ended = self.course_ends.contains(course_id);
+        if !ended && course.start_date > Self::now() {
+            return Err(Error::CourseNotStarted);
+        }
+
+        if !ended {
+            self.record_course_end(&course);
+        }
+        self.mark_registration_phase(course_id);
+        let expired = self.expire_proposals(course_id);
+        self.course_proposals.remove(course_id);
+        for student in self.standing_counter_index.get(course_id).unwrap_or_default() {
+            self.remove_standing_counter(course_id, student);
+        }
+        self.standing_counter_index.remove(course_id);
+        self.registration_transitions.remove(course_id);
+        self.tie_break_policies.remove(course_id);
+        self.drop_seat_requests(course_id);
+        Ok(expired)
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Term deadlines and archiving test
+    #[ink::test]
+    fn term_deadlines() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let now = start_time - 7 * MILLIS_PER_DAY;
+        let term = Term {
+            start: now,
+            end: now + 100 * MILLIS_PER_DAY,
+            add_deadline: now + MILLIS_PER_DAY,
+            drop_deadline: now + 14 * MILLIS_PER_DAY,
+            swap_deadline: now + 2 * MILLIS_PER_DAY,
+        };
+        assert_eq!(course_reg.create_term(Term { add_deadline: term.end + 1, ..term }, None), Err(Error::InvalidTerm));
+        assert_eq!(course_reg.create_term(Term { add_deadline: term.start - 1, ..term }, None), Err(Error::InvalidTerm));
+        assert_eq!(course_reg.create_term(Term { drop_deadline: term.start - 1, ..term }, None), Err(Error::InvalidTerm));
+        assert_eq!(course_reg.create_term(Term { swap_deadline: term.start - 1, ..term }, None), Err(Error::InvalidTerm));
+        let far = Term {
+            start: now + MAX_SCHEDULE_AHEAD + 1,
+            end: now + MAX_SCHEDULE_AHEAD + 100 * MILLIS_PER_DAY,
+            add_deadline: now + MAX_SCHEDULE_AHEAD + 1,
+            drop_deadline: now + MAX_SCHEDULE_AHEAD + 1,
+            swap_deadline: now + MAX_SCHEDULE_AHEAD + 1,
+        };
+        assert_eq!(course_reg.create_term(far, None), Err(Error::InvalidTerm));
+        let term_id = course_reg.create_term(term, None).unwrap();
+        assert_eq!(course_reg.create_course(course_id, 10, term.end, term_id, None, None), Err(Error::CourseOutsideTerm));
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id + 1, None, None), Err(Error::NonexistentTerm));
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+
+        // registration closes at the add deadline, before the course starts
+        set_next_caller(student);
+        TestClock::set(term.add_deadline);
+        assert_eq!(course_reg.register_to_course(course_id, None),
+                   Err(Error::RegistrationClosed { closed_at: term.add_deadline }));
+        TestClock::set(now);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        TestClock::set(term.swap_deadline);
+        assert_eq!(course_reg.propose_swap(course_id, None), Err(Error::SwapsClosed { closed_at: term.swap_deadline }));
+
+        assert!(!course_reg.is_course_archived(course_id));
+        TestClock::set(term.end);
+        assert!(course_reg.is_course_archived(course_id));
+    }
+
+    /// Cleanup of the courses of an ended term test
+    #[ink::test]
+    fn term_closing() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let teacher = AccountId::from([0x1; 32]);
+        let student = AccountId::from([0x2; 32]);
+        let course_id1 = CourseId(hash_keccak_256("test_course1".as_bytes()));
+        let course_id2 = CourseId(hash_keccak_256("test_course2".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        let other_term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_teacher(teacher, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        set_next_caller(teacher);
+        assert_eq!(course_reg.create_course(course_id1, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id2, 10, start_time, other_term_id, None, None), Ok(()));
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(course_id1, None).map(|_| ()), Ok(()));
+        let proposal_id = course_reg.propose_swap(course_id1, None).unwrap();
+
+        set_next_caller(owner);
+        let term_end = start_time + 100 * MILLIS_PER_DAY;
+        assert_eq!(course_reg.close_term(term_id, ink_prelude::vec![course_id1], None),
+                   Err(Error::TermNotOver { ends_at: term_end }));
+        TestClock::set(term_end);
+        assert_eq!(course_reg.close_term(term_id, ink_prelude::vec![course_id1, course_id2, CourseId([0x1; 32])], None),
+                   Ok(ink_prelude::vec![Ok(1), Err(Error::CourseOutsideTerm), Err(Error::NonexistentCourse)]));
+        assert_eq!(course_reg.get_course_info(course_id1).unwrap().state, CourseState::Archived);
+        assert_eq!(course_reg.get_swap_proposal(proposal_id), Err(Error::SwapProposalNotFound));
+        assert!(course_reg.is_registered(course_id1, student));
+        set_next_caller(teacher);
+        assert_eq!(course_reg.end_course(course_id1, None), Err(Error::CourseAlreadyEnded));
+    }
+
+    /// Term closing leaving a failing course untouched test
+    #[ink::test]
+    fn term_closing_partial_failure() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let course_id1 = CourseId(hash_keccak_256("test_course1".as_bytes()));
+        let course_id2 = CourseId(hash_keccak_256("test_course2".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        for course_id in [course_id1, course_id2] {
+            assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        }
+        set_next_caller(student);
+        let mut proposal_ids = ink_prelude::vec::Vec::new();
+        for course_id in [course_id1, course_id2] {
+            assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+            proposal_ids.push(course_reg.propose_swap(course_id, None).unwrap());
+        }
+
+        // the second course fails its check, after the first one was closed
+        set_next_caller(owner);
+        let term_end = start_time + 100 * MILLIS_PER_DAY;
+        let mut course = course_reg.courses.get(course_id2).unwrap();
+        course.start_date = term_end + 1;
+        course_reg.courses.insert(course_id2, &course);
+        TestClock::set(term_end);
+        assert_eq!(course_reg.close_term(term_id, ink_prelude::vec![course_id1, course_id2], None),
+                   Ok(ink_prelude::vec![Ok(1), Err(Error::CourseNotStarted)]));
+        assert_eq!(course_reg.get_swap_proposal(proposal_ids[0]), Err(Error::SwapProposalNotFound));
+        assert!(course_reg.course_ends.contains(course_id1));
+        assert!(!course_reg.course_ends.contains(course_id2));
+        assert!(course_reg.get_swap_proposal(proposal_ids[1]).is_ok());
+        assert_eq!(course_reg.course_proposals.get(course_id2), Some(ink_prelude::vec![proposal_ids[1]]));
+        assert_eq!(course_reg.get_active_course_count(owner), 1);
+    }
+}
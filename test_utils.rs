@@ -0,0 +1,45 @@
+//! Helpers shared by the unit tests of the modules
+
+use ink_env::hash;
+
+use crate::clock::TestClock;
+use crate::course_reg::CourseReg;
+use crate::types::*;
+
+pub fn set_next_caller(caller: AccountId) {
+    ink_env::test::set_caller::<ink_env::DefaultEnvironment>(caller);
+}
+
+pub fn get_current_time() -> Timestamp {
+    let since_the_epoch = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("Time went backwards");
+    since_the_epoch.as_millis() as Timestamp
+}
+
+/// sets the test clock to the current time and
+/// returns a course start date a week from now
+pub fn get_start_time() -> Timestamp {
+    let now = get_current_time();
+    TestClock::set(now);
+    now + 7 * MILLIS_PER_DAY
+}
+
+pub fn hash_keccak_256(input: &[u8]) -> [u8; 32] {
+    let mut output = <hash::Keccak256 as hash::HashOutput>::Type::default();
+    ink_env::hash_bytes::<hash::Keccak256>(input, &mut output);
+    output
+}
+
+/// creates a term around the course start date as the owner,
+/// its courses can be registered to and swapped until they start
+pub fn create_term(course_reg: &mut CourseReg, start_time: Timestamp) -> TermId {
+    let term = Term {
+        start: start_time - 7 * MILLIS_PER_DAY,
+        end: start_time + 100 * MILLIS_PER_DAY,
+        add_deadline: start_time,
+        drop_deadline: start_time + 14 * MILLIS_PER_DAY,
+        swap_deadline: start_time,
+    };
+    course_reg.create_term(term, None).unwrap()
+}
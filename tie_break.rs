@@ -0,0 +1,227 @@
+//! The order of the registrations that arrive together when registration opens
+//!
+//! under a policy other than first come, first served, the registrations of
+//! the first `TIE_BREAK_WINDOW` after the opening are collected as requests
+//! and seated in the policy's order once the window passed
+
+use ink_lang::codegen::StaticEnv;
+use ink_prelude::vec::Vec;
+use scale::Encode;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    /// sets the policy of the course, before its registration opens
+    pub(crate) fn set_tie_break_policy_inner(&mut self, course_id: CourseId, policy: TieBreakPolicy) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_TIE_BREAK)?;
+        let course = self.require_course_admin(course_id)?;
+        let state = self.course_state_of(&course);
+        if state != CourseState::Announced {
+            return Err(Error::InvalidCourseTransition { state });
+        }
+        if policy == TieBreakPolicy::FirstComeFirstServed {
+            self.tie_break_policies.remove(course_id);
+        } else {
+            self.tie_break_policies.insert(course_id, &policy);
+        }
+        Ok(())
+    }
+
+    /// returns the end of the collection window of the course, if the window is open
+    pub(crate) fn collection_window(&self, course_id: CourseId) -> Option<Timestamp> {
+        if !self.feature_enabled(FEATURE_TIE_BREAK) || !self.tie_break_policies.contains(course_id) {
+            return None;
+        }
+        let course = self.courses.get(course_id)?;
+        let opens_at = self.registration_opening(&course);
+        let settles_at = opens_at.saturating_add(TIE_BREAK_WINDOW);
+        let now = Self::now();
+        (opens_at <= now && now < settles_at).then_some(settles_at)
+    }
+
+    /// fails while the registrations to the course are collected
+    pub(crate) fn ensure_not_collecting(&self, course_id: CourseId) -> Result<(), Error> {
+        match self.collection_window(course_id) {
+            Some(settles_at) => Err(Error::RegistrationCollecting { settles_at }),
+            None => Ok(()),
+        }
+    }
+
+    /// records the caller's request for a seat, the request is checked like a registration
+    pub(crate) fn request_seat_inner(&mut self, course_id: CourseId) -> Result<(), Error> {
+        self.ensure_version()?;
+        self.ensure_feature(FEATURE_TIE_BREAK)?;
+        let caller = Self::env().caller();
+        if self.collection_window(course_id).is_none() {
+            return Err(Error::NotCollecting);
+        }
+        self.mark_registration_phase(course_id);
+        self.check_register_to_courses(caller, &[course_id])?;
+        let mut requests = self.seat_requests.get(course_id).unwrap_or_default();
+        if requests.contains(&caller) {
+            return Err(Error::AlreadyRequested);
+        }
+        let mut requested = self.seat_request_courses.get(caller).unwrap_or_default();
+        if requests.len() >= MAX_SEAT_REQUESTS as usize
+            || requested.len() >= MAX_REGISTRATIONS_PER_ACCOUNT as usize {
+            return Err(Error::TooManySeatRequests);
+        }
+        requests.push(caller);
+        self.seat_requests.insert(course_id, &requests);
+        requested.push(course_id);
+        self.seat_request_courses.insert(caller, &requested);
+        Ok(())
+    }
+
+    /// removes the seat requests of the course and returns them
+    /// in request order, the requesters no longer list the course
+    pub(crate) fn drop_seat_requests(&mut self, course_id: CourseId) -> Vec<AccountId> {
+        let requests = self.seat_requests.get(course_id).unwrap_or_default();
+        self.seat_requests.remove(course_id);
+        for student in &requests {
+            let mut requested = self.seat_request_courses.get(student).unwrap_or_default();
+            requested.retain(|requested_id| *requested_id != course_id);
+            if requested.is_empty() {
+                self.seat_request_courses.remove(student);
+            } else {
+                self.seat_request_courses.insert(student, &requested);
+            }
+        }
+        requests
+    }
+
+    /// seats the collected requests in the order of the course's policy,
+    /// anyone can settle once the collection window passed
+    ///
+    /// returns the number of seated requests, the others no longer pass the checks
+    pub(crate) fn settle_registrations_inner(&mut self, course_id: CourseId) -> Result<u32, Error> {
+        self.ensure_version()?;
+        if !self.courses.contains(course_id) {
+            return Err(Error::NonexistentCourse);
+        }
+        self.ensure_not_collecting(course_id)?;
+        Ok(self.settle_requests(course_id))
+    }
+
+    /// seats the collected requests of the course, if there are any
+    pub(crate) fn settle_requests(&mut self, course_id: CourseId) -> u32 {
+        let requests = self.drop_seat_requests(course_id);
+        if requests.is_empty() {
+            return 0;
+        }
+        let policy = self.tie_break_policies.get(course_id).unwrap_or_default();
+        let mut seated = 0;
+        for student in self.tie_break_order(course_id, policy, requests) {
+            if self.check_register_to_courses(student, &[course_id]).is_err() {
+                continue;
+            }
+            if self.move_registration(course_id, None, Some(student)).is_ok() {
+                self.record_activity(student, course_id, ActivityKind::Added);
+                seated += 1;
+            }
+        }
+        seated
+    }
+
+    /// orders the requests by the policy, request order breaks the remaining ties
+    fn tie_break_order(&self, course_id: CourseId, policy: TieBreakPolicy, mut requests: Vec<AccountId>) -> Vec<AccountId> {
+        match policy {
+            TieBreakPolicy::FirstComeFirstServed => (),
+            TieBreakPolicy::Randomized => {
+                // a deterministic shuffle: the seed depends on the set of requests, not
+                // on their order in the block, and is known as soon as the set is
+                requests.sort();
+                let mut seed = Self::hash_keccak_256(&(course_id, &requests).encode());
+                for i in (1..requests.len()).rev() {
+                    seed = Self::hash_keccak_256(&seed);
+                    let mut bytes = [0u8; 8];
+                    bytes.copy_from_slice(&seed[..8]);
+                    let j = (u64::from_le_bytes(bytes) % (i as u64 + 1)) as usize;
+                    requests.swap(i, j);
+                }
+            }
+            TieBreakPolicy::Seniority => {
+                requests.sort_by_key(|student| core::cmp::Reverse(self.transcripts.get(student).unwrap_or_default().len()));
+            }
+        }
+        requests
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Seniority tie-break of the opening registrations test
+    #[ink::test]
+    fn seniority_tie_break() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let junior = AccountId::from([0x1; 32]);
+        let senior = AccountId::from([0x2; 32]);
+        let late = AccountId::from([0x3; 32]);
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        let past_id = CourseId(hash_keccak_256("past_course".as_bytes()));
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let commitment = hash_keccak_256("grade".as_bytes());
+        for student in [junior, senior, late] {
+            assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        }
+
+        // the senior student has a graded course
+        assert_eq!(course_reg.create_course(past_id, 10, start_time, term_id, None, None), Ok(()));
+        set_next_caller(senior);
+        assert_eq!(course_reg.register_to_course(past_id, None).map(|_| ()), Ok(()));
+        set_next_caller(owner);
+        TestClock::set(start_time);
+        assert_eq!(course_reg.end_course(past_id, None), Ok(()));
+        assert_eq!(course_reg.record_grade(past_id, senior, commitment, None), Ok(()));
+
+        let next_start = start_time + 30 * MILLIS_PER_DAY;
+        let next_term_id = create_term(&mut course_reg, next_start);
+        let opens_at = course_reg.get_term(next_term_id).unwrap().start;
+        assert_eq!(course_reg.create_course(course_id, 1, next_start, next_term_id, None, None), Ok(()));
+        set_next_caller(junior);
+        assert_eq!(course_reg.set_tie_break_policy(course_id, TieBreakPolicy::Seniority, None),
+                   Err(Error::InsufficientPermissions));
+        set_next_caller(owner);
+        assert_eq!(course_reg.set_tie_break_policy(course_id, TieBreakPolicy::Seniority, None), Ok(()));
+        assert_eq!(course_reg.get_tie_break_policy(course_id), TieBreakPolicy::Seniority);
+
+        // the requests of the window are seated by seniority
+        TestClock::set(opens_at);
+        let settles_at = opens_at + TIE_BREAK_WINDOW;
+        set_next_caller(junior);
+        assert_eq!(course_reg.register_to_course(course_id, None), Err(Error::RegistrationCollecting { settles_at }));
+        assert_eq!(course_reg.request_seat(course_id, None), Ok(()));
+        assert_eq!(course_reg.request_seat(course_id, None), Err(Error::AlreadyRequested));
+        set_next_caller(senior);
+        assert_eq!(course_reg.request_seat(course_id, None), Ok(()));
+        assert_eq!(course_reg.get_seat_requests(course_id), ink_prelude::vec![junior, senior]);
+        set_next_caller(owner);
+        assert_eq!(course_reg.set_tie_break_policy(course_id, TieBreakPolicy::Randomized, None),
+                   Err(Error::InvalidCourseTransition { state: CourseState::RegistrationOpen }));
+        assert_eq!(course_reg.settle_registrations(course_id, None), Err(Error::RegistrationCollecting { settles_at }));
+
+        // the first registration after the window settles the requests
+        TestClock::set(settles_at);
+        set_next_caller(late);
+        assert_eq!(course_reg.request_seat(course_id, None), Err(Error::NotCollecting));
+        assert_eq!(course_reg.register_to_course(course_id, None), Err(Error::CourseCapacityFull { capacity: 1 }));
+        assert!(course_reg.is_registered(course_id, senior));
+        assert!(!course_reg.is_registered(course_id, junior));
+        assert_eq!(course_reg.settle_registrations(course_id, None), Ok(0));
+    }
+}
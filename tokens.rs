@@ -0,0 +1,204 @@
+//! The CourseRegistration token primitives, every token change goes through them
+//!
+//! a token is held by its owner in the token storage, listed once in the
+//! owner's index, or locked in a swap proposal while it's offered, never both.
+//! The proposals locking an account's tokens are listed in its escrow index
+//!
+//! a token is identified by its owner and its course: course ids are never
+//! reused, across terms neither, and an account holds at most one token of
+//! a course, so the swap messages naming a course name a single token
+
+use ink_prelude::vec::Vec;
+
+use crate::course_reg::CourseReg;
+use crate::types::*;
+
+impl CourseReg {
+    /// creates the owner's CourseRegistration token for the course,
+    /// an owner holds at most one token per course
+    pub(crate) fn mint(&mut self, course_id: CourseId, owner: AccountId) {
+        if self.registrations.contains(TokenId { owner, course_id }) {
+            return;
+        }
+        let token = CourseRegistration { owner, course_id };
+        self.registrations.insert(token.token_id(), &token);
+        let mut index = self.registration_index.get(owner).unwrap_or_default();
+        index.push(course_id);
+        self.registration_index.insert(owner, &index);
+    }
+
+    /// takes the owner's CourseRegistration token for the course
+    /// out of the token storage, if the owner has one
+    pub(crate) fn burn(&mut self, course_id: CourseId, owner: AccountId) -> Option<CourseRegistration> {
+        let token = self.registrations.get(TokenId { owner, course_id })?;
+        self.registrations.remove(TokenId { owner, course_id });
+        let mut index = self.registration_index.get(owner).unwrap_or_default();
+        index.retain(|id| id != &course_id);
+        self.registration_index.insert(owner, &index);
+        Some(token)
+    }
+
+    /// lists the proposal in the owner's escrow index, once per token it locks
+    pub(crate) fn lock_in_escrow(&mut self, proposal_id: ProposalId, owner: AccountId) {
+        let mut index = self.escrow_index.get(owner).unwrap_or_default();
+        index.push(proposal_id);
+        self.escrow_index.insert(owner, &index);
+    }
+
+    /// removes one listing of the proposal from the owner's escrow index
+    pub(crate) fn release_from_escrow(&mut self, proposal_id: ProposalId, owner: AccountId) {
+        let mut index = self.escrow_index.get(owner).unwrap_or_default();
+        if let Some(position) = index.iter().position(|id| *id == proposal_id) {
+            index.remove(position);
+        }
+        if index.is_empty() {
+            self.escrow_index.remove(owner);
+        } else {
+            self.escrow_index.insert(owner, &index);
+        }
+    }
+
+    /// returns the owner's tokens locked in swap proposals
+    pub(crate) fn get_escrowed_inner(&self, owner: AccountId) -> Vec<EscrowedToken> {
+        let mut proposal_ids = self.escrow_index.get(owner).unwrap_or_default();
+        proposal_ids.sort_unstable();
+        proposal_ids.dedup();
+        let mut escrowed = Vec::new();
+        for proposal in proposal_ids.into_iter().filter_map(|proposal_id| self.proposals.get(proposal_id)) {
+            let tokens = core::iter::once(&proposal.offer).chain(proposal.counter_offers.iter());
+            for token in tokens.filter(|token| token.owner == owner) {
+                escrowed.push(EscrowedToken { proposal_id: proposal.proposal_id, course_id: token.course_id });
+            }
+        }
+        escrowed
+    }
+
+    /// moves the token of the course from one owner to another,
+    /// `from: None` mints a new token and `to: None` burns it
+    ///
+    /// the `from` token may already be locked in a swap proposal,
+    /// then only the new one is minted
+    pub(crate) fn move_token(&mut self, course_id: CourseId, from: Option<AccountId>, to: Option<AccountId>) {
+        if let Some(from) = from {
+            self.burn(course_id, from);
+        }
+        if let Some(to) = to {
+            self.mint(course_id, to);
+        }
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// returns the number of tokens the account holds,
+    /// failing if the index and the token storage disagree
+    fn held_tokens(course_reg: &CourseReg, account: AccountId) -> usize {
+        let index = course_reg.registration_index.get(account).unwrap_or_default();
+        for (position, course_id) in index.iter().enumerate() {
+            assert!(!index[..position].contains(course_id));
+            assert!(course_reg.registrations.contains(TokenId { owner: account, course_id: *course_id }));
+        }
+        index.len()
+    }
+
+    /// Every token exists exactly once test
+    #[ink::test]
+    fn token_invariants() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student1 = AccountId::from([0x1; 32]);
+        let student2 = AccountId::from([0x2; 32]);
+        let student3 = AccountId::from([0x3; 32]);
+        let course_id1 = CourseId(hash_keccak_256("test_course1".as_bytes()));
+        let course_id2 = CourseId(hash_keccak_256("test_course2".as_bytes()));
+        let course_id3 = CourseId(hash_keccak_256("test_course3".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        for (student, course_id) in [(student1, course_id1), (student2, course_id2), (student3, course_id3)] {
+            assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+            assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        }
+        course_reg.mint(course_id1, student1);
+        course_reg.mint(course_id1, student1);
+        assert_eq!(held_tokens(&course_reg, student1), 1);
+        assert!(course_reg.burn(course_id1, student1).is_some());
+        assert!(course_reg.burn(course_id1, student1).is_none());
+
+        for (student, course_id) in [(student1, course_id1), (student2, course_id2), (student3, course_id3)] {
+            set_next_caller(student);
+            assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+        }
+        set_next_caller(student1);
+        let proposal_id = course_reg.propose_swap(course_id1, None).unwrap();
+        assert_eq!(held_tokens(&course_reg, student1), 0);
+        set_next_caller(student2);
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id2, None), Ok(()));
+        set_next_caller(student3);
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id3, None), Ok(()));
+        set_next_caller(student1);
+        assert_eq!(course_reg.accept_counter_offer(proposal_id, course_id2, student2, None).map(|_| ()), Ok(()));
+
+        // the accepted tokens changed hands, the other counter offer went back
+        for (student, course_id) in [(student1, course_id2), (student2, course_id1), (student3, course_id3)] {
+            assert_eq!(held_tokens(&course_reg, student), 1);
+            assert!(course_reg.registrations.contains(TokenId { owner: student, course_id }));
+            assert!(course_reg.is_registered(course_id, student));
+        }
+    }
+
+    /// Escrowed tokens and their recovery test
+    #[ink::test]
+    fn token_escrow() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student1 = AccountId::from([0x1; 32]);
+        let student2 = AccountId::from([0x2; 32]);
+        let course_id1 = CourseId(hash_keccak_256("test_course1".as_bytes()));
+        let course_id2 = CourseId(hash_keccak_256("test_course2".as_bytes()));
+        let course_id3 = CourseId(hash_keccak_256("test_course3".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_student(student1, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student2, None), Ok(()));
+        for course_id in [course_id1, course_id2, course_id3] {
+            assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        }
+        set_next_caller(student1);
+        assert_eq!(course_reg.register_to_course(course_id1, None).map(|_| ()), Ok(()));
+        let proposal_id = course_reg.propose_swap(course_id1, None).unwrap();
+        set_next_caller(student2);
+        assert_eq!(course_reg.register_to_courses(ink_prelude::vec![course_id2, course_id3], None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id2, None), Ok(()));
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id3, None), Ok(()));
+        let escrowed = |course_id| EscrowedToken { proposal_id, course_id };
+        assert_eq!(course_reg.get_escrowed(student1), ink_prelude::vec![escrowed(course_id1)]);
+        assert_eq!(course_reg.get_escrowed(student2), ink_prelude::vec![escrowed(course_id2), escrowed(course_id3)]);
+        assert_eq!(held_tokens(&course_reg, student2), 0);
+
+        // the counter offerer takes one token back
+        assert_eq!(course_reg.cancel_swap_proposal(proposal_id, None), Err(Error::NotProposalOwner));
+        assert_eq!(course_reg.withdraw_counter_offer(proposal_id, course_id1, None), Err(Error::CounterOfferNotFound));
+        assert_eq!(course_reg.withdraw_counter_offer(proposal_id, course_id2, None), Ok(()));
+        assert_eq!(course_reg.get_escrowed(student2), ink_prelude::vec![escrowed(course_id3)]);
+        assert_eq!(held_tokens(&course_reg, student2), 1);
+
+        // the proposer cancels, every token returns
+        set_next_caller(student1);
+        assert_eq!(course_reg.cancel_swap_proposal(proposal_id, None), Ok(()));
+        assert_eq!(course_reg.get_escrowed(student1), ink_prelude::vec![]);
+        assert_eq!(course_reg.get_escrowed(student2), ink_prelude::vec![]);
+        assert_eq!(held_tokens(&course_reg, student1), 1);
+        assert_eq!(held_tokens(&course_reg, student2), 2);
+        assert_eq!(course_reg.get_swap_proposal(proposal_id), Err(Error::SwapProposalNotFound));
+    }
+}
@@ -0,0 +1,102 @@
+//! Registration opened or closed by hand, ahead of the term's schedule
+//!
+//! the term's dates still drive the course, a registrar can only move
+//! the registration boundaries earlier, never past the scheduled ones
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    /// opens registration to an announced course now, before its term starts
+    pub(crate) fn open_registration_inner(&mut self, course_id: CourseId) -> Result<(), Error> {
+        self.transition(course_id, CourseState::Announced, |transition, now| transition.opened_at = Some(now))
+    }
+
+    /// closes registration to the course now, before its add deadline
+    pub(crate) fn close_registration_inner(&mut self, course_id: CourseId) -> Result<(), Error> {
+        self.transition(course_id, CourseState::RegistrationOpen, |transition, now| transition.closed_at = Some(now))
+    }
+
+    /// records the transition of the course if it's in the `from` state,
+    /// the teacher of the course or the owner can make it
+    fn transition(&mut self,
+                  course_id: CourseId,
+                  from: CourseState,
+                  record: impl FnOnce(&mut RegistrationTransitions, Timestamp)) -> Result<(), Error> {
+        self.ensure_version()?;
+        let course = self.require_course_admin(course_id)?;
+        let state = self.course_state_of(&course);
+        if state != from {
+            return Err(Error::InvalidCourseTransition { state });
+        }
+        let mut transitions = self.registration_transitions.get(course_id).unwrap_or_default();
+        record(&mut transitions, Self::now());
+        self.registration_transitions.insert(course_id, &transitions);
+        self.mark_registration_phase(course_id);
+        Ok(())
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Registration opened and closed by hand test
+    #[ink::test]
+    fn registration_transitions() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let teacher = AccountId::from([0x1; 32]);
+        let student = AccountId::from([0x2; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let now = get_start_time() - 7 * MILLIS_PER_DAY;
+        let start_time = now + 30 * MILLIS_PER_DAY;
+        let term = Term {
+            start: now + 10 * MILLIS_PER_DAY,
+            end: start_time + 100 * MILLIS_PER_DAY,
+            add_deadline: start_time,
+            drop_deadline: start_time,
+            swap_deadline: start_time,
+        };
+        let term_id = course_reg.create_term(term, None).unwrap();
+        assert_eq!(course_reg.admit_as_teacher(teacher, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        set_next_caller(teacher);
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.get_course_info(course_id).unwrap().state, CourseState::Announced);
+        assert_eq!(course_reg.close_registration(course_id, None),
+                   Err(Error::InvalidCourseTransition { state: CourseState::Announced }));
+        set_next_caller(student);
+        assert_eq!(course_reg.open_registration(course_id, None), Err(Error::InsufficientPermissions));
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()),
+                   Err(Error::RegistrationNotOpen { opens_at: term.start }));
+
+        // the teacher opens registration ahead of the term
+        set_next_caller(teacher);
+        assert_eq!(course_reg.open_registration(course_id, None), Ok(()));
+        assert_eq!(course_reg.get_course_info(course_id).unwrap().state, CourseState::RegistrationOpen);
+        set_next_caller(student);
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+
+        // and the owner closes it ahead of the add deadline
+        TestClock::set(now + MILLIS_PER_DAY);
+        set_next_caller(owner);
+        assert_eq!(course_reg.close_registration(course_id, None), Ok(()));
+        assert_eq!(course_reg.get_course_info(course_id).unwrap().state, CourseState::RegistrationClosed);
+        assert_eq!(course_reg.open_registration(course_id, None),
+                   Err(Error::InvalidCourseTransition { state: CourseState::RegistrationClosed }));
+        assert_eq!(course_reg.admit_as_student(AccountId::from([0x3; 32]), None), Ok(()));
+        set_next_caller(AccountId::from([0x3; 32]));
+        assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()),
+                   Err(Error::RegistrationClosed { closed_at: now + MILLIS_PER_DAY }));
+    }
+}
@@ -0,0 +1,1134 @@
+//! The data types and limits shared by the contract modules
+
+use ink_prelude::vec::Vec;
+use ink_storage::traits::{PackedAllocate, PackedLayout, SpreadAllocate, SpreadLayout};
+
+use crate::errors::Error;
+
+/// The account id of the environment the contract runs in
+pub type AccountId = <ink_env::DefaultEnvironment as ink_env::Environment>::AccountId;
+/// The balance of the environment the contract runs in
+pub type Balance = <ink_env::DefaultEnvironment as ink_env::Environment>::Balance;
+/// The timestamp of the environment the contract runs in, milliseconds since the unix epoch
+pub type Timestamp = <ink_env::DefaultEnvironment as ink_env::Environment>::Timestamp;
+
+/// The storage layout version this code works with
+///
+/// Bumped by every upgrade that changes the layout, messages refuse
+/// to touch storage written with a different version.
+pub const STORAGE_VERSION: u32 = 6;
+/// The version of the view shapes, bumped when a view changes
+pub const VIEW_VERSION: u32 = 1;
+
+/// The maximum number of enrollment periods kept per student and course, the oldest goes first
+pub const MAX_ENROLLMENT_PERIODS: u32 = 16;
+
+/// Milliseconds in a day, timestamps are milliseconds since the unix epoch
+pub const MILLIS_PER_DAY: Timestamp = 24 * 60 * 60 * 1000;
+/// The earliest timestamp accepted by the messages (2001-09-09),
+/// anything below is most likely given in seconds instead of milliseconds
+pub const MIN_TIMESTAMP: Timestamp = 1_000_000_000_000;
+/// How far in the future timestamps given to the messages may lie
+pub const MAX_SCHEDULE_AHEAD: Timestamp = 5 * 365 * MILLIS_PER_DAY;
+
+/// The max capacity of a course, bounds the roster of every course
+pub const MAX_COURSE_CAPACITY: u32 = 250;
+/// The max number of active courses a teacher's quota can allow
+pub const MAX_COURSES_PER_TEACHER: u32 = 50;
+/// The max number of seats past the capacity kept for accommodated students
+pub const MAX_ACCOMMODATION_BUFFER: u32 = 10;
+/// The default number of seats past the capacity kept for accommodated students
+pub const DEFAULT_ACCOMMODATION_BUFFER: u32 = 2;
+/// The default number of active courses a teacher can have
+pub const DEFAULT_COURSE_QUOTA: u32 = 20;
+/// The max number of registration tokens an account can hold
+pub const MAX_REGISTRATIONS_PER_ACCOUNT: u32 = 50;
+/// The max number of public seats of a course
+pub const MAX_PUBLIC_CAPACITY: u32 = 10_000;
+/// The time an account waits between two public registrations
+pub const PUBLIC_REGISTRATION_COOLDOWN: Timestamp = 60 * 60 * 1000;
+/// The max number of courses in a student's draft plan
+pub const MAX_PLANNED_COURSES: u32 = 20;
+/// The max number of open swap proposals offering the same course
+pub const MAX_PROPOSALS_PER_COURSE: u32 = 100;
+/// The max number of proposal ids `find_swaps` walks for a page without an offered course
+pub const MAX_SWAP_SCAN: u64 = 200;
+/// The max number of counter offers a swap proposal can collect
+pub const MAX_COUNTER_OFFERS: u32 = 20;
+/// The max number of standing counter offers waiting for a course
+pub const MAX_STANDING_COUNTERS: u32 = 20;
+/// The max number of grades recorded for a student
+pub const MAX_GRADES_PER_STUDENT: u32 = 200;
+/// The max number of credits a course can be worth
+pub const MAX_COURSE_CREDITS: u32 = 30;
+/// The max number of attendance sessions a course can have
+pub const MAX_SESSIONS_PER_COURSE: u32 = 500;
+/// The max number of office-hour slots a course can have
+pub const MAX_OFFICE_HOURS_PER_COURSE: u32 = 200;
+
+/// The max number of assignments a course can have
+pub const MAX_ASSIGNMENTS_PER_COURSE: u32 = 100;
+
+/// How long after the end of a course its students can submit evaluations
+pub const EVALUATION_COMMIT_PERIOD: Timestamp = 7 * MILLIS_PER_DAY;
+/// How long after the commit period the evaluations can be revealed
+pub const EVALUATION_REVEAL_PERIOD: Timestamp = 7 * MILLIS_PER_DAY;
+/// The lowest score of a course evaluation
+pub const MIN_EVALUATION_SCORE: u8 = 1;
+/// The highest score of a course evaluation
+pub const MAX_EVALUATION_SCORE: u8 = 5;
+
+/// The max number of courses listed by a program, required and elective
+pub const MAX_PROGRAM_COURSES: u32 = 100;
+/// The max number of elective buckets of a program
+pub const MAX_ELECTIVE_BUCKETS: u32 = 10;
+
+/// The domain prefix of the derived course ids
+pub const COURSE_ID_DOMAIN: &[u8] = b"course_reg::course_id";
+/// The domain prefix of the schedule commitments
+pub const SCHEDULE_DOMAIN: &[u8] = b"course_reg::schedule";
+/// The max length of a sealed notice in bytes
+pub const MAX_SEALED_NOTICE_LENGTH: u32 = 256;
+/// The max length of the name of a grade in bytes
+pub const MAX_GRADE_LABEL_LENGTH: u32 = 8;
+/// The max length of a course's metadata URI in bytes
+pub const MAX_URI_LENGTH: u32 = 256;
+/// The max number of blackout windows of the school
+pub const MAX_BLACKOUTS: u32 = 10;
+/// The time a student's membership stays valid after admission, unless renewed
+pub const MEMBERSHIP_VALIDITY: Timestamp = 6 * 365 * MILLIS_PER_DAY;
+/// The max number of students admitted ahead of their activation at a time
+pub const MAX_PENDING_ACTIVATIONS: u32 = 100;
+/// The time after registration opens during which a tie-break policy collects the requests, about a block
+pub const TIE_BREAK_WINDOW: Timestamp = 6_000;
+/// The max number of seat requests collected per course
+pub const MAX_SEAT_REQUESTS: u32 = 200;
+/// The max number of accounts of a batch membership call
+pub const MAX_BATCH_SIZE: u32 = 100;
+/// The max number of accounts of a batch that also get an event each, larger batches are only summarized
+pub const MAX_ITEMIZED_BATCH: u32 = 8;
+/// The max length of a cohort's name in bytes
+pub const MAX_COHORT_NAME_LENGTH: u32 = 64;
+/// The max number of roster snapshots of a course
+pub const MAX_SNAPSHOTS_PER_COURSE: u32 = 50;
+/// The max number of exams of a course
+pub const MAX_EXAMS_PER_COURSE: u32 = 20;
+/// The max number of applications to a supervision slot
+pub const MAX_SUPERVISION_APPLICATIONS: u32 = 50;
+/// The max number of corequisites of a course
+pub const MAX_COREQUISITES: u32 = 10;
+/// The max credits in progress of a student on probation
+pub const PROBATION_CREDIT_CAP: u32 = 12;
+
+// the feature flags of `supported_features`, one bit per optional subsystem,
+// new subsystems take the next bit and the bits are never reused
+/// The flag of the registration swap proposals
+pub const FEATURE_SWAPS: u64 = 1 << 0;
+/// The flag of the committed grades and academic summaries
+pub const FEATURE_GRADES: u64 = 1 << 1;
+/// The flag of the attendance sessions
+pub const FEATURE_ATTENDANCE: u64 = 1 << 2;
+/// The flag of the assignment submissions
+pub const FEATURE_ASSIGNMENTS: u64 = 1 << 3;
+/// The flag of the course evaluations
+pub const FEATURE_EVALUATIONS: u64 = 1 << 4;
+/// The flag of the degree programs
+pub const FEATURE_PROGRAMS: u64 = 1 << 5;
+/// The flag of the academic standing
+pub const FEATURE_STANDING: u64 = 1 << 6;
+/// The flag of the corequisites and batch registration
+pub const FEATURE_COREQUISITES: u64 = 1 << 7;
+/// The flag of the supervision slots
+pub const FEATURE_SUPERVISION: u64 = 1 << 8;
+/// The flag of the exam sittings
+pub const FEATURE_EXAMS: u64 = 1 << 9;
+/// The flag of the cohorts
+pub const FEATURE_COHORTS: u64 = 1 << 10;
+/// The flag of the seat releases between students
+pub const FEATURE_SEAT_RELEASES: u64 = 1 << 11;
+/// The flag of the profile hashes
+pub const FEATURE_PROFILES: u64 = 1 << 12;
+/// The flag of the course metadata URIs
+pub const FEATURE_COURSE_METADATA: u64 = 1 << 13;
+/// The flag of the roster snapshots
+pub const FEATURE_ROSTER_SNAPSHOTS: u64 = 1 << 14;
+/// The flag of the sealed notifications
+pub const FEATURE_NOTIFICATIONS: u64 = 1 << 15;
+/// The flag of the draft enrollment plans
+pub const FEATURE_PLANS: u64 = 1 << 16;
+/// The flag of the open enrollment to public courses
+pub const FEATURE_PUBLIC_ENROLLMENT: u64 = 1 << 17;
+/// The flag of the registration questions
+pub const FEATURE_REGISTRATION_QUESTIONS: u64 = 1 << 18;
+/// The flag of the accommodated registrations
+pub const FEATURE_ACCOMMODATIONS: u64 = 1 << 19;
+/// The flag of the per-course swap policies
+pub const FEATURE_SWAP_POLICIES: u64 = 1 << 20;
+/// The flag of the registration hooks of the courses
+pub const FEATURE_REGISTRATION_HOOKS: u64 = 1 << 21;
+/// The flag of the office-hour bookings
+pub const FEATURE_OFFICE_HOURS: u64 = 1 << 22;
+/// The flag of the tie-break policies of the opening registrations
+pub const FEATURE_TIE_BREAK: u64 = 1 << 23;
+/// The features this version of the contract implements
+pub const SUPPORTED_FEATURES: u64 = FEATURE_SWAPS
+    | FEATURE_GRADES
+    | FEATURE_ATTENDANCE
+    | FEATURE_ASSIGNMENTS
+    | FEATURE_EVALUATIONS
+    | FEATURE_PROGRAMS
+    | FEATURE_STANDING
+    | FEATURE_COREQUISITES
+    | FEATURE_SUPERVISION
+    | FEATURE_EXAMS
+    | FEATURE_COHORTS
+    | FEATURE_SEAT_RELEASES
+    | FEATURE_PROFILES
+    | FEATURE_COURSE_METADATA
+    | FEATURE_ROSTER_SNAPSHOTS
+    | FEATURE_NOTIFICATIONS
+    | FEATURE_PLANS
+    | FEATURE_PUBLIC_ENROLLMENT
+    | FEATURE_REGISTRATION_QUESTIONS
+    | FEATURE_ACCOMMODATIONS
+    | FEATURE_SWAP_POLICIES
+    | FEATURE_REGISTRATION_HOOKS
+    | FEATURE_OFFICE_HOURS
+    | FEATURE_TIE_BREAK;
+
+/// The id of a cohort, assigned in creation order
+pub type CohortId = u32;
+/// The id of a roster snapshot of a course, assigned per course in snapshot order
+pub type SnapshotId = u32;
+/// The id of an exam of a course, assigned in creation order
+pub type ExamId = u32;
+/// The id of a degree program, assigned in creation order
+pub type ProgramId = u32;
+/// The most entries in a chunk of a state export
+pub const EXPORT_CHUNK_SIZE: u32 = 50;
+/// The students a full course turns away before it's reported in high demand
+pub const HIGH_DEMAND_THRESHOLD: u32 = 10;
+
+/// The id of a department, chosen by the owner
+pub type DepartmentId = u32;
+/// The id of a room, chosen by the owner
+pub type RoomId = u32;
+/// The max number of courses held in a room
+pub const MAX_COURSES_PER_ROOM: u32 = 100;
+/// The id of a term, assigned in creation order
+pub type TermId = u32;
+/// The id of an attendance session, assigned per course in opening order
+pub type SessionId = u32;
+/// The id of an office-hour slot, assigned per course in publishing order
+pub type SlotId = u32;
+/// The id of an assignment, assigned per course in creation order
+pub type AssignmentId = u32;
+
+/// A grade given by a teacher, in the points of the school's grade scale
+pub type Grade = u8;
+
+/// A university course created by a teacher
+///
+/// the roster lives in its own storage cell, so reading a course
+/// doesn't decode every registered student
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, PartialEq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct Course {
+    /// the teacher who created the course
+    pub(crate) teacher: AccountId,
+    /// the id of the course
+    pub(crate) course_id: CourseId,
+    /// the max number of students who can register
+    pub(crate) capacity: u32,
+    /// the starting time of the course
+    pub(crate) start_date: Timestamp,
+    /// the number of registered students
+    pub(crate) enrolled_count: u32,
+}
+
+/// The V3 layout of a course, only read by the migration
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct CourseV3 {
+    pub(crate) teacher: AccountId,
+    pub(crate) course_id: CourseId,
+    pub(crate) capacity: u32,
+    pub(crate) registrations: Vec<AccountId>,
+    pub(crate) start_date: Timestamp,
+    pub(crate) enrolled_count: u32,
+}
+
+/// The progress of a migration step
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct MigrationProgress {
+    /// the number of old entries planned for the step, `None` until the owner planned it
+    pub planned: Option<u32>,
+    /// the number of planned entries converted so far
+    pub converted: u32,
+}
+
+/// The V2 layout of a course, only read by the migration
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct CourseV2 {
+    pub(crate) teacher: AccountId,
+    pub(crate) course_id: CourseId,
+    pub(crate) capacity: u32,
+    pub(crate) registrations: Vec<AccountId>,
+    pub(crate) start_date: Timestamp,
+}
+
+/// The identifier of a course, the hash its teacher chose it by
+///
+/// encoded as the bare hash, so the cells written before it had a type read alike
+#[derive(PackedLayout, SpreadLayout, SpreadAllocate, scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout))]
+pub struct CourseId(pub [u8; 32]);
+
+impl From<[u8; 32]> for CourseId {
+    fn from(hash: [u8; 32]) -> Self {
+        CourseId(hash)
+    }
+}
+
+/// The identifier of a swap proposal, assigned in creation order
+///
+/// encoded as the bare number, so the cells written before it had a type read alike
+#[derive(PackedLayout, SpreadLayout, SpreadAllocate, scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout))]
+pub struct ProposalId(pub u64);
+
+impl From<u64> for ProposalId {
+    fn from(id: u64) -> Self {
+        ProposalId(id)
+    }
+}
+
+impl ProposalId {
+    /// returns the id assigned after this one, None once the ids ran out
+    pub fn next(self) -> Option<ProposalId> {
+        self.0.checked_add(1).map(ProposalId)
+    }
+}
+
+/// The identifier of a registration token, an owner holds at most one token of a course
+///
+/// encoded as the `(owner, course_id)` pair the tokens were first stored by
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct TokenId {
+    /// the owner of the token
+    pub owner: AccountId,
+    /// the course the token registers to
+    pub course_id: CourseId,
+}
+
+/// A course registration token
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, PartialEq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct CourseRegistration {
+    /// the owner of the token
+    pub(crate) owner: AccountId,
+    /// the id of the course
+    pub(crate) course_id: CourseId,
+}
+
+impl CourseRegistration {
+    /// returns the id the token is stored by
+    pub(crate) fn token_id(&self) -> TokenId {
+        TokenId { owner: self.owner, course_id: self.course_id }
+    }
+}
+
+/// A course registration token swap proposal
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, PartialEq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct CourseRegistrationSwapProposal {
+    /// the id of the proposal
+    pub(crate) proposal_id: ProposalId,
+    /// the offered token
+    pub(crate) offer: CourseRegistration,
+    /// the tokens offered in exchange
+    pub(crate) counter_offers: Vec<CourseRegistration>
+}
+
+/// The V4 layout of a swap proposal, only read by the migration
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct SwapProposalV4 {
+    pub(crate) offer: CourseRegistration,
+    pub(crate) counter_offers: Vec<CourseRegistration>
+}
+
+/// The replay and staleness guard of a mutating message
+///
+/// relayed calls carry the next nonce of the caller, so a replayed call
+/// is refused, and optionally the state version they were built against
+#[derive(scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct CallGuard {
+    /// the next nonce of the caller, see `get_nonce`
+    pub nonce: u64,
+    /// the state version the call expects, see `get_state_version`
+    pub expected_state_version: Option<u64>,
+}
+
+/// What a mutating message changed, for front-ends to update without reading it back
+#[derive(scale::Encode, scale::Decode, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct Receipt {
+    /// the state version after the call, see `get_state_version`
+    pub state_version: u64,
+    /// the courses whose seats changed
+    pub course_ids: Vec<CourseId>,
+    /// the free seats of the courses after the call, in the order of `course_ids`
+    pub remaining_seats: Vec<u32>,
+}
+
+/// A grade recorded for a student, committed to by the teacher
+///
+/// only the salted hash of the grade is stored until the student
+/// chooses to disclose it
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, PartialEq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct GradeRecord {
+    /// the id of the graded course
+    pub(crate) course_id: CourseId,
+    /// the hash of the grade and the salt, see `grade_commitment`
+    pub(crate) commitment: [u8; 32],
+    /// the grade, once revealed by the student
+    pub(crate) revealed_grade: Option<Grade>,
+}
+
+/// The school-wide settings, set by the owner
+#[derive(PackedLayout, SpreadLayout, SpreadAllocate, scale::Encode, scale::Decode, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout))]
+pub struct SchoolConfig {
+    /// the grade points of every grade in hundredths, indexed by the grade
+    pub grade_points: Vec<u32>,
+    /// the lowest grade that earns the credits of a course
+    pub passing_grade: Grade,
+    /// the names of the grades, like `A` to `F`, indexed by the grade, empty for a numeric scale
+    pub grade_labels: Vec<Vec<u8>>,
+    /// the quiet periods during which swaps and seat transfers are frozen
+    pub blackouts: Vec<Blackout>,
+    /// the number of active courses a teacher can have, unless the owner overrides it
+    pub course_quota: u32,
+    /// the seats past the capacity of every course accommodated students can take
+    pub accommodation_buffer: u32,
+    /// the swaps two accounts can settle with each other in a term, `None` if uncapped
+    pub swap_pair_cap: Option<u32>,
+}
+
+/// The features the owner disabled on this deployment
+///
+/// new features are enabled by default, their bits aren't set yet
+#[derive(PackedLayout, SpreadLayout, SpreadAllocate, scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout))]
+pub struct FeatureFlags {
+    /// the `FEATURE_...` flags of the disabled features
+    pub disabled: u64,
+}
+
+/// A period during which swaps and seat transfers are frozen
+#[derive(PackedLayout, SpreadLayout, SpreadAllocate, scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout))]
+pub struct Blackout {
+    /// the start of the blackout
+    pub start: Timestamp,
+    /// the end of the blackout, exclusive
+    pub end: Timestamp,
+}
+
+/// the blackouts are plain values packed in the config's Vec
+impl PackedAllocate for Blackout {
+    fn allocate_packed(&mut self, _at: &ink_primitives::Key) {}
+}
+
+impl Default for SchoolConfig {
+    /// the five-point scale, 1 is failing and 5 is the best grade
+    fn default() -> Self {
+        SchoolConfig {
+            grade_points: ink_prelude::vec![0, 100, 200, 300, 400, 500],
+            passing_grade: 2,
+            grade_labels: Vec::new(),
+            blackouts: Vec::new(),
+            course_quota: DEFAULT_COURSE_QUOTA,
+            accommodation_buffer: DEFAULT_ACCOMMODATION_BUFFER,
+            swap_pair_cap: None,
+        }
+    }
+}
+
+/// The credits and grade point average of a student
+#[derive(scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct AcademicSummary {
+    /// the credits of the courses passed with a revealed grade
+    pub credits_earned: u32,
+    /// the credits of the registered courses that haven't ended
+    pub credits_in_progress: u32,
+    /// the credit weighted average of the revealed grades' points, in hundredths
+    pub gpa: u32,
+}
+
+/// An attendance session of a course
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, PartialEq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct Session {
+    /// the start of the check-in window
+    pub(crate) starts: Timestamp,
+    /// the end of the check-in window
+    pub(crate) ends: Timestamp,
+    /// the Keccak256 hash of the check-in code
+    pub(crate) code_hash: [u8; 32],
+}
+
+/// The time of an office-hour slot a teacher publishes
+#[derive(scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct OfficeHourWindow {
+    /// the start of the slot
+    pub starts: Timestamp,
+    /// the end of the slot
+    pub ends: Timestamp,
+}
+
+/// An office-hour slot of a course, for a single student
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout))]
+pub struct OfficeHourSlot {
+    /// the start of the slot
+    pub starts: Timestamp,
+    /// the end of the slot
+    pub ends: Timestamp,
+    /// the student who booked the slot
+    pub booked_by: Option<AccountId>,
+}
+
+/// The attendance of a student in a course
+#[derive(scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct Attendance {
+    /// the number of sessions the student checked in to
+    pub attended: u32,
+    /// the number of sessions opened for the course
+    pub sessions: u32,
+}
+
+/// An assignment of a course
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, PartialEq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct Assignment {
+    /// the time submissions are due
+    pub(crate) deadline: Timestamp,
+}
+
+/// The anchored submission of an assignment
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, PartialEq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct Submission {
+    /// the hash of the submitted content
+    pub(crate) content_hash: [u8; 32],
+    /// the time of the submission
+    pub(crate) submitted_at: Timestamp,
+    /// true if submitted after the deadline
+    pub(crate) late: bool,
+}
+
+/// A student's committed evaluation of a course
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, PartialEq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct Evaluation {
+    /// the hash of the score, the comment hash and the salt, see `evaluation_commitment`
+    pub(crate) commitment: [u8; 32],
+    /// true once the score was revealed and counted
+    pub(crate) revealed: bool,
+}
+
+/// The revealed evaluation scores of a course or a teacher
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, Default, PartialEq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct RatingTotals {
+    /// the number of revealed scores
+    pub(crate) count: u32,
+    /// the sum of the revealed scores
+    pub(crate) score_total: u32,
+}
+
+/// The evaluation rating of a course or a teacher
+#[derive(scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct Rating {
+    /// the number of revealed scores
+    pub count: u32,
+    /// the average score in hundredths
+    pub average: u32,
+}
+
+/// A term of the school, its deadlines apply to all of its courses
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct Term {
+    /// the start of the term, no course of the term starts earlier
+    pub start: Timestamp,
+    /// the end of the term, its courses are archived afterwards
+    pub end: Timestamp,
+    /// the time registration to the courses closes
+    pub add_deadline: Timestamp,
+    /// the time dropping the courses closes
+    pub drop_deadline: Timestamp,
+    /// the time swapping registrations of the courses closes
+    pub swap_deadline: Timestamp,
+}
+
+/// A set of elective courses the students earn a minimum of credits from
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct ElectiveBucket {
+    /// the courses of the bucket
+    pub courses: Vec<CourseId>,
+    /// the credits the students have to earn from the courses
+    pub min_credits: u32,
+}
+
+/// A degree program, the requirements of a diploma
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct Program {
+    /// the courses every student of the program has to pass
+    pub required_courses: Vec<CourseId>,
+    /// the elective requirements of the program
+    pub elective_buckets: Vec<ElectiveBucket>,
+}
+
+/// The credits a student earned from an elective bucket
+#[derive(scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct BucketProgress {
+    /// the credits of the passed courses of the bucket
+    pub earned_credits: u32,
+    /// the credits required from the bucket
+    pub min_credits: u32,
+}
+
+/// A student's progress through the requirements of their program
+///
+/// a course counts as completed once its passing grade is revealed
+#[derive(scale::Encode, scale::Decode, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct ProgramProgress {
+    /// the program of the student
+    pub program_id: ProgramId,
+    /// the required courses the student completed
+    pub completed_required: Vec<CourseId>,
+    /// the required courses the student has yet to complete
+    pub missing_required: Vec<CourseId>,
+    /// the progress in the elective buckets, in the order of the program
+    pub buckets: Vec<BucketProgress>,
+    /// true if every requirement of the program is satisfied
+    pub complete: bool,
+}
+
+/// The academic standing of a student, set by the owner on the grade and credit outcomes
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub enum AcademicStanding {
+    /// registers without restrictions
+    #[default]
+    Good,
+    /// registers up to `PROBATION_CREDIT_CAP` credits in progress
+    Probation,
+    /// can't register to courses
+    Suspended,
+}
+
+/// A student's application to a supervision slot
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct SupervisionApplication {
+    /// the applying student
+    pub student: AccountId,
+    /// the hash of the student's proposal, kept off-chain
+    pub proposal_hash: [u8; 32],
+}
+
+/// An exam sitting of a course
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct Exam {
+    /// the start of the exam
+    pub date: Timestamp,
+    /// the number of seats of the exam
+    pub capacity: u32,
+    /// the hash of the exam's location, kept off-chain
+    pub location_hash: [u8; 32],
+    /// the students sign up from this time
+    pub signup_opens: Timestamp,
+    /// the students sign up until this time, at the latest the exam date
+    pub signup_closes: Timestamp,
+    /// the exam period, a student sits at most one exam of a course per period
+    pub period: u32,
+    /// resits are open to the students who failed a first attempt
+    pub resit: bool,
+}
+
+/// A named group of school members
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct Cohort {
+    /// the name of the cohort
+    pub name: Vec<u8>,
+    /// the number of members of the cohort
+    pub member_count: u32,
+}
+
+/// The parameters of a course created in a batch
+#[derive(scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct CourseSpec {
+    /// the id of the course
+    pub course_id: CourseId,
+    /// the number of seats of the course
+    pub capacity: u32,
+    /// the start of the course, a future timestamp within the term
+    pub start_date: Timestamp,
+    /// the term of the course
+    pub term_id: TermId,
+    /// the room the course is held in, if any
+    pub room: Option<RoomId>,
+}
+
+/// The registration boundaries of a course that were marked with an event
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub enum RegistrationPhase {
+    /// the opening was announced when the course was created
+    #[default]
+    Announced,
+    /// registration opened
+    Opened,
+    /// registration closed
+    Closed,
+}
+
+/// The hashes of a member's off-chain profile
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct Profile {
+    /// the hash of the profile, e.g. the member's name
+    pub profile_hash: [u8; 32],
+    /// the hash of the member's contact details
+    pub contact_hash: [u8; 32],
+    /// the time the hashes were last set
+    pub updated_at: Timestamp,
+}
+
+/// The off-chain metadata of a course, e.g. its syllabus
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct CourseMetadata {
+    /// the IPFS or HTTPS URI of the metadata
+    pub uri: Vec<u8>,
+    /// the Keccak256 hash of the content behind the URI
+    pub content_hash: [u8; 32],
+}
+
+/// The kinds of registration changes counted per term
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum ActivityKind {
+    Added,
+    Dropped,
+    Swapped,
+}
+
+/// The ways a swap proposal ends, counted in the proposer's swap record
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum SwapOutcome {
+    Honored,
+    WithdrawnAfterCounters,
+    Expired,
+}
+
+/// A role of an account in the school
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Role {
+    Owner,
+    Teacher,
+    Member,
+}
+
+/// The caller of a message and the school roles it holds
+pub(crate) struct Ctx {
+    /// the caller of the message
+    pub(crate) caller: AccountId,
+    /// the caller owns the contract
+    pub(crate) owner: bool,
+    /// the caller is an admitted teacher
+    pub(crate) teacher: bool,
+    /// the caller is an admitted member, student or teacher
+    pub(crate) member: bool,
+}
+
+/// The role of the caller in a course
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum CourseRole {
+    Teacher,
+    Student,
+    Outsider,
+}
+
+/// The registration changes of a student in a term
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct TermActivity {
+    /// the registrations the student took
+    pub added: u32,
+    /// the registrations the student gave up
+    pub dropped: u32,
+    /// the registrations the student swapped for another
+    pub swapped: u32,
+}
+
+/// The time a student held a seat in a course
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct EnrollmentPeriod {
+    /// the time the student took the seat
+    pub from: Timestamp,
+    /// the time the student gave up the seat, `None` while it's held
+    pub until: Option<Timestamp>,
+}
+
+/// The state of a course on the registry's read interface
+#[derive(scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub enum CourseState {
+    /// created, registration opens with its term
+    Announced,
+    /// students can register to the course
+    RegistrationOpen,
+    /// registration closed, the course hasn't ended yet
+    RegistrationClosed,
+    /// the teacher ended the course
+    Ended,
+    /// the course's term is over
+    Archived,
+}
+
+/// An inconsistency between the registration structures, found by `check_invariants`
+#[derive(scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub enum InvariantViolation {
+    /// the course's enrolled count differs from the length of its roster
+    EnrolledCountMismatch { course_id: CourseId },
+    /// the student is on the roster or holds a token but isn't in the enrollment set
+    NotEnrolled { course_id: CourseId, student: AccountId },
+    /// the student's seat has no token, neither held nor escrowed in a proposal
+    MissingToken { course_id: CourseId, student: AccountId },
+    /// the student's token is held and escrowed in a proposal at the same time
+    TokenHeldAndEscrowed { course_id: CourseId, student: AccountId },
+    /// the course is listed more than once in the student's token index
+    DuplicateIndexEntry { course_id: CourseId, student: AccountId },
+}
+
+/// The hash of a course roster at a point in time
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct RosterSnapshot {
+    /// the Keccak256 hash of the encoded roster sorted by account
+    pub roster_hash: [u8; 32],
+    /// the time the snapshot was taken
+    pub taken_at: Timestamp,
+}
+
+/// A student admitted ahead of the activation of their membership
+#[derive(scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct PendingActivation {
+    /// the admitted student
+    pub account: AccountId,
+    /// the membership activates at this time
+    pub active_from: Timestamp,
+}
+
+/// The stable view of a course, with its derived data
+#[derive(scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct CourseView {
+    /// the `VIEW_VERSION` the view was built with
+    pub version: u32,
+    /// the id of the course
+    pub course_id: CourseId,
+    /// the teacher who created the course
+    pub teacher: AccountId,
+    /// the max number of students who can register
+    pub capacity: u32,
+    /// the number of registered students
+    pub enrolled_count: u32,
+    /// the free seats of the course
+    pub remaining_seats: u32,
+    /// the starting time of the course
+    pub start_date: Timestamp,
+    /// the term of the course, none for the courses created before terms
+    pub term_id: Option<TermId>,
+    /// the state of the course at the time of the query
+    pub state: CourseState,
+}
+
+/// The stable view of a registration token
+#[derive(scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct RegistrationView {
+    /// the `VIEW_VERSION` the view was built with
+    pub version: u32,
+    /// the id of the course
+    pub course_id: CourseId,
+    /// the owner of the token
+    pub owner: AccountId,
+}
+
+/// The stable view of a swap proposal
+#[derive(scale::Encode, scale::Decode, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct SwapView {
+    /// the `VIEW_VERSION` the view was built with
+    pub version: u32,
+    /// the id of the proposal
+    pub proposal_id: ProposalId,
+    /// the offered course
+    pub course_id: CourseId,
+    /// the student offering the course
+    pub proposer: AccountId,
+    /// the tokens offered in exchange
+    pub counter_offers: Vec<RegistrationView>,
+}
+
+/// A planned course that would fail to register, and why
+#[derive(scale::Encode, scale::Decode, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct PlanIssue {
+    /// the planned course
+    pub course_id: CourseId,
+    /// the error registering to the course would fail with
+    pub error: Error,
+}
+
+/// The public pool of a course open to non-members
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct PublicEnrollment {
+    /// the number of public seats, apart from the member seats
+    pub capacity: u32,
+    /// the fee of a public seat, paid with the registration
+    pub fee: Balance,
+}
+
+/// The hash of a registered student's answer to the course's question
+#[derive(scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct RegistrationAnswer {
+    /// the registered student
+    pub student: AccountId,
+    /// the hash of the off-chain answer
+    pub answer_hash: [u8; 32],
+}
+
+/// The outcome accepting a counter offer would have right now
+#[derive(scale::Encode, scale::Decode, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct SwapPreview {
+    /// the errors of the failing settlement checks, empty if the swap would settle
+    pub violations: Vec<Error>,
+}
+
+/// A token locked in a swap proposal
+#[derive(scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct EscrowedToken {
+    /// the proposal locking the token
+    pub proposal_id: ProposalId,
+    /// the course of the token
+    pub course_id: CourseId,
+}
+
+/// The seats a course can be swapped against
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub enum SwapPolicy {
+    /// seats of any course
+    #[default]
+    Open,
+    /// seats of courses taught by the same teacher
+    SameTeacher,
+    /// seats of courses in the same department
+    SameDepartment,
+}
+
+/// The order of the registrations that arrive when registration opens
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub enum TieBreakPolicy {
+    /// the order the registrations are executed in
+    #[default]
+    FirstComeFirstServed,
+    /// a deterministic shuffle seeded by the course and the set of requests
+    ///
+    /// not a lottery: anyone can compute the order of a set of requests
+    /// before the window closes and see how another request would change
+    /// it; it only keeps the order from depending on the order the
+    /// requests were executed in
+    Randomized,
+    /// the students with more graded courses first
+    Seniority,
+}
+
+/// A counter offer to a swap proposal, named by its course and its owner
+#[derive(scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct CounterOfferRef {
+    /// the proposal the counter offer was made to
+    pub proposal_id: ProposalId,
+    /// the course of the offered registration
+    pub course_id: CourseId,
+    /// the owner of the offered registration
+    pub owner: AccountId,
+}
+
+/// How the swap proposals of an account ended
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct SwapReputation {
+    /// the proposals settled with a counter offer
+    pub honored: u32,
+    /// the proposals cancelled after counter offers arrived
+    pub withdrawn_after_counters: u32,
+    /// the proposals expired when swaps closed
+    pub expired: u32,
+}
+
+/// An opaque position in a list view, passed back to get the following page
+pub type Cursor = [u8; 32];
+
+/// A page of a list view
+#[derive(scale::Encode, scale::Decode, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct Page<T> {
+    /// the items of the page, in the order of the list
+    pub items: Vec<T>,
+    /// the cursor of the following page, `None` on the last page
+    pub next_cursor: Option<Cursor>,
+    /// the number of items in the whole list
+    pub total: u32,
+}
+
+/// The sections of the storage a state export walks
+#[derive(scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub enum StateSection {
+    /// the owner, the versions, the school config and the feature flags
+    Config,
+    /// the terms by id
+    Terms,
+    /// the degree programs by id
+    Programs,
+    /// the cohorts by id
+    Cohorts,
+    /// the open swap proposals by id
+    Proposals,
+}
+
+/// A chunk of a state export
+#[derive(scale::Encode, scale::Decode, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct StateChunk {
+    /// the exported section
+    pub section: StateSection,
+    /// the SCALE-encoded entries of the chunk
+    pub data: Vec<u8>,
+    /// the cursor of the following chunk, `None` on the last chunk
+    pub next_cursor: Option<u64>,
+    /// the hash of the state the chunk was taken from
+    pub manifest_hash: [u8; 32],
+}
+
+/// A member imported from a legacy system
+#[derive(scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct MemberImport {
+    /// the account of the member
+    pub account: AccountId,
+    /// true if the member is a teacher
+    pub as_teacher: bool,
+}
+
+/// A course imported from a legacy system
+#[derive(scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct CourseImport {
+    /// the id of the course
+    pub course_id: CourseId,
+    /// the teacher of the course
+    pub teacher: AccountId,
+    /// the number of seats of the course
+    pub capacity: u32,
+    /// the start of the course within the term, it may have passed
+    pub start_date: Timestamp,
+    /// the term of the course
+    pub term_id: TermId,
+}
+
+/// A registration imported from a legacy system
+#[derive(scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct RegistrationImport {
+    /// the course of the registration
+    pub course_id: CourseId,
+    /// the registered student
+    pub student: AccountId,
+}
+
+/// An action of the teacher of a course the owner can take in their stead
+#[derive(scale::Encode, scale::Decode, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub enum TeacherAction {
+    /// ends the course
+    EndCourse,
+    /// sets the credits of the course
+    SetCredits { credits: u32 },
+    /// publishes the metadata of the course
+    SetUri { uri: Vec<u8>, content_hash: [u8; 32] },
+    /// accepts an applicant of the supervision slot
+    AcceptSupervisionApplicant { student: AccountId },
+}
+
+/// The interest in a course the seats couldn't meet
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout))]
+pub struct DemandMetrics {
+    /// the students who recorded being turned away by the full course
+    pub capacity_rejections: u32,
+    /// the times the course was added to a student's plan
+    pub plan_additions: u32,
+}
+
+/// A course of a student's schedule, as committed to by `get_schedule_commitment`
+#[derive(scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+pub struct ScheduleEntry {
+    /// the id of the course
+    pub course_id: CourseId,
+    /// the start of the course
+    pub start_date: Timestamp,
+}
+
+/// The registration boundaries of a course moved ahead of its term's schedule
+#[derive(PackedLayout, SpreadLayout, scale::Encode, scale::Decode, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "std", derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout))]
+pub struct RegistrationTransitions {
+    /// the time registration was opened by hand
+    pub opened_at: Option<Timestamp>,
+    /// the time registration was closed by hand
+    pub closed_at: Option<Timestamp>,
+}
@@ -0,0 +1,144 @@
+//! Dry runs of the mutating messages, for wallets to explain a failure before it costs fees
+//!
+//! every dry run runs the checks of its message, shared with the message
+//! itself, as the caller, so it fails with the error the call would fail with
+
+use ink_lang::codegen::StaticEnv;
+use ink_prelude::vec::Vec;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn validate_register_inner(&self, course_ids: Vec<CourseId>) -> Result<(), Error> {
+        self.check_register_to_courses(Self::env().caller(), &course_ids)
+    }
+
+    pub(crate) fn validate_propose_swap_inner(&self, course_id: CourseId) -> Result<(), Error> {
+        self.check_propose_swap(Self::env().caller(), course_id)
+    }
+
+    pub(crate) fn validate_counter_swap_proposal_inner(&self,
+                                                       proposal_id: ProposalId,
+                                                       counter_course_id: CourseId) -> Result<(), Error> {
+        self.check_counter_swap_proposal(Self::env().caller(), proposal_id, counter_course_id)
+            .map(|_| ())
+    }
+
+    pub(crate) fn validate_accept_counter_offer_inner(&self,
+                                                      proposal_id: ProposalId,
+                                                      accepted_course_id: CourseId,
+                                                      accepted_owner: AccountId) -> Result<(), Error> {
+        self.check_accept_counter_offer(Self::env().caller(), proposal_id, accepted_course_id, accepted_owner)
+            .map(|_| ())
+    }
+
+    /// fails like `create_course` as the caller, then lists every rule the spec breaks
+    pub(crate) fn validate_course_spec_inner(&self, spec: CourseSpec) -> Result<Vec<Error>, Error> {
+        self.require_role(Role::Teacher)?;
+        Ok(self.course_spec_violations(&spec, false))
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::clock::TestClock;
+    use crate::course_reg::CourseReg;
+    use crate::errors::Error;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+    use ink_prelude::vec;
+
+    /// Dry runs agree with the calls test
+    #[ink::test]
+    fn dry_runs() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student1 = AccountId::from([0x1; 32]);
+        let student2 = AccountId::from([0x2; 32]);
+        let course_id1 = CourseId(hash_keccak_256("test_course1".as_bytes()));
+        let course_id2 = CourseId(hash_keccak_256("test_course2".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_student(student1, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student2, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id1, 1, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id2, 1, start_time, term_id, None, None), Ok(()));
+
+        set_next_caller(student1);
+        assert_eq!(course_reg.validate_propose_swap(course_id1), Err(Error::NoSwappableRegistrations));
+        assert_eq!(course_reg.validate_register(vec![course_id1, course_id1]), Err(Error::AlreadyRegistered));
+        assert_eq!(course_reg.validate_register(vec![course_id1]), Ok(()));
+        assert_eq!(course_reg.register_to_course(course_id1, None).map(|_| ()), Ok(()));
+        set_next_caller(student2);
+        assert_eq!(course_reg.validate_register(vec![course_id1]), Err(Error::CourseCapacityFull { capacity: 1 }));
+        assert_eq!(course_reg.register_to_course(course_id1, None), Err(Error::CourseCapacityFull { capacity: 1 }));
+        assert_eq!(course_reg.register_to_course(course_id2, None).map(|_| ()), Ok(()));
+
+        set_next_caller(student1);
+        assert_eq!(course_reg.validate_propose_swap(course_id1), Ok(()));
+        let proposal_id = course_reg.propose_swap(course_id1, None).unwrap();
+        set_next_caller(student2);
+        assert_eq!(course_reg.validate_counter_swap_proposal(proposal_id, course_id1),
+                   Err(Error::RegistrationNotOwned));
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id1, None), Err(Error::RegistrationNotOwned));
+        assert_eq!(course_reg.validate_counter_swap_proposal(proposal_id, course_id2), Ok(()));
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id2, None), Ok(()));
+        assert_eq!(course_reg.validate_accept_counter_offer(proposal_id, course_id2, student2),
+                   Err(Error::NotProposalOwner));
+        set_next_caller(student1);
+        assert_eq!(course_reg.validate_accept_counter_offer(proposal_id, course_id2, student2), Ok(()));
+
+        // the dry run fails like the call once swaps closed
+        TestClock::set(start_time);
+        let closed = course_reg.validate_accept_counter_offer(proposal_id, course_id2, student2);
+        assert!(closed.is_err());
+        assert_eq!(course_reg.accept_counter_offer(proposal_id, course_id2, student2, None).map(|_| ()), closed);
+    }
+
+    /// Every broken rule of a course spec test
+    #[ink::test]
+    fn course_spec_rules() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student = AccountId::from([0x1; 32]);
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_student(student, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        let valid = CourseSpec { course_id: CourseId([0x1; 32]), capacity: 10, start_date: start_time, term_id, room: None };
+        assert_eq!(course_reg.validate_course_spec(valid), Ok(vec![]));
+
+        let broken = CourseSpec { course_id, capacity: 0, start_date: start_time, term_id: term_id + 1, room: None };
+        assert_eq!(course_reg.validate_course_spec(broken), Ok(vec![
+            Error::CourseAlreadyExists,
+            Error::NonexistentTerm,
+            Error::InvalidCapacity,
+        ]));
+        // the call fails with the first broken rule
+        assert_eq!(course_reg.create_course(broken.course_id,
+                                            broken.capacity,
+                                            broken.start_date,
+                                            broken.term_id,
+                                            broken.room,
+                                            None),
+                   Err(Error::CourseAlreadyExists));
+        let past = CourseSpec { start_date: 1, capacity: MAX_COURSE_CAPACITY + 1, ..valid };
+        assert_eq!(course_reg.validate_course_spec(past), Ok(vec![
+            Error::InvalidTimestamp,
+            Error::CourseOutsideTerm,
+            Error::CourseCapacityTooLarge,
+        ]));
+        let roomless = CourseSpec { room: Some(1), ..valid };
+        assert_eq!(course_reg.validate_course_spec(roomless), Ok(vec![Error::NonexistentRoom]));
+        set_next_caller(student);
+        assert_eq!(course_reg.validate_course_spec(valid), Err(Error::InsufficientPermissions));
+    }
+}
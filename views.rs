@@ -0,0 +1,172 @@
+//! The stable shapes the public views return, decoupled from the storage types
+
+use ink_prelude::vec::Vec;
+
+use crate::course_reg::CourseReg;
+use crate::errors::Error;
+use crate::types::*;
+
+impl CourseReg {
+    pub(crate) fn course_view(&self, course: Course) -> CourseView {
+        CourseView {
+            version: VIEW_VERSION,
+            course_id: course.course_id,
+            teacher: course.teacher,
+            capacity: course.capacity,
+            enrolled_count: course.enrolled_count,
+            remaining_seats: course.capacity.saturating_sub(course.enrolled_count),
+            start_date: course.start_date,
+            term_id: self.course_terms.get(course.course_id),
+            state: self.course_state_of(&course),
+        }
+    }
+
+    pub(crate) fn registration_view(registration: CourseRegistration) -> RegistrationView {
+        RegistrationView {
+            version: VIEW_VERSION,
+            course_id: registration.course_id,
+            owner: registration.owner,
+        }
+    }
+
+    pub(crate) fn swap_view(proposal: CourseRegistrationSwapProposal) -> SwapView {
+        SwapView {
+            version: VIEW_VERSION,
+            proposal_id: proposal.proposal_id,
+            course_id: proposal.offer.course_id,
+            proposer: proposal.offer.owner,
+            counter_offers: proposal.counter_offers.into_iter().map(Self::registration_view).collect::<Vec<_>>(),
+        }
+    }
+
+    /// returns at most `count` items of the list following the cursor of the previous page
+    ///
+    /// the cursor holds the position of the previous page's last item and the
+    /// first 28 bytes of its key, so the page resumes after that item wherever
+    /// the list moved it, or at its old position once it left the list
+    pub(crate) fn page<T>(list: Vec<T>,
+                          cursor: Option<Cursor>,
+                          count: u32,
+                          key: impl Fn(&T) -> [u8; 32]) -> Result<Page<T>, Error> {
+        let total = u32::try_from(list.len()).map_err(|_| Error::ArithmeticOverflow)?;
+        let from = match cursor {
+            Some(cursor) => {
+                let mut position = [0u8; 4];
+                position.copy_from_slice(&cursor[..4]);
+                match list.iter().position(|item| key(item)[..28] == cursor[4..]) {
+                    Some(last) => last + 1,
+                    None => (u32::from_le_bytes(position) as usize).min(list.len()),
+                }
+            }
+            None => 0,
+        };
+        let items: Vec<T> = list.into_iter().skip(from).take(count as usize).collect();
+        let end = from + items.len();
+        let next_cursor = match items.last() {
+            Some(last) if end < total as usize => {
+                let mut cursor = Cursor::default();
+                cursor[..4].copy_from_slice(&((end - 1) as u32).to_le_bytes());
+                cursor[4..].copy_from_slice(&key(last)[..28]);
+                Some(cursor)
+            }
+            _ => None,
+        };
+        Ok(Page { items, next_cursor, total })
+    }
+
+    /// returns the key a page cursor recognizes a numeric id by
+    pub(crate) fn id_key(id: u64) -> [u8; 32] {
+        let mut key = [0u8; 32];
+        key[..8].copy_from_slice(&id.to_le_bytes());
+        key
+    }
+}
+
+/// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::course_reg::CourseReg;
+    use crate::test_utils::*;
+    use crate::types::*;
+
+    use ink_lang as ink;
+
+    /// Course, registration and swap views test
+    #[ink::test]
+    fn structured_views() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let student1 = AccountId::from([0x1; 32]);
+        let student2 = AccountId::from([0x2; 32]);
+        let course_id1 = CourseId(hash_keccak_256("test_course1".as_bytes()));
+        let course_id2 = CourseId(hash_keccak_256("test_course2".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.admit_as_student(student1, None), Ok(()));
+        assert_eq!(course_reg.admit_as_student(student2, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id1, 10, start_time, term_id, None, None), Ok(()));
+        assert_eq!(course_reg.create_course(course_id2, 10, start_time, term_id, None, None), Ok(()));
+        set_next_caller(student1);
+        assert_eq!(course_reg.register_to_course(course_id1, None).map(|_| ()), Ok(()));
+        let course = CourseView {
+            version: VIEW_VERSION,
+            course_id: course_id1,
+            teacher: owner,
+            capacity: 10,
+            enrolled_count: 1,
+            remaining_seats: 9,
+            start_date: start_time,
+            term_id: Some(term_id),
+            state: CourseState::RegistrationOpen,
+        };
+        assert_eq!(course_reg.get_course_info(course_id1), Ok(course));
+        let registration = RegistrationView { version: VIEW_VERSION, course_id: course_id1, owner: student1 };
+        assert_eq!(course_reg.get_own_registrations(), Ok(ink_prelude::vec![registration]));
+
+        let proposal_id = course_reg.propose_swap(course_id1, None).unwrap();
+        set_next_caller(student2);
+        assert_eq!(course_reg.register_to_course(course_id2, None).map(|_| ()), Ok(()));
+        assert_eq!(course_reg.counter_swap_proposal(proposal_id, course_id2, None), Ok(()));
+        let swap = SwapView {
+            version: VIEW_VERSION,
+            proposal_id,
+            course_id: course_id1,
+            proposer: student1,
+            counter_offers: ink_prelude::vec![RegistrationView { version: VIEW_VERSION, course_id: course_id2, owner: student2 }],
+        };
+        assert_eq!(course_reg.get_swap_proposal(proposal_id), Ok(swap.clone()));
+        assert_eq!(course_reg.get_proposed_swaps(course_id1, None, 10).map(|page| page.items), Ok(ink_prelude::vec![swap]));
+    }
+
+    /// Proposal pages resuming after a cancelled proposal test
+    #[ink::test]
+    fn proposal_pages() {
+        let owner = AccountId::from([0x0;32]);
+        set_next_caller(owner);
+        let mut course_reg = CourseReg::new(owner);
+        let students: ink_prelude::vec::Vec<AccountId> = (1..=3u8).map(|i| AccountId::from([i; 32])).collect();
+        let course_id = CourseId(hash_keccak_256("test_course".as_bytes()));
+        let start_time = get_start_time();
+        let term_id = create_term(&mut course_reg, start_time);
+        assert_eq!(course_reg.create_course(course_id, 10, start_time, term_id, None, None), Ok(()));
+        let mut proposal_ids = ink_prelude::vec::Vec::new();
+        for student in &students {
+            set_next_caller(owner);
+            assert_eq!(course_reg.admit_as_student(*student, None), Ok(()));
+            set_next_caller(*student);
+            assert_eq!(course_reg.register_to_course(course_id, None).map(|_| ()), Ok(()));
+            proposal_ids.push(course_reg.propose_swap(course_id, None).unwrap());
+        }
+        let page = course_reg.get_proposed_swaps(course_id, None, 2).unwrap();
+        let ids: ink_prelude::vec::Vec<ProposalId> = page.items.iter().map(|swap| swap.proposal_id).collect();
+        assert_eq!((ids.as_slice(), page.total), (&proposal_ids[..2], 3));
+
+        // the last proposal seen is cancelled, the next page resumes at the one after it
+        set_next_caller(students[1]);
+        assert_eq!(course_reg.cancel_swap_proposal(proposal_ids[1], None), Ok(()));
+        let page = course_reg.get_proposed_swaps(course_id, page.next_cursor, 2).unwrap();
+        assert_eq!(page.items.iter().map(|swap| swap.proposal_id).collect::<ink_prelude::vec::Vec<_>>(), proposal_ids[2..]);
+        assert_eq!(page.next_cursor, None);
+    }
+}